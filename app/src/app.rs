@@ -1,30 +1,54 @@
 use scout_core::{ Shared, SearchResult };
 
 use super::window::{ Window, WindowCallbacks };
+use super::query_history::QueryHistory;
+use super::preferences::Preferences;
 
 pub struct AppCallbacks {
-	pub on_search: Box<dyn FnMut(&str) -> Vec<Box<dyn SearchResult>>>
+	pub on_search: Box<dyn FnMut(&str) -> Vec<Box<dyn SearchResult>>>,
+	pub on_peek: Box<dyn FnMut(&str) -> Option<String>>,
+	pub on_refresh: Box<dyn FnMut()>,
+	pub on_reload: Box<dyn FnMut()>,
+	pub on_cycle_sort: Box<dyn FnMut()>
 }
 
 impl Default for AppCallbacks {
 	fn default() -> Self {
 		AppCallbacks {
-			on_search: Box::new(|_| vec![])
+			on_search: Box::new(|_| vec![]),
+			on_peek: Box::new(|_| None),
+			on_refresh: Box::new(|| ()),
+			on_reload: Box::new(|| ()),
+			on_cycle_sort: Box::new(|| ())
 		}
 	}
 }
 
 pub struct App {
+	daemon: bool,
+	query: String,
 	stylesheets: Vec<&'static str>,
 	window: Option<Shared<Window>>,
+	preferences: Shared<Preferences>,
+	query_history: Shared<QueryHistory>,
 	callbacks: AppCallbacks
 }
 
 impl App {
-	pub fn new() -> Shared<Self> {
+	/**
+	 * Creates the app. In daemon mode the window starts hidden,
+	 * and is toggled by activating the running instance again.
+	 * The window reads the preferences specified, which are reloaded in place.
+	 */
+
+	pub fn new(daemon: bool, preferences: Shared<Preferences>) -> Shared<Self> {
 		Shared::new(App {
+			daemon,
+			query: String::new(),
 			window: None,
+			preferences,
 			stylesheets: vec![],
+			query_history: QueryHistory::new(None),
 			callbacks: AppCallbacks::default()
 		})
 	}
@@ -38,23 +62,61 @@ impl App {
 	}
 
 	pub fn init(&mut self, gtk: &gtk::Application, bind: &Shared<Self>) {
-		self.window = Some(Window::new(gtk, &self.stylesheets));
+		self.window = Some(Window::new(gtk, &self.stylesheets, self.daemon, self.preferences.clone()));
 
 		let bind_a = bind.clone();
 		let bind_b = bind.clone();
+		let bind_c = bind.clone();
+		let bind_d = bind.clone();
+		let bind_e = bind.clone();
+		let bind_f = bind.clone();
+		let bind_g = bind.clone();
 		self.window.as_ref().unwrap().borrow_mut().bind(WindowCallbacks {
 			on_input: Box::new(move |v| bind_a.borrow_mut().on_input(v)),
-			on_submit: Box::new(move || bind_b.borrow_mut().on_submit())
+			on_submit: Box::new(move || bind_b.borrow_mut().on_submit()),
+			on_refresh: Box::new(move || bind_c.borrow_mut().on_refresh()),
+			on_reload: Box::new(move || bind_g.borrow_mut().on_reload()),
+			on_cycle_sort: Box::new(move || bind_f.borrow_mut().on_cycle_sort()),
+			on_history_prev: Box::new(move |at_start| bind_d.borrow_mut().on_history_prev(at_start)),
+			on_history_next: Box::new(move || bind_e.borrow_mut().on_history_next())
 		});
 		self.stylesheets.clear();
 	}
 
 	fn on_input(&mut self, value: &str) {
+		self.query = value.to_owned();
 		let results = (self.callbacks.on_search)(value);
+		let answer = (self.callbacks.on_peek)(value);
 		self.window.as_ref().unwrap().borrow_mut().set_results(results);
+		self.window.as_ref().unwrap().borrow().set_peek(answer.as_deref());
 	}
 
 	fn on_submit(&mut self) {
 		println!("submit");
+		if let Err(err) = self.query_history.borrow_mut().record(&self.query) {
+			println!("[WARN] Failed to save query history: {}", err);
+		}
+	}
+
+	fn on_history_prev(&mut self, at_start: bool) -> Option<String> {
+		let mut query_history = self.query_history.borrow_mut();
+		if !at_start && !query_history.is_recalling() { return None; }
+		query_history.prev().map(|query| query.to_owned())
+	}
+
+	fn on_history_next(&mut self) -> Option<String> {
+		self.query_history.borrow_mut().next().map(|query| query.to_owned())
+	}
+
+	fn on_refresh(&mut self) {
+		(self.callbacks.on_refresh)();
+	}
+
+	fn on_reload(&mut self) {
+		(self.callbacks.on_reload)();
+	}
+
+	fn on_cycle_sort(&mut self) {
+		(self.callbacks.on_cycle_sort)();
 	}
 }
@@ -0,0 +1,228 @@
+/*!
+ * Built-in results for managing Scout itself, like `restart scout`, which refresh and reload it in-process.
+ */
+
+use std::rc::Rc;
+use gtk::prelude::*;
+
+use scout_core::SearchResult;
+
+/** The score of control results, which are only shown when a query names their command. */
+const CONTROL_SCORE: usize = 100;
+
+/** The fewest characters of a command that need to be typed before it is suggested. */
+const MIN_COMMAND_LENGTH: usize = 4;
+
+
+/**
+ * What a control result does when it is activated.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlAction {
+	/** Discards the discovery cache and refreshes every plugin's results, then reloads all preferences, without closing the window. */
+	Restart,
+	/** Asks every plugin to discover its results again. */
+	Refresh,
+	/** Reads the app's and every plugin's preferences again. */
+	Reload
+}
+
+/** The commands control results are found by, and the actions they dispatch. */
+const COMMANDS: &[(&str, ControlAction)] = &[
+	("restart scout", ControlAction::Restart),
+	("reload scout", ControlAction::Restart),
+	("refresh scout", ControlAction::Refresh),
+	("reload preferences", ControlAction::Reload)
+];
+
+impl ControlAction {
+
+	/**
+	 * Returns the actions whose commands start with the query, ignoring case and repeated whitespace.
+	 * Queries shorter than `MIN_COMMAND_LENGTH` don't match, so that short searches aren't crowded.
+	 */
+
+	pub fn parse(query: &str) -> Vec<ControlAction> {
+		let query = query.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase();
+		if query.chars().count() < MIN_COMMAND_LENGTH { return vec![]; }
+
+		let mut actions = vec![];
+		for (command, action) in COMMANDS.iter() {
+			if command.starts_with(&query) && !actions.contains(action) { actions.push(*action); }
+		}
+		actions
+	}
+
+	pub fn get_title(&self) -> &'static str {
+		match self {
+			ControlAction::Restart => "Restart Scout",
+			ControlAction::Refresh => "Refresh Scout",
+			ControlAction::Reload => "Reload Preferences"
+		}
+	}
+
+	pub fn get_description(&self) -> &'static str {
+		match self {
+			ControlAction::Restart => "Discovers every plugin's results again and reloads all preferences, without closing the window.",
+			ControlAction::Refresh => "Discovers every plugin's results again.",
+			ControlAction::Reload => "Reads the app's and every plugin's preferences again."
+		}
+	}
+}
+
+
+/**
+ * A result that runs a control action when activated, through the dispatch function it was created with.
+ */
+
+pub struct ScoutControlResult {
+	action: ControlAction,
+	dispatch: Rc<dyn Fn(ControlAction)>,
+
+	widget: gtk::Box,
+	top_button: gtk::Button
+}
+
+impl ScoutControlResult {
+
+	/**
+	 * Creates a new control result, with a corresponding result widget.
+	 */
+
+	pub fn new(action: ControlAction, dispatch: Rc<dyn Fn(ControlAction)>) -> Self {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Control");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		top_button.add(&widget_top);
+
+		let icon = gtk::Image::from_icon_name(Some("view-refresh"), gtk::IconSize::Dnd);
+		icon.set_size_request(32, 32);
+		icon.set_pixel_size(32);
+		widget_top.pack_start(&icon, false, false, 4);
+
+		let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget_top.pack_start(&description_box, true, true, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>SCOUT</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_ellipsize(pango::EllipsizeMode::End);
+		category_label.set_use_markup(true);
+		category_label.set_xalign(0.0);
+		description_box.pack_start(&category_label, false, false, 1);
+
+		let label = gtk::Label::new(Some(action.get_title()));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		label.set_xalign(0.0);
+		description_box.pack_start(&label, false, false, 1);
+
+		top_button.set_tooltip_text(Some(action.get_description()));
+		let dispatch_clone = dispatch.clone();
+		top_button.connect_clicked(move |_| dispatch_clone(action));
+
+		ScoutControlResult { action, dispatch, widget, top_button }
+	}
+}
+
+impl SearchResult for ScoutControlResult {
+	fn get_score(&self) -> usize {
+		CONTROL_SCORE
+	}
+
+	fn get_id(&self) -> String {
+		format!("scout-control-{:?}", self.action).to_lowercase()
+	}
+
+	fn get_title(&self) -> String {
+		self.action.get_title().to_owned()
+	}
+
+	fn get_subtitle(&self) -> String {
+		"SCOUT".to_owned()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some("view-refresh".to_owned())
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		(self.dispatch)(self.action);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Control");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = gtk::Image::from_icon_name(Some("view-refresh"), gtk::IconSize::Dialog);
+		icon.set_pixel_size(96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>SCOUT</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(self.action.get_title()));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let description = gtk::Label::new(Some(self.action.get_description()));
+		description.get_style_context().add_class("Description");
+		description.set_line_wrap_mode(pango::WrapMode::WordChar);
+		description.set_justify(gtk::Justification::Center);
+		description.set_max_width_chars(36);
+		description.set_line_wrap(true);
+		widget.pack_start(&description, false, false, 0);
+
+		widget.upcast()
+	}
+}
+
+
+/**
+ * Returns a control result for each action whose command the query starts.
+ */
+
+pub fn get_results(query: &str, dispatch: &Rc<dyn Fn(ControlAction)>) -> Vec<Box<dyn SearchResult>> {
+	ControlAction::parse(query).into_iter()
+		.map(|action| Box::new(ScoutControlResult::new(action, dispatch.clone())) as Box<dyn SearchResult>)
+		.collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn commands_dispatch_their_actions() {
+		assert_eq!(ControlAction::parse("restart scout"), vec![ ControlAction::Restart ]);
+		assert_eq!(ControlAction::parse("RELOAD   Scout "), vec![ ControlAction::Restart ]);
+		assert_eq!(ControlAction::parse("refr"), vec![ ControlAction::Refresh ]);
+		assert_eq!(ControlAction::parse("reload pref"), vec![ ControlAction::Reload ]);
+	}
+
+	#[test]
+	fn partial_commands_suggest_every_match_once() {
+		assert_eq!(ControlAction::parse("reload"), vec![ ControlAction::Restart, ControlAction::Reload ]);
+		assert_eq!(ControlAction::parse("re"), vec![]);
+		assert_eq!(ControlAction::parse("scout"), vec![]);
+		assert_eq!(ControlAction::parse("restart firefox"), vec![]);
+	}
+}
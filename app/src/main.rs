@@ -2,35 +2,161 @@ use std::alloc::System;
 #[global_allocator]
 static ALLOCATOR: System = System;
 
+use std::rc::Rc;
 use gio::prelude::*;
+use scout_core::Shared;
 
 mod app;
 mod window;
 mod plugin;
+mod server;
 mod preferences;
+mod query_history;
+mod state;
+mod prewarm;
+mod control;
+#[cfg(test)]
+mod mock;
 
 use plugin::PluginParser;
 use app::{ App, AppCallbacks };
+use control::ControlAction;
+use preferences::Preferences;
+
+/**
+ * Applies the app preferences that change how the plugin parser searches.
+ */
+
+fn apply_preferences(plugins: &mut PluginParser, preferences: &Preferences) {
+	plugins.set_sort_mode(preferences.sort_mode);
+	plugins.set_min_query_length(preferences.min_query_length);
+	plugins.set_always_show(preferences.always_show.clone());
+	plugins.set_category_order(preferences.category_order.clone());
+}
+
+/**
+ * Loads the plugins enabled in the app preferences, which search their sources as they are loaded.
+ */
+
+fn load_plugins(plugins: &mut PluginParser, app: &Shared<App>, preferences: &Preferences) {
+	plugins.load(app, "target/debug/libscout_plugin_application.so").expect("Invocation Failed");
+	plugins.load(app, "target/debug/libscout_plugin_directory.so").expect("Invocation Failed");
+	plugins.load(app, "target/debug/libscout_plugin_emoji.so").expect("Invocation Failed");
+	if preferences.bookmarks {
+		plugins.load(app, "target/debug/libscout_plugin_bookmarks.so").expect("Invocation Failed");
+	}
+	if preferences.recent_documents {
+		plugins.load(app, "target/debug/libscout_plugin_recent.so").expect("Invocation Failed");
+	}
+	if preferences.services {
+		plugins.load(app, "target/debug/libscout_plugin_services.so").expect("Invocation Failed");
+	}
+	if preferences.toggles {
+		plugins.load(app, "target/debug/libscout_plugin_toggles.so").expect("Invocation Failed");
+	}
+}
 
 fn main() {
-	let app = App::new();
+	// In daemon mode Scout stays resident with its window hidden, and launching it again toggles the window.
+	let daemon = std::env::args().any(|arg| arg == "--daemon");
+	let get_arg = |name: &str| std::env::args().skip_while(|arg| arg != name).nth(1);
+	let explain = get_arg("--explain");
+
+	if std::env::args().any(|arg| arg == "--prewarm") {
+		match prewarm::prewarm() {
+			Ok(found) => println!("Cached {} desktop entries.", found),
+			Err(err) => println!("[WARN] Failed to write the discovery cache: {}", err)
+		}
+		return;
+	}
+
+	// Exports or imports the configuration and state before anything reads them, then exits.
+	for flag in [ "--export", "--import", "--validate" ].iter() {
+		if std::env::args().any(|arg| &arg == flag) && get_arg(flag).filter(|path| !path.starts_with("--")).is_none() {
+			println!("[WARN] {} needs the path of a file, like 'scout {} <file>'.", flag, flag);
+			std::process::exit(1);
+		}
+	}
+
+	// Lints a desktop file the way the application plugin reads it, then exits.
+	if let Some(path) = get_arg("--validate") {
+		let issues = scout_plugin_application::validate::validate_entry(std::path::Path::new(&path));
+		if issues.is_empty() { println!("'{}' has no problems.", path); }
+		for issue in issues.iter() { println!("[WARN] {}", issue); }
+		std::process::exit(if issues.is_empty() { 0 } else { 1 });
+	}
+
+	if let Some(path) = get_arg("--export") {
+		if let Err(err) = state::export(std::path::Path::new(&path)) { println!("[WARN] Failed to export to '{}': {}", path, err); }
+		return;
+	}
+	if let Some(path) = get_arg("--import") {
+		let replace = std::env::args().any(|arg| arg == "--replace");
+		if let Err(err) = state::import(std::path::Path::new(&path), replace) { println!("[WARN] Failed to import '{}': {}", path, err); }
+		return;
+	}
+
+	let preferences = Preferences::new(None);
+	let app = App::new(daemon, preferences.clone());
 	let plugins = PluginParser::new();
+	apply_preferences(&mut plugins.borrow_mut(), &preferences.borrow());
 
-	let plugins_clone = plugins.clone();
+	// Control results like `restart scout` refresh and reload everything in-process, keeping the window open.
+	// Refreshing discards the discovery cache, and the preferences are replaced in place so the window sees them.
+	let plugins_control = plugins.clone();
+	let preferences_control = preferences.clone();
+	let dispatch: Rc<dyn Fn(ControlAction)> = Rc::new(move |action| {
+		if action != ControlAction::Reload { plugins_control.borrow().refresh(); }
+		if action != ControlAction::Refresh {
+			plugins_control.borrow().reload();
+			let reloaded = Preferences::new(None).borrow().clone();
+			apply_preferences(&mut plugins_control.borrow_mut(), &reloaded);
+			*preferences_control.borrow_mut() = reloaded;
+		}
+	});
+
+	let plugins_search = plugins.clone();
+	let plugins_peek = plugins.clone();
+	let plugins_refresh = plugins.clone();
+	let plugins_reload = plugins.clone();
+	let plugins_sort = plugins.clone();
 	app.borrow_mut().bind(AppCallbacks {
-		on_search: Box::new(move |query| plugins_clone.borrow_mut().get_results(query))
+		on_search: Box::new(move |query| {
+			let mut results = control::get_results(query, &dispatch);
+			results.extend(plugins_search.borrow_mut().get_results(query));
+			results
+		}),
+		on_peek: Box::new(move |query| plugins_peek.borrow().peek(query)),
+		on_refresh: Box::new(move || plugins_refresh.borrow().refresh()),
+		on_reload: Box::new(move || plugins_reload.borrow().reload()),
+		on_cycle_sort: Box::new(move || plugins_sort.borrow_mut().cycle_sort_mode())
 	});
 
-	plugins.borrow_mut().load(&app, "target/debug/libscout_plugin_application.so").expect("Invocation Failed");
-	plugins.borrow_mut().load(&app, "target/debug/libscout_plugin_directory.so").expect("Invocation Failed");
+	// Prints how each result of a query was scored, instead of starting the window.
+	if let Some(query) = explain {
+		load_plugins(&mut plugins.borrow_mut(), &app, &preferences.borrow());
+		for result in plugins.borrow().get_results(&query) {
+			println!("{} ({}): {}", result.get_title(), result.get_score(),
+				result.explain_score().unwrap_or_else(|| "No explanation.".to_owned()));
+		}
+		return;
+	}
 
 	let gtk = gtk::Application::new(Some("com.aurailus.scout"), Default::default())
 		.expect("Failed to initialize GTK application.");
+	if daemon { gtk.hold(); }
 
+	// Startup only runs in the primary instance, so only it loads the plugins and owns the socket.
 	let app_clone = app.clone();
+	let plugins_server = plugins.clone();
 	gtk.connect_startup(move |gtk| {
+		load_plugins(&mut plugins_server.borrow_mut(), &app_clone, &preferences.borrow());
 		let bind = app_clone.clone();
 		app_clone.borrow_mut().init(gtk, &bind);
+
+		if preferences.borrow().socket_server {
+			if let Err(err) = server::start(plugins_server.clone(), None) { println!("[WARN] Failed to start socket server: {}", err); }
+		}
 	});
 	gtk.run(&[]);
 }
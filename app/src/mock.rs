@@ -0,0 +1,37 @@
+/*!
+ * A search result for tests, with the fields that the app sorts and activates results by.
+ */
+
+use scout_core::{ SearchResult, Shared };
+
+pub struct MockResult {
+	pub id: &'static str,
+	pub title: &'static str,
+	pub score: usize,
+	pub frequency: f64,
+	pub last_used: Option<u64>,
+	pub category: Option<&'static str>,
+	pub activated: Shared<bool>
+}
+
+impl MockResult {
+
+	/**
+	 * Creates a result with the title and score specified, identified by its title, that has never been used.
+	 */
+
+	pub fn new(title: &'static str, score: usize) -> MockResult {
+		MockResult { id: title, title, score, frequency: 0.0, last_used: None, category: None, activated: Shared::new(false) }
+	}
+}
+
+impl SearchResult for MockResult {
+	fn get_score(&self) -> usize { self.score }
+	fn get_id(&self) -> String { self.id.to_owned() }
+	fn get_title(&self) -> String { self.title.to_owned() }
+	fn get_subtitle(&self) -> String { String::new() }
+	fn get_frequency(&self) -> f64 { self.frequency }
+	fn get_last_used(&self) -> Option<u64> { self.last_used }
+	fn get_category(&self) -> Option<String> { self.category.map(str::to_owned) }
+	fn activate(&self) { self.activated.replace(true); }
+}
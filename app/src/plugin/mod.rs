@@ -5,3 +5,6 @@ pub use plugin_bindings::*;
 
 mod plugin_parser;
 pub use plugin_parser::*;
+
+mod sort_mode;
+pub use sort_mode::*;
@@ -1,20 +1,68 @@
 /**! Parses plugins and stores them, providing methods to retrieve data from them. */
 
 use std::rc::Rc;
-use scout_core::{ Shared, SearchResult };
+use scout_core::{ Shared, SearchResult, InvocationError };
 
 use crate::app::App;
+use super::SortMode;
 use super::plugin_bindings::PluginBindings;
 
 #[derive(Default)]
 pub struct PluginParser {
 	plugins: Vec<Shared<Box<dyn scout_core::Plugin>>>,
-	_bindings: Vec<Shared<Box<dyn scout_core::PluginBindings>>>
+	plugin_names: Vec<String>,
+	_bindings: Vec<Shared<Box<dyn scout_core::PluginBindings>>>,
+	sort_mode: SortMode,
+	min_query_length: usize,
+	always_show: Vec<String>,
+	category_order: Vec<String>
 }
 
 impl PluginParser {
 	pub fn new() -> Shared<PluginParser> { Shared::new(PluginParser::default()) }
 
+	pub fn set_sort_mode(&mut self, sort_mode: SortMode) {
+		self.sort_mode = sort_mode;
+	}
+
+
+	/**
+	 * Sets the number of characters a query needs before plugins are searched.
+	 */
+
+	pub fn set_min_query_length(&mut self, min_query_length: usize) {
+		self.min_query_length = min_query_length;
+	}
+
+
+	/**
+	 * Sets the names of plugins whose results are always shown, below the others,
+	 * even when they score too low compared to the best result.
+	 */
+
+	pub fn set_always_show(&mut self, always_show: Vec<String>) {
+		self.always_show = always_show;
+	}
+
+
+	/**
+	 * Sets the categories listed first when sorting by category, in order.
+	 */
+
+	pub fn set_category_order(&mut self, category_order: Vec<String>) {
+		self.category_order = category_order;
+	}
+
+
+	/**
+	 * Switches to the next sort mode.
+	 */
+
+	pub fn cycle_sort_mode(&mut self) {
+		self.sort_mode = self.sort_mode.next();
+		println!("Sorting results by {:?}.", self.sort_mode);
+	}
+
 	/**
 	 * Attempts to load a plugin at the specified path.
 	 * Returns a result indicating success.
@@ -42,6 +90,7 @@ impl PluginParser {
 				Ok((identifier, plugin)) => {
 					println!("Registered plugin '{}'.", identifier);
 					self.plugins.push(plugin);
+					self.plugin_names.push(identifier.to_owned());
 					drop(bindings_borrow);
 					self._bindings.push(bindings);
 					Ok(())
@@ -54,24 +103,214 @@ impl PluginParser {
 		}
 	}
 
+	/**
+	 * Asks every plugin to discover its results again.
+	 * Plugins that do not support refreshing are skipped.
+	 */
+
+	pub fn refresh(&self) {
+		for plugin in self.plugins.iter() {
+			match plugin.borrow_mut().refresh() {
+				Ok(()) | Err(InvocationError::DoesNotProvide(_)) => (),
+				Err(err) => println!("[WARN] Failed to refresh plugin: {:?}", err)
+			}
+		}
+	}
+
+	/**
+	 * Asks every plugin to read its preferences again.
+	 * Plugins without preferences are skipped.
+	 */
+
+	pub fn reload(&self) {
+		for plugin in self.plugins.iter() {
+			match plugin.borrow_mut().reload() {
+				Ok(()) | Err(InvocationError::DoesNotProvide(_)) => (),
+				Err(err) => println!("[WARN] Failed to reload plugin preferences, keeping the previous ones: {:?}", err)
+			}
+		}
+	}
+
+	/**
+	 * Returns the answer of the first plugin that has one for the query.
+	 */
+
+	pub fn peek(&self, query: &str) -> Option<String> {
+		if query.trim().is_empty() { return None; }
+
+		self.plugins.iter().find_map(|plugin| match plugin.borrow().peek(query) {
+			Ok(answer) => answer,
+			Err(InvocationError::DoesNotProvide(_)) => None,
+			Err(err) => { println!("[WARN] Failed to peek plugin: {:?}", err); None }
+		})
+	}
+
+	/**
+	 * Splits a query like `!app firefox` into the index of the plugin it names and the rest of the query.
+	 * A bang names the first plugin whose name starts with it, and unknown bangs return `None`.
+	 */
+
+	fn parse_bang<'a>(&self, query: &'a str) -> Option<(usize, &'a str)> {
+		let query = query.trim_start().strip_prefix('!')?;
+		let (bang, rest) = query.split_at(query.find(char::is_whitespace).unwrap_or(query.len()));
+		if bang.is_empty() { return None; }
+
+		let ind = self.plugin_names.iter().position(|name| name.starts_with(bang))?;
+		Some((ind, rest.trim_start()))
+	}
+
+
 	/**
 	 * Calls a plugin by name,
 	 * Returns a result with data or an error.
 	 */
 
 	pub fn get_results(&self, query: &str) -> Vec<Box<dyn SearchResult>> {
+		self.get_named_results(query).into_iter().map(|(_, result)| result).collect()
+	}
+
+
+	/**
+	 * Returns the results of a query like `get_results`, along with the name of the plugin each result came from.
+	 */
+
+	pub fn get_named_results(&self, query: &str) -> Vec<(String, Box<dyn SearchResult>)> {
 		let mut results = vec![];
+		let mut always_shown = vec![];
 
-		for plugin in self.plugins.iter() {
-			let res = scout_core::or_continue!(plugin.borrow().get_results(query));
-			results.extend(res);
+		// Short queries show the default view, which is currently empty.
+		if query.trim().chars().count() < self.min_query_length { return results; }
+
+		let bang = self.parse_bang(query);
+		let query = bang.map_or(query, |(_, rest)| rest);
+		let empty_query = query.trim().is_empty();
+
+		for (ind, (plugin, name)) in self.plugins.iter().zip(self.plugin_names.iter()).enumerate() {
+			if bang.is_some_and(|(bang_ind, _)| bang_ind != ind) { continue; }
+			let res = scout_core::or_continue!(plugin.borrow().get_results(query)).into_iter().map(|result| (name.clone(), result));
+			if self.always_show.contains(name) { always_shown.extend(res); }
+			else { results.extend(res); }
+		}
+
+		results.retain(|(_, result)| result.get_score() > 0);
+		results.sort_by(|(_, result_a), (_, result_b)| result_b.get_score().cmp(&result_a.get_score()));
+		// Empty queries, like a bare `!recent`, list results by a score like recency, which says nothing about how well they match.
+		let min = if results.len() >= 1 && !empty_query { (results[0].1.get_score() as f64 * 0.75) as usize } else { 0 };
+		results.retain(|(_, result)| result.get_score() >= min);
+		if self.sort_mode != SortMode::Score {
+			results.sort_by(|(_, a), (_, b)| self.sort_mode.compare(a.as_ref(), b.as_ref(), &self.category_order, empty_query));
 		}
 
-		results.retain(|result| result.get_score() > 0);
-		results.sort_by(|result_a, result_b| result_b.get_score().partial_cmp(&result_a.get_score()).unwrap());
-		let min = if results.len() >= 1 { (results[0].get_score() as f64 * 0.75) as usize } else { 0 };
-		results.retain(|result| result.get_score() >= min);
+		always_shown.retain(|(_, result)| result.get_score() > 0);
+		always_shown.sort_by(|(_, result_a), (_, result_b)| result_b.get_score().cmp(&result_a.get_score()));
+		results.extend(always_shown);
 
 		results
 	}
 }
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::MockResult;
+
+	/** A plugin that returns the results whose titles start with the query. */
+	struct MockPlugin(Vec<(&'static str, usize)>);
+
+	impl scout_core::Plugin for MockPlugin {
+		fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+			Ok(self.0.iter()
+				.filter(|(title, _)| title.to_lowercase().starts_with(query))
+				.map(|&(title, score)| Box::new(MockResult::new(title, score)) as Box<dyn SearchResult>)
+				.collect())
+		}
+	}
+
+	/** A plugin without results, that answers every query with the same text. */
+	struct PeekPlugin(&'static str);
+
+	impl scout_core::Plugin for PeekPlugin {
+		fn get_results(&self, _query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+			Ok(vec![])
+		}
+
+		fn peek(&self, _query: &str) -> scout_core::Result<Option<String>> {
+			Ok(Some(self.0.to_owned()))
+		}
+	}
+
+	fn get_parser(plugins: Vec<(&str, MockPlugin)>) -> PluginParser {
+		let mut parser = PluginParser::default();
+		for (name, plugin) in plugins {
+			parser.plugins.push(Shared::new(Box::new(plugin) as Box<dyn scout_core::Plugin>));
+			parser.plugin_names.push(name.to_owned());
+		}
+		parser
+	}
+
+	fn get_titles(parser: &PluginParser, query: &str) -> Vec<String> {
+		parser.get_results(query).iter().map(|result| result.get_title()).collect()
+	}
+
+	#[test]
+	fn short_queries_are_not_searched() {
+		let mut parser = get_parser(vec![ ("application", MockPlugin(vec![ ("Firefox", 10), ("Files", 10) ])) ]);
+		parser.set_min_query_length(3);
+
+		assert!(get_titles(&parser, "fi").is_empty());
+		assert!(get_titles(&parser, "  fi ").is_empty());
+		assert_eq!(get_titles(&parser, "fir"), vec![ "Firefox" ]);
+
+		parser.set_min_query_length(1);
+		assert_eq!(get_titles(&parser, "fi").len(), 2);
+	}
+
+	#[test]
+	fn always_shown_plugins_are_listed_last_below_the_cutoff() {
+		let mut parser = get_parser(vec![
+			("web", MockPlugin(vec![ ("Find on the web", 10) ])),
+			("application", MockPlugin(vec![ ("Firefox", 100), ("Files", 90) ]))
+		]);
+		assert_eq!(get_titles(&parser, "fi"), vec![ "Firefox", "Files" ]);
+
+		parser.set_always_show(vec![ "web".to_owned() ]);
+		assert_eq!(get_titles(&parser, "fi"), vec![ "Firefox", "Files", "Find on the web" ]);
+		assert_eq!(get_titles(&parser, "xyz"), Vec::<String>::new());
+	}
+
+	#[test]
+	fn bangs_name_the_first_plugin_they_start() {
+		let parser = get_parser(vec![ ("calculator", MockPlugin(vec![])), ("application", MockPlugin(vec![])), ("web", MockPlugin(vec![])) ]);
+		assert_eq!(parser.parse_bang("!app firefox"), Some((1, "firefox")));
+		assert_eq!(parser.parse_bang("  !calc   2+2"), Some((0, "2+2")));
+		assert_eq!(parser.parse_bang("!web"), Some((2, "")));
+		assert_eq!(parser.parse_bang("!maps paris"), None);
+		assert_eq!(parser.parse_bang("! firefox"), None);
+		assert_eq!(parser.parse_bang("firefox !app"), None);
+	}
+
+	#[test]
+	fn bangs_search_only_the_plugin_they_name() {
+		let parser = get_parser(vec![
+			("application", MockPlugin(vec![ ("Rust Analyzer", 50) ])),
+			("web", MockPlugin(vec![ ("Rust lang", 50) ]))
+		]);
+		assert_eq!(get_titles(&parser, "rust"), vec![ "Rust Analyzer", "Rust lang" ]);
+		assert_eq!(get_titles(&parser, "!web rust"), vec![ "Rust lang" ]);
+		assert_eq!(get_titles(&parser, "!app rust"), vec![ "Rust Analyzer" ]);
+		assert_eq!(get_titles(&parser, "!maps rust"), Vec::<String>::new());
+	}
+
+
+	#[test]
+	fn peeking_returns_the_first_answer() {
+		let mut parser = get_parser(vec![ ("apps", MockPlugin(vec![ ("Firefox", 10) ])) ]);
+		assert_eq!(parser.peek("2+2"), None);
+
+		parser.plugins.push(Shared::new(Box::new(PeekPlugin("= 4")) as Box<dyn scout_core::Plugin>));
+		parser.plugins.push(Shared::new(Box::new(PeekPlugin("= 5")) as Box<dyn scout_core::Plugin>));
+		assert_eq!(parser.peek("2+2"), Some("= 4".to_owned()));
+		assert_eq!(parser.peek(" "), None);
+	}
+}
@@ -0,0 +1,146 @@
+/*!
+ * Orderings that can be applied to search results.
+ */
+
+use serde::{ Serialize, Deserialize };
+
+use scout_core::SearchResult;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+	Score,
+	Alphabetical,
+	Frequency,
+	Recency,
+	Category
+}
+
+impl Default for SortMode {
+	fn default() -> Self { SortMode::Score }
+}
+
+impl SortMode {
+
+	/**
+	 * Returns the mode after this one, wrapping around to `Score`.
+	 */
+
+	pub fn next(self) -> Self {
+		match self {
+			SortMode::Score => SortMode::Alphabetical,
+			SortMode::Alphabetical => SortMode::Frequency,
+			SortMode::Frequency => SortMode::Recency,
+			SortMode::Recency => SortMode::Category,
+			SortMode::Category => SortMode::Score
+		}
+	}
+
+
+	/**
+	 * Returns the key a category is ordered by, from the result's `get_category_key` rather than its displayed category.
+	 * Categories in `order` come first, in that order, ignoring case, followed by the rest alphabetically,
+	 * and then results without a category. Categories are compared by their uppercase characters, so the order doesn't depend on the locale.
+	 */
+
+	pub fn get_category_key(category: Option<&str>, order: &[String]) -> (usize, String) {
+		match category {
+			None => (usize::MAX, String::new()),
+			Some(category) => (order.iter().position(|ordered| ordered.eq_ignore_ascii_case(category)).unwrap_or(order.len()),
+				category.to_uppercase())
+		}
+	}
+
+
+	/**
+	 * Returns true if the mode only breaks ties between equally scored results of a query,
+	 * since ordering a search by name or by how often or when it was used would bury the best matches.
+	 */
+
+	pub fn breaks_ties(self) -> bool {
+		self == SortMode::Alphabetical || self == SortMode::Frequency || self == SortMode::Recency
+	}
+
+
+	/**
+	 * Compares two results by this mode, breaking ties by score. `Alphabetical`, `Frequency`, and `Recency` only order the
+	 * results of a query that have the same score, and only order every result when the query is empty.
+	 * Sorting by category groups results in the order given by `category_order`, see `get_category_key`.
+	 */
+
+	pub fn compare(self, a: &dyn SearchResult, b: &dyn SearchResult, category_order: &[String], empty_query: bool) -> std::cmp::Ordering {
+		let score_order = if self.breaks_ties() && !empty_query { b.get_score().cmp(&a.get_score()) } else { std::cmp::Ordering::Equal };
+		let order = match self {
+			SortMode::Score => std::cmp::Ordering::Equal,
+			SortMode::Alphabetical => a.get_title().to_lowercase().cmp(&b.get_title().to_lowercase()),
+			SortMode::Frequency => b.get_frequency().partial_cmp(&a.get_frequency()).unwrap_or(std::cmp::Ordering::Equal),
+			SortMode::Recency => b.get_last_used().cmp(&a.get_last_used()),
+			SortMode::Category => SortMode::get_category_key(a.get_category_key().as_deref(), category_order)
+				.cmp(&SortMode::get_category_key(b.get_category_key().as_deref(), category_order))
+		};
+		score_order.then(order).then_with(|| b.get_score().cmp(&a.get_score()))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::MockResult;
+
+	fn get_results() -> Vec<MockResult> {
+		vec![
+			MockResult { frequency: 1.0, last_used: Some(100), category: Some("Game"), ..MockResult::new("Zed", 30) },
+			MockResult { frequency: 5.0, last_used: Some(300), category: Some("Development"), ..MockResult::new("Atom", 30) },
+			MockResult::new("Mousepad", 50),
+			MockResult { frequency: 2.0, last_used: Some(200), category: Some("Graphics"), ..MockResult::new("Blender", 10) }
+		]
+	}
+
+	fn sort(mode: SortMode, category_order: &[String], empty_query: bool) -> Vec<&'static str> {
+		let mut results = get_results();
+		results.sort_by(|a, b| mode.compare(a, b, category_order, empty_query));
+		results.iter().map(|result| result.title).collect()
+	}
+
+	#[test]
+	fn score_orders_by_score() {
+		assert_eq!(sort(SortMode::Score, &[], false), vec![ "Mousepad", "Zed", "Atom", "Blender" ]);
+	}
+
+	#[test]
+	fn alphabetical_frequency_and_recency_only_break_ties_of_a_query() {
+		assert_eq!(sort(SortMode::Alphabetical, &[], false), vec![ "Mousepad", "Atom", "Zed", "Blender" ]);
+		assert_eq!(sort(SortMode::Alphabetical, &[], true), vec![ "Atom", "Blender", "Mousepad", "Zed" ]);
+		assert_eq!(sort(SortMode::Recency, &[], false), vec![ "Mousepad", "Atom", "Zed", "Blender" ]);
+		assert_eq!(sort(SortMode::Recency, &[], true), vec![ "Atom", "Blender", "Zed", "Mousepad" ]);
+	}
+
+	#[test]
+	fn frequency_orders_by_usage() {
+		assert_eq!(sort(SortMode::Frequency, &[], false), vec![ "Mousepad", "Atom", "Zed", "Blender" ]);
+		assert_eq!(sort(SortMode::Frequency, &[], true), vec![ "Atom", "Blender", "Zed", "Mousepad" ]);
+	}
+
+	#[test]
+	fn modes_cycle_back_to_score() {
+		let mut mode = SortMode::Score;
+		for _ in 0..5 { mode = mode.next(); }
+		assert_eq!(mode, SortMode::Score);
+	}
+
+
+	#[test]
+	fn categories_come_in_the_configured_order() {
+		assert_eq!(sort(SortMode::Category, &[ "graphics".to_owned() ], false), vec![ "Blender", "Atom", "Zed", "Mousepad" ]);
+		assert_eq!(sort(SortMode::Category, &[ "Game".to_owned(), "Development".to_owned() ], false), vec![ "Zed", "Atom", "Blender", "Mousepad" ]);
+		assert_eq!(sort(SortMode::Category, &[], false), vec![ "Atom", "Zed", "Blender", "Mousepad" ]);
+	}
+
+	#[test]
+	fn category_keys_are_locale_independent() {
+		let order = vec![ "Graphics".to_owned() ];
+		assert_eq!(SortMode::get_category_key(Some("graphics"), &order), (0, "GRAPHICS".to_owned()));
+		assert_eq!(SortMode::get_category_key(Some("Game"), &order), (1, "GAME".to_owned()));
+		assert_eq!(SortMode::get_category_key(None, &order), (usize::MAX, String::new()));
+	}
+}
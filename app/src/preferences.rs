@@ -3,6 +3,9 @@ use std::io::{ Read, Write, Seek, SeekFrom };
 
 use scout_core::Shared;
 
+use crate::plugin::SortMode;
+use crate::window::Feedback;
+
 fn default_opacity() -> u32 { 90 }
 
 fn default_hide_on_unfocus() -> bool { true }
@@ -11,6 +14,32 @@ fn default_always_on_top() -> bool { true }
 
 fn default_developer() -> bool { false }
 
+fn default_wrap_selection() -> bool { true }
+
+fn default_socket_server() -> bool { false }
+
+fn default_min_query_length() -> usize { 1 }
+
+fn default_bookmarks() -> bool { false }
+
+fn default_recent_documents() -> bool { false }
+
+fn default_services() -> bool { false }
+
+fn default_toggles() -> bool { false }
+
+fn default_close_on_activate() -> bool { true }
+
+fn default_instant_enter() -> bool { false }
+
+fn default_instant_enter_threshold() -> usize { 100 }
+
+fn default_feedback_sound() -> String { "button-pressed".to_owned() }
+
+fn default_hover_preview() -> bool { false }
+
+fn default_preview_delay_ms() -> u32 { 500 }
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Preferences {
 
@@ -27,7 +56,58 @@ pub struct Preferences {
 	pub always_on_top: bool,
 
 	#[serde(default = "default_developer")]
-	pub developer: bool
+	pub developer: bool,
+
+	#[serde(default = "default_wrap_selection")]
+	pub wrap_selection: bool,
+
+	#[serde(default)]
+	pub sort_mode: SortMode,
+
+	#[serde(default = "default_socket_server")]
+	pub socket_server: bool,
+
+	#[serde(default = "default_min_query_length")]
+	pub min_query_length: usize,
+
+	#[serde(default = "default_bookmarks")]
+	pub bookmarks: bool,
+
+	#[serde(default = "default_recent_documents")]
+	pub recent_documents: bool,
+
+	#[serde(default = "default_services")]
+	pub services: bool,
+
+	#[serde(default = "default_toggles")]
+	pub toggles: bool,
+
+	#[serde(default = "default_close_on_activate")]
+	pub close_on_activate: bool,
+
+	#[serde(default)]
+	pub always_show: Vec<String>,
+
+	#[serde(default)]
+	pub category_order: Vec<String>,
+
+	#[serde(default = "default_instant_enter")]
+	pub instant_enter: bool,
+
+	#[serde(default = "default_instant_enter_threshold")]
+	pub instant_enter_threshold: usize,
+
+	#[serde(default)]
+	pub feedback: Feedback,
+
+	#[serde(default = "default_feedback_sound")]
+	pub feedback_sound: String,
+
+	#[serde(default = "default_hover_preview")]
+	pub hover_preview: bool,
+
+	#[serde(default = "default_preview_delay_ms")]
+	pub preview_delay_ms: u32
 }
 
 impl Preferences {
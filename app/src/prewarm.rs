@@ -0,0 +1,13 @@
+/*!
+ * Headless warming of the application plugin's discovery cache, for running from a user service before Scout is opened.
+ * This runs the plugin's own discovery with its preferences, so the next start reads the cache instead of searching every directory.
+ */
+
+/**
+ * Searches the application directories and writes the discovery cache, without opening a window or needing a display.
+ * Returns the number of desktop entries found.
+ */
+
+pub fn prewarm() -> Result<usize, scout_plugin_application::ScoutError> {
+	scout_plugin_application::prewarm()
+}
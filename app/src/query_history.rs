@@ -0,0 +1,141 @@
+/*!
+ * Stores previously submitted queries, so they can be recalled with the arrow keys.
+ */
+
+use std::io::{ Read, Write };
+
+use scout_core::Shared;
+
+/** The maximum number of queries kept in the history file. */
+static MAX_QUERIES: usize = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct QueryHistory {
+	path: std::path::PathBuf,
+	queries: Vec<String>,
+	position: Option<usize>
+}
+
+impl QueryHistory {
+
+	/**
+	 * Loads the query history from the file specified, or from `$XDG_DATA_HOME/scout/query_history`.
+	 */
+
+	pub fn new(path: Option<&std::path::Path>) -> Shared<Self> {
+		let path = path
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| dirs::data_dir()
+				.map(|dir| dir.join("scout/query_history"))
+				.unwrap_or_else(|| std::path::Path::new(&[ "/home/", &whoami::username(), "/.local/share/scout/query_history" ].join(""))
+				.to_owned()));
+
+		let mut contents = String::new();
+		if let Ok(mut file) = std::fs::File::open(&path) { drop(file.read_to_string(&mut contents)); }
+		let queries = contents.lines().filter(|line| !line.is_empty()).map(|line| line.to_owned()).collect();
+
+		Shared::new(QueryHistory { path, queries, position: None })
+	}
+
+
+	/**
+	 * Adds a query to the end of the history, unless it is empty or the same as the last query,
+	 * and resets the recall position.
+	 */
+
+	pub fn record(&mut self, query: &str) -> std::io::Result<()> {
+		self.position = None;
+		if query.trim().is_empty() || self.queries.last().is_some_and(|last| last == query) { return Ok(()); }
+
+		self.queries.push(query.to_owned());
+		if self.queries.len() > MAX_QUERIES { self.queries.drain(..self.queries.len() - MAX_QUERIES); }
+		self.save()
+	}
+
+
+	/**
+	 * Steps back to the previous query, stopping at the oldest one.
+	 */
+
+	pub fn prev(&mut self) -> Option<&str> {
+		if self.queries.is_empty() { return None; }
+
+		let position = self.position.map_or(self.queries.len() - 1, |position| position.saturating_sub(1));
+		self.position = Some(position);
+		Some(&self.queries[position])
+	}
+
+
+	/**
+	 * Steps forward to the next query. Stepping past the newest query
+	 * stops recalling, and returns an empty query.
+	 */
+
+	pub fn next(&mut self) -> Option<&str> {
+		let position = self.position?;
+
+		if position + 1 >= self.queries.len() {
+			self.position = None;
+			return Some("");
+		}
+
+		self.position = Some(position + 1);
+		Some(&self.queries[position + 1])
+	}
+
+
+	/**
+	 * Returns true if a past query is currently being recalled.
+	 */
+
+	pub fn is_recalling(&self) -> bool {
+		self.position.is_some()
+	}
+
+	fn save(&self) -> std::io::Result<()> {
+		if let Some(parent) = self.path.parent() { std::fs::create_dir_all(parent)?; }
+		let mut file = std::fs::File::create(&self.path)?;
+		file.write_all(self.queries.iter().map(|query| [ query.as_str(), "\n" ].join("")).collect::<String>().as_bytes())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_history() -> QueryHistory {
+		QueryHistory { queries: vec![ "firefox".to_owned(), "files".to_owned(), "terminal".to_owned() ], ..Default::default() }
+	}
+
+	#[test]
+	fn prev_steps_back_and_stops_at_the_oldest() {
+		let mut history = get_history();
+		assert!(!history.is_recalling());
+		assert_eq!(history.prev(), Some("terminal"));
+		assert_eq!(history.prev(), Some("files"));
+		assert_eq!(history.prev(), Some("firefox"));
+		assert_eq!(history.prev(), Some("firefox"));
+		assert!(history.is_recalling());
+	}
+
+	#[test]
+	fn next_steps_forward_and_stops_recalling_past_the_newest() {
+		let mut history = get_history();
+		assert_eq!(history.next(), None);
+
+		history.prev();
+		history.prev();
+		assert_eq!(history.next(), Some("terminal"));
+		assert_eq!(history.next(), Some(""));
+		assert!(!history.is_recalling());
+		assert_eq!(history.next(), None);
+	}
+
+	#[test]
+	fn empty_history_recalls_nothing() {
+		let mut history = QueryHistory::default();
+		assert_eq!(history.prev(), None);
+		assert_eq!(history.next(), None);
+	}
+}
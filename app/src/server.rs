@@ -0,0 +1,230 @@
+/*!
+ * An optional Unix socket server, allowing external frontends to search and activate results.
+ * Each connection sends newline-delimited JSON requests, and receives one JSON response per request.
+ *
+ * `{"query":"fire"}` responds with `{"results":[{"id":"application:firefox","name":"Firefox","icon":"firefox","subtitle":"WEB BROWSER"}]}`.
+ * `{"activate":"application:firefox"}` activates a result from the last query, responding with `{"activated":true}`.
+ * Ids are prefixed with the name of the plugin the result came from, so that plugins can't shadow each other's results.
+ * Malformed requests respond with `{"error":"..."}`.
+ */
+
+use std::io::{ BufRead, BufReader, Write };
+use std::os::unix::fs::{ DirBuilderExt, MetadataExt, PermissionsExt };
+use std::os::unix::net::{ UnixListener, UnixStream };
+
+use serde::{ Serialize, Deserialize };
+
+use scout_core::{ Shared, SearchResult };
+
+use crate::plugin::PluginParser;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Request {
+	Query { query: String },
+	Activate { activate: String }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ResultInfo {
+	pub id: String,
+	pub name: String,
+	pub icon: Option<String>,
+	pub subtitle: String
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Response {
+	Results { results: Vec<ResultInfo> },
+	Activated { activated: bool },
+	Error { error: String }
+}
+
+/**
+ * Handles requests on the main thread, where the plugins live.
+ * Keeps the results of the last query, with the plugin each came from, so they can be activated by id.
+ */
+
+pub struct Dispatcher {
+	plugins: Shared<PluginParser>,
+	results: Vec<(String, Box<dyn SearchResult>)>
+}
+
+impl Dispatcher {
+	pub fn new(plugins: Shared<PluginParser>) -> Self {
+		Dispatcher { plugins, results: vec![] }
+	}
+
+	/**
+	 * Returns the id of a result on the socket, which is prefixed with its plugin's name, like `application:firefox`.
+	 */
+
+	pub fn get_id(plugin: &str, result: &dyn SearchResult) -> String {
+		[ plugin, ":", &result.get_id() ].join("")
+	}
+
+	pub fn dispatch(&mut self, request: Request) -> Response {
+		match request {
+			Request::Query { query } => {
+				self.results = self.plugins.borrow().get_named_results(&query);
+				Response::Results { results: self.results.iter().map(|(plugin, result)| ResultInfo {
+					id: Dispatcher::get_id(plugin, result.as_ref()),
+					name: result.get_title(),
+					icon: result.get_icon_name(),
+					subtitle: result.get_subtitle()
+				}).collect() }
+			},
+			Request::Activate { activate } => {
+				let result = self.results.iter().find(|(plugin, result)| Dispatcher::get_id(plugin, result.as_ref()) == activate);
+				if let Some((_, result)) = result { result.activate(); }
+				Response::Activated { activated: result.is_some() }
+			}
+		}
+	}
+}
+
+
+/**
+ * Parses a single request line, and returns the serialized response.
+ */
+
+fn respond(line: &str, send: &glib::Sender<(Request, std::sync::mpsc::Sender<Response>)>) -> String {
+	let response = match serde_json::from_str::<Request>(line) {
+		Ok(request) => {
+			let (reply, receive) = std::sync::mpsc::channel();
+			send.send((request, reply)).ok()
+				.and_then(|_| receive.recv().ok())
+				.unwrap_or_else(|| Response::Error { error: "Scout is shutting down.".to_owned() })
+		},
+		Err(err) => Response::Error { error: err.to_string() }
+	};
+
+	serde_json::to_string(&response).unwrap()
+}
+
+fn handle_connection(stream: UnixStream, send: glib::Sender<(Request, std::sync::mpsc::Sender<Response>)>) -> std::io::Result<()> {
+	let mut writer = stream.try_clone()?;
+	for line in BufReader::new(stream).lines() {
+		let line = line?;
+		if line.trim().is_empty() { continue; }
+		writer.write_all([ respond(&line, &send), "\n".to_owned() ].join("").as_bytes())?;
+	}
+	Ok(())
+}
+
+
+/**
+ * Returns the directory the socket is created in, which is `$XDG_RUNTIME_DIR`, or a directory only the user can access
+ * in the temporary directory, like `/tmp/scout-1000`. The temporary directory is refused if another user owns it.
+ */
+
+fn get_socket_dir() -> std::io::Result<std::path::PathBuf> {
+	if let Some(dir) = dirs::runtime_dir() { return Ok(dir); }
+
+	// The process's own entry in /proc is owned by its user.
+	let uid = std::fs::metadata("/proc/self")?.uid();
+	let dir = std::env::temp_dir().join(format!("scout-{}", uid));
+	if let Err(err) = std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+		if err.kind() != std::io::ErrorKind::AlreadyExists { return Err(err); }
+	}
+
+	let metadata = std::fs::symlink_metadata(&dir)?;
+	if !metadata.is_dir() || metadata.uid() != uid {
+		return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("'{}' belongs to another user", dir.display())));
+	}
+	std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+	Ok(dir)
+}
+
+
+/**
+ * Starts listening on the socket specified, or `scout.sock` in the directory from `get_socket_dir`.
+ * The socket can only be used by the user. Connections are read on background threads, and requests are dispatched on the main loop.
+ */
+
+pub fn start(plugins: Shared<PluginParser>, path: Option<&std::path::Path>) -> std::io::Result<()> {
+	let path = match path {
+		Some(path) => path.to_owned(),
+		None => get_socket_dir()?.join("scout.sock")
+	};
+
+	// A previous instance may have left its socket behind.
+	if path.exists() { std::fs::remove_file(&path)?; }
+	let listener = UnixListener::bind(&path)?;
+	std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+	println!("Listening on '{}'.", path.display());
+
+	let (send, receive) = glib::MainContext::channel::<(Request, std::sync::mpsc::Sender<Response>)>(glib::PRIORITY_DEFAULT);
+	let mut dispatcher = Dispatcher::new(plugins);
+	receive.attach(None, move |(request, reply)| {
+		drop(reply.send(dispatcher.dispatch(request)));
+		glib::Continue(true)
+	});
+
+	std::thread::spawn(move || {
+		for stream in listener.incoming() {
+			let stream = scout_core::or_continue!(stream);
+			let send = send.clone();
+			std::thread::spawn(move || {
+				if let Err(err) = handle_connection(stream, send) { println!("[WARN] Socket connection failed: {}", err); }
+			});
+		}
+	});
+
+	Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::MockResult;
+
+	#[test]
+	fn requests_are_parsed_from_json() {
+		assert_eq!(serde_json::from_str::<Request>(r#"{"query":"fire"}"#).unwrap(), Request::Query { query: "fire".to_owned() });
+		assert_eq!(serde_json::from_str::<Request>(r#"{"activate":"application:firefox"}"#).unwrap(),
+			Request::Activate { activate: "application:firefox".to_owned() });
+		assert!(serde_json::from_str::<Request>(r#"{"search":"fire"}"#).is_err());
+	}
+
+	#[test]
+	fn responses_are_serialized_untagged() {
+		let results = Response::Results { results: vec![ ResultInfo {
+			id: "application:firefox".to_owned(),
+			name: "Firefox".to_owned(),
+			icon: Some("firefox".to_owned()),
+			subtitle: "WEB BROWSER".to_owned()
+		} ] };
+
+		assert_eq!(serde_json::to_string(&results).unwrap(),
+			r#"{"results":[{"id":"application:firefox","name":"Firefox","icon":"firefox","subtitle":"WEB BROWSER"}]}"#);
+		assert_eq!(serde_json::to_string(&Response::Activated { activated: false }).unwrap(), r#"{"activated":false}"#);
+		assert_eq!(serde_json::to_string(&Response::Error { error: "Oops".to_owned() }).unwrap(), r#"{"error":"Oops"}"#);
+	}
+
+	#[test]
+	fn results_are_activated_by_their_prefixed_id() {
+		let (firefox, files) = (Shared::new(false), Shared::new(false));
+		let mut dispatcher = Dispatcher::new(PluginParser::new());
+		dispatcher.results = vec![
+			("application".to_owned(), Box::new(MockResult { activated: firefox.clone(), ..MockResult::new("firefox", 0) })),
+			("directory".to_owned(), Box::new(MockResult { activated: files.clone(), ..MockResult::new("firefox", 0) }))
+		];
+
+		assert_eq!(dispatcher.dispatch(Request::Activate { activate: "firefox".to_owned() }), Response::Activated { activated: false });
+		assert_eq!(dispatcher.dispatch(Request::Activate { activate: "directory:firefox".to_owned() }), Response::Activated { activated: true });
+		assert!(!*firefox.borrow());
+		assert!(*files.borrow());
+	}
+
+	#[test]
+	fn querying_replaces_the_activatable_results() {
+		let mut dispatcher = Dispatcher::new(PluginParser::new());
+		dispatcher.results = vec![ ("application".to_owned(), Box::new(MockResult::new("firefox", 0))) ];
+
+		assert_eq!(dispatcher.dispatch(Request::Query { query: "fire".to_owned() }), Response::Results { results: vec![] });
+		assert_eq!(dispatcher.dispatch(Request::Activate { activate: "application:firefox".to_owned() }), Response::Activated { activated: false });
+	}
+}
@@ -0,0 +1,246 @@
+/*!
+ * Exporting and importing Scout's configuration and state as a single JSON archive, for moving between machines.
+ * The archive maps each file's path, relative to `$XDG_CONFIG_HOME` or `$XDG_DATA_HOME`, to its contents.
+ * The popularity file can be anywhere, so it is stored by its own key and written to wherever the imported configuration sets it.
+ */
+
+use std::collections::BTreeMap;
+
+/** The configuration files included in archives, relative to `$XDG_CONFIG_HOME`. */
+const CONFIG_FILES: [&str; 4] = [ "scout.conf", "scout_application.conf", "scout_directory.conf", "scout/snippets.toml" ];
+
+/** The state files included in archives, relative to `$XDG_DATA_HOME`. */
+const DATA_FILES: [&str; 2] = [ "scout/history", "scout/query_history" ];
+
+/** The launch history, which is merged by combining launches. */
+const HISTORY_FILE: &str = "data/scout/history";
+
+/** The snippets, which are TOML, so they are replaced instead of merged. */
+const SNIPPETS_FILE: &str = "config/scout/snippets.toml";
+
+/** The key of the popularity file set by the application plugin's `popularity_file` preference, which is replaced instead of merged. */
+const POPULARITY_FILE: &str = "popularity";
+
+/**
+ * A portable copy of the configuration and state files, keyed by `config/` or `data/` and their relative path.
+ */
+
+pub type Archive = BTreeMap<String, String>;
+
+
+/**
+ * Returns the user's configuration and data directories.
+ */
+
+fn get_dirs() -> (std::path::PathBuf, std::path::PathBuf) {
+	let home = std::path::PathBuf::from([ "/home/", &whoami::username() ].join(""));
+	(dirs::config_dir().unwrap_or_else(|| home.join(".config")), dirs::data_dir().unwrap_or_else(|| home.join(".local/share")))
+}
+
+
+/**
+ * Returns the absolute path of each file included in archives, by its key in the archive.
+ */
+
+fn get_files(config_dir: &std::path::Path, data_dir: &std::path::Path) -> Vec<(String, std::path::PathBuf)> {
+	CONFIG_FILES.iter().map(|file| ([ "config/", file ].join(""), config_dir.join(file)))
+		.chain(DATA_FILES.iter().map(|file| ([ "data/", file ].join(""), data_dir.join(file))))
+		.chain(get_popularity_file(config_dir).map(|file| (POPULARITY_FILE.to_owned(), file)))
+		.collect()
+}
+
+
+/**
+ * Returns the popularity file set in the application plugin's configuration, if there is one.
+ */
+
+fn get_popularity_file(config_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+	let config = std::fs::read_to_string(config_dir.join("scout_application.conf")).ok()?;
+	let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+	config.get("popularity_file")?.as_str().filter(|path| !path.is_empty()).map(std::path::PathBuf::from)
+}
+
+
+/**
+ * Reads every configuration and state file that exists into an archive, and writes it to the path specified.
+ */
+
+pub fn export(path: &std::path::Path) -> std::io::Result<()> {
+	let (config_dir, data_dir) = get_dirs();
+	export_from(&config_dir, &data_dir, path)
+}
+
+
+/**
+ * Exports the configuration and state files like `export`, from the configuration and data directories specified.
+ */
+
+pub fn export_from(config_dir: &std::path::Path, data_dir: &std::path::Path, path: &std::path::Path) -> std::io::Result<()> {
+	let archive: Archive = get_files(config_dir, data_dir).into_iter()
+		.filter_map(|(key, file)| std::fs::read_to_string(file).ok().map(|contents| (key, contents)))
+		.collect();
+
+	std::fs::write(path, serde_json::to_string_pretty(&archive)?)
+}
+
+
+/**
+ * Merges an imported configuration file into the existing one. Keys in the imported file replace
+ * existing ones, and keys only in the existing file are kept. Invalid files are replaced entirely.
+ */
+
+pub fn merge_config(existing: &str, imported: &str) -> String {
+	match (serde_json::from_str::<serde_json::Value>(existing), serde_json::from_str::<serde_json::Value>(imported)) {
+		(Ok(serde_json::Value::Object(mut existing)), Ok(serde_json::Value::Object(imported))) => {
+			existing.extend(imported);
+			serde_json::Value::Object(existing).to_string()
+		},
+		_ => imported.to_owned()
+	}
+}
+
+
+/**
+ * Merges two launch histories, so that launch counts add up instead of one history replacing the other.
+ * Launches in both histories are only counted once, and the result is ordered by time.
+ */
+
+pub fn merge_history(existing: &str, imported: &str) -> String {
+	let mut launches: Vec<(u64, &str)> = existing.lines().chain(imported.lines())
+		.filter_map(|line| {
+			let mut parts = line.splitn(2, ' ');
+			Some((parts.next()?.parse::<u64>().ok()?, parts.next()?))
+		})
+		.collect();
+
+	launches.sort();
+	launches.dedup();
+	launches.iter().map(|(timestamp, id)| format!("{} {}\n", timestamp, id)).collect()
+}
+
+
+/**
+ * Merges two line based histories, adding the imported lines that aren't already in the existing history.
+ */
+
+pub fn merge_lines(existing: &str, imported: &str) -> String {
+	let mut lines: Vec<&str> = existing.lines().filter(|line| !line.is_empty()).collect();
+	for line in imported.lines().filter(|line| !line.is_empty()) {
+		if !lines.contains(&line) { lines.push(line); }
+	}
+	lines.iter().map(|line| [ line, "\n" ].join("")).collect()
+}
+
+
+/**
+ * Imports an archive written by `export`. Files are merged with the existing ones
+ * unless `replace` is true, in which case they are overwritten.
+ * The files are found again after each one is written, so the popularity file goes where the imported configuration sets it.
+ */
+
+pub fn import(path: &std::path::Path, replace: bool) -> std::io::Result<()> {
+	let (config_dir, data_dir) = get_dirs();
+	import_into(&config_dir, &data_dir, path, replace)
+}
+
+
+/**
+ * Imports an archive like `import`, into the configuration and data directories specified.
+ */
+
+pub fn import_into(config_dir: &std::path::Path, data_dir: &std::path::Path, path: &std::path::Path, replace: bool) -> std::io::Result<()> {
+	let archive: Archive = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+	let mut imported_keys = vec![];
+	while let Some((key, file)) = get_files(config_dir, data_dir).into_iter().find(|(key, _)| !imported_keys.contains(key)) {
+		imported_keys.push(key.clone());
+		let imported = match archive.get(&key) {
+			Some(imported) => imported,
+			None => continue
+		};
+
+		let contents = match std::fs::read_to_string(&file).ok().filter(|_| !replace) {
+			None => imported.to_owned(),
+			Some(_) if key == SNIPPETS_FILE || key == POPULARITY_FILE => imported.to_owned(),
+			Some(existing) if key == HISTORY_FILE => merge_history(&existing, imported),
+			Some(existing) if key.starts_with("config/") => merge_config(&existing, imported),
+			Some(existing) => merge_lines(&existing, imported)
+		};
+
+		if let Some(parent) = file.parent() { std::fs::create_dir_all(parent)?; }
+		std::fs::write(&file, contents)?;
+	}
+
+	Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/**
+	 * Creates empty configuration and data directories in a temporary directory named after the test.
+	 */
+
+	fn get_dirs_for(name: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+		let dir = std::env::temp_dir().join(format!("scout-state-{}-{}", std::process::id(), name));
+		drop(std::fs::remove_dir_all(&dir));
+		std::fs::create_dir_all(dir.join("config")).unwrap();
+		std::fs::create_dir_all(dir.join("data")).unwrap();
+		(dir.join("config"), dir.join("data"))
+	}
+
+	fn read(dir: &std::path::Path, file: &str) -> String {
+		std::fs::read_to_string(dir.join(file)).unwrap()
+	}
+
+	#[test]
+	fn archives_round_trip() {
+		let (config_dir, data_dir) = get_dirs_for("export");
+		std::fs::write(config_dir.join("scout.conf"), r#"{"width":700}"#).unwrap();
+		std::fs::create_dir_all(data_dir.join("scout")).unwrap();
+		std::fs::write(data_dir.join("scout/history"), "100 firefox.desktop\n").unwrap();
+		let archive = config_dir.parent().unwrap().join("archive.json");
+		export_from(&config_dir, &data_dir, &archive).unwrap();
+
+		let (imported_config_dir, imported_data_dir) = get_dirs_for("import");
+		import_into(&imported_config_dir, &imported_data_dir, &archive, false).unwrap();
+		assert_eq!(read(&imported_config_dir, "scout.conf"), r#"{"width":700}"#);
+		assert_eq!(read(&imported_data_dir, "scout/history"), "100 firefox.desktop\n");
+		assert!(!imported_config_dir.join("scout_application.conf").exists());
+
+		std::fs::remove_dir_all(config_dir.parent().unwrap()).unwrap();
+		std::fs::remove_dir_all(imported_config_dir.parent().unwrap()).unwrap();
+	}
+
+	#[test]
+	fn imported_histories_are_merged_unless_replaced() {
+		let (config_dir, data_dir) = get_dirs_for("merge");
+		std::fs::create_dir_all(data_dir.join("scout")).unwrap();
+		std::fs::write(data_dir.join("scout/history"), "100 firefox.desktop\n300 code.desktop\n").unwrap();
+		std::fs::write(config_dir.join("scout.conf"), r#"{"width":700,"height":400}"#).unwrap();
+
+		let archive = config_dir.parent().unwrap().join("archive.json");
+		let imported: Archive = [ ("data/scout/history", "200 firefox.desktop\n300 code.desktop\n"), ("config/scout.conf", r#"{"width":800}"#) ]
+			.iter().map(|(key, contents)| (key.to_string(), contents.to_string())).collect();
+		std::fs::write(&archive, serde_json::to_string(&imported).unwrap()).unwrap();
+
+		import_into(&config_dir, &data_dir, &archive, false).unwrap();
+		assert_eq!(read(&data_dir, "scout/history"), "100 firefox.desktop\n200 firefox.desktop\n300 code.desktop\n");
+		assert_eq!(serde_json::from_str::<serde_json::Value>(&read(&config_dir, "scout.conf")).unwrap(),
+			serde_json::json!({ "width": 800, "height": 400 }));
+
+		import_into(&config_dir, &data_dir, &archive, true).unwrap();
+		assert_eq!(read(&data_dir, "scout/history"), "200 firefox.desktop\n300 code.desktop\n");
+		assert_eq!(read(&config_dir, "scout.conf"), r#"{"width":800}"#);
+
+		std::fs::remove_dir_all(config_dir.parent().unwrap()).unwrap();
+	}
+
+	#[test]
+	fn line_histories_keep_existing_lines_first() {
+		assert_eq!(merge_lines("firefox\ncode\n", "code\nblender\n"), "firefox\ncode\nblender\n");
+		assert_eq!(merge_config("invalid", r#"{"width":800}"#), r#"{"width":800}"#);
+	}
+}
@@ -0,0 +1,84 @@
+/*!
+ * Optional feedback given when a result is activated, either flashing the result or playing an event sound.
+ */
+
+use gtk::prelude::*;
+use serde::{ Serialize, Deserialize };
+
+/** How long an activated result is flashed for, in milliseconds. */
+pub const FLASH_DURATION: u32 = 150;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Feedback {
+	None,
+	Visual,
+	Sound
+}
+
+impl Default for Feedback {
+	fn default() -> Self { Feedback::None }
+}
+
+
+/**
+ * Returns the program and arguments that play the feedback sound, or `None` if the mode doesn't play sounds.
+ * Paths are played with `paplay`, and anything else is treated as a sound theme event id for `canberra-gtk-play`.
+ */
+
+pub fn get_sound_command(feedback: Feedback, sound: &str) -> Option<(&'static str, Vec<String>)> {
+	if feedback != Feedback::Sound || sound.is_empty() { return None; }
+	if sound.contains('/') { Some(("paplay", vec![ sound.to_owned() ])) }
+	else { Some(("canberra-gtk-play", vec![ "-i".to_owned(), sound.to_owned() ])) }
+}
+
+
+/**
+ * Plays the feedback sound in the background, silently doing nothing if the player isn't installed.
+ */
+
+pub fn play_sound(feedback: Feedback, sound: &str) {
+	if let Some((program, args)) = get_sound_command(feedback, sound) {
+		drop(std::process::Command::new(program).args(&args)
+			.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn());
+	}
+}
+
+
+/**
+ * Briefly highlights a result widget by adding the `flash` style class.
+ */
+
+pub fn flash(widget: &gtk::Widget) {
+	widget.get_style_context().add_class("flash");
+	let widget = widget.clone();
+	glib::timeout_add_local(FLASH_DURATION, move || {
+		widget.get_style_context().remove_class("flash");
+		glib::Continue(false)
+	});
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sound_commands_are_only_built_in_sound_mode() {
+		assert_eq!(get_sound_command(Feedback::None, "button-pressed"), None);
+		assert_eq!(get_sound_command(Feedback::Visual, "button-pressed"), None);
+		assert_eq!(get_sound_command(Feedback::Sound, ""), None);
+
+		assert_eq!(get_sound_command(Feedback::Sound, "button-pressed"),
+			Some(("canberra-gtk-play", vec![ "-i".to_owned(), "button-pressed".to_owned() ])));
+		assert_eq!(get_sound_command(Feedback::Sound, "/usr/share/sounds/click.oga"),
+			Some(("paplay", vec![ "/usr/share/sounds/click.oga".to_owned() ])));
+	}
+
+	#[test]
+	fn feedback_modes_are_lowercase() {
+		assert_eq!(serde_json::from_str::<Feedback>(r#""sound""#).unwrap(), Feedback::Sound);
+		assert_eq!(serde_json::to_string(&Feedback::Visual).unwrap(), r#""visual""#);
+		assert_eq!(Feedback::default(), Feedback::None);
+	}
+}
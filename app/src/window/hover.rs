@@ -0,0 +1,59 @@
+/*!
+ * Showing a result's preview after the pointer has rested on it, like a tooltip.
+ */
+
+/**
+ * The result the pointer is over, and the monotonic time in microseconds that it entered it.
+ */
+
+pub type Hover = Option<(usize, i64)>;
+
+/**
+ * Returns true if the preview of the result at `index`, which the pointer entered at `entered_at`,
+ * should be shown at `now`. The pointer must still be on the same result it entered then,
+ * and have stayed on it for at least `delay_ms` milliseconds.
+ */
+
+pub fn should_preview(hover: Hover, index: usize, entered_at: i64, now: i64, delay_ms: u32) -> bool {
+	hover == Some((index, entered_at)) && now - entered_at >= delay_ms as i64 * 1000
+}
+
+
+/**
+ * Returns the index of the widget that contains the vertical position, given each widget's top and height.
+ */
+
+pub fn get_hovered(y: i32, bounds: &[(i32, i32)]) -> Option<usize> {
+	bounds.iter().position(|(top, height)| y >= *top && y < top + height)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn previews_show_after_the_delay() {
+		assert!(should_preview(Some((2, 1_000)), 2, 1_000, 1_000 + 300_000, 300));
+		assert!(should_preview(Some((2, 1_000)), 2, 1_000, 1_000 + 500_000, 300));
+		assert!(!should_preview(Some((2, 1_000)), 2, 1_000, 1_000 + 299_999, 300));
+		assert!(should_preview(Some((0, 0)), 0, 0, 0, 0));
+	}
+
+	#[test]
+	fn leaving_or_reentering_cancels_the_preview() {
+		assert!(!should_preview(None, 2, 1_000, 1_000_000, 300));
+		assert!(!should_preview(Some((3, 1_000)), 2, 1_000, 1_000_000, 300));
+		assert!(!should_preview(Some((2, 2_000)), 2, 1_000, 1_000_000, 300));
+	}
+
+	#[test]
+	fn hovered_widgets_are_found_by_position() {
+		let bounds = [ (0, 40), (40, 40), (80, 60) ];
+		assert_eq!(get_hovered(0, &bounds), Some(0));
+		assert_eq!(get_hovered(40, &bounds), Some(1));
+		assert_eq!(get_hovered(139, &bounds), Some(2));
+		assert_eq!(get_hovered(140, &bounds), None);
+		assert_eq!(get_hovered(-1, &bounds), None);
+	}
+}
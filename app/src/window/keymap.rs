@@ -0,0 +1,88 @@
+/*!
+ * Maps key presses in the main window to the actions they trigger.
+ *
+ * | Key          | Action                                                    |
+ * |--------------|-----------------------------------------------------------|
+ * | Enter        | Activates the selected result, and hides the window, or   |
+ * |              | clears the query if `close_on_activate` is disabled.      |
+ * | Ctrl+Enter   | Activates the selected result, keeping the window open.   |
+ * | Shift+Enter  | Triggers the selected result's secondary action. Can be   |
+ * |              | combined with Ctrl to keep the window open.               |
+ * | Tab          | Moves focus from the search entry into the selected       |
+ * |              | result's actions.                                         |
+ * | Ctrl+N       | Selects the next result.                                  |
+ * | Ctrl+P       | Selects the previous result.                              |
+ * | Ctrl+R       | Rescans plugins, and searches the current query again.    |
+ * | Ctrl+Shift+R | Reloads plugin preferences, and searches the query again. |
+ * | Ctrl+S       | Switches the sort mode, and searches the current query.   |
+ * | Up / Down    | Recalls previous queries, handled by the search entry.    |
+ */
+
+use gdk::keys::constants as keys;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+	Activate { keep_open: bool, secondary: bool },
+	FocusActions,
+	SelectNext,
+	SelectPrev,
+	Refresh,
+	Reload,
+	CycleSort
+}
+
+/**
+ * Returns the action triggered by a key press, or `None` if it should be handled normally.
+ */
+
+pub fn get_key_action(keyval: &gdk::keys::Key, state: gdk::ModifierType) -> Option<KeyAction> {
+	let control = state.contains(gdk::ModifierType::CONTROL_MASK);
+	let shift = state.contains(gdk::ModifierType::SHIFT_MASK);
+	let keyval = keyval.clone();
+
+	if keyval == keys::Return || keyval == keys::KP_Enter { Some(KeyAction::Activate { keep_open: control, secondary: shift }) }
+	else if keyval == keys::Tab && !control { Some(KeyAction::FocusActions) }
+	else if !control { None }
+	else if keyval == keys::n { Some(KeyAction::SelectNext) }
+	else if keyval == keys::p { Some(KeyAction::SelectPrev) }
+	else if keyval == keys::r { Some(KeyAction::Refresh) }
+	else if keyval == keys::R { Some(KeyAction::Reload) }
+	else if keyval == keys::s { Some(KeyAction::CycleSort) }
+	else { None }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const NONE: gdk::ModifierType = gdk::ModifierType::empty();
+	const CONTROL: gdk::ModifierType = gdk::ModifierType::CONTROL_MASK;
+	const SHIFT: gdk::ModifierType = gdk::ModifierType::SHIFT_MASK;
+
+	#[test]
+	fn enter_activates_with_its_modifiers() {
+		assert_eq!(get_key_action(&keys::Return, NONE), Some(KeyAction::Activate { keep_open: false, secondary: false }));
+		assert_eq!(get_key_action(&keys::KP_Enter, CONTROL), Some(KeyAction::Activate { keep_open: true, secondary: false }));
+		assert_eq!(get_key_action(&keys::Return, SHIFT), Some(KeyAction::Activate { keep_open: false, secondary: true }));
+		assert_eq!(get_key_action(&keys::Return, CONTROL | SHIFT), Some(KeyAction::Activate { keep_open: true, secondary: true }));
+	}
+
+	#[test]
+	fn control_shortcuts_map_to_actions() {
+		assert_eq!(get_key_action(&keys::Tab, NONE), Some(KeyAction::FocusActions));
+		assert_eq!(get_key_action(&keys::n, CONTROL), Some(KeyAction::SelectNext));
+		assert_eq!(get_key_action(&keys::p, CONTROL), Some(KeyAction::SelectPrev));
+		assert_eq!(get_key_action(&keys::r, CONTROL), Some(KeyAction::Refresh));
+		assert_eq!(get_key_action(&keys::R, CONTROL | SHIFT), Some(KeyAction::Reload));
+		assert_eq!(get_key_action(&keys::s, CONTROL), Some(KeyAction::CycleSort));
+	}
+
+	#[test]
+	fn other_keys_are_handled_normally() {
+		assert_eq!(get_key_action(&keys::n, NONE), None);
+		assert_eq!(get_key_action(&keys::Tab, CONTROL), None);
+		assert_eq!(get_key_action(&keys::Up, NONE), None);
+		assert_eq!(get_key_action(&keys::x, CONTROL), None);
+	}
+}
@@ -3,6 +3,12 @@ mod style;
 mod prefs_page;
 pub use prefs_page::*;
 
+mod keymap;
+pub use keymap::*;
+
+mod selection;
+pub use selection::*;
+
 mod window;
 pub use window::*;
 
@@ -11,3 +17,8 @@ pub use about::*;
 
 mod prefs;
 pub use prefs::*;
+
+mod feedback;
+pub use feedback::Feedback;
+
+mod hover;
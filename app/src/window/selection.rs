@@ -0,0 +1,121 @@
+/*!
+ * Tracks which of the displayed results is selected.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+	count: usize,
+	index: Option<usize>,
+	wrap: bool
+}
+
+impl Selection {
+
+	/**
+	 * Creates an empty selection. If `wrap` is true, moving past either end
+	 * of the results selects the result at the other end.
+	 */
+
+	pub fn new(wrap: bool) -> Self {
+		Selection { count: 0, index: None, wrap }
+	}
+
+
+	/**
+	 * Resets the selection for a new list of results, selecting the first one.
+	 */
+
+	pub fn reset(&mut self, count: usize) {
+		self.count = count;
+		self.index = if count > 0 { Some(0) } else { None };
+	}
+
+
+	/**
+	 * Selects the next result, returning the new selection index.
+	 */
+
+	pub fn select_next(&mut self) -> Option<usize> {
+		let index = self.index?;
+		self.index = Some(if index + 1 < self.count { index + 1 } else if self.wrap { 0 } else { index });
+		self.index
+	}
+
+
+	/**
+	 * Selects the previous result, returning the new selection index.
+	 */
+
+	pub fn select_prev(&mut self) -> Option<usize> {
+		let index = self.index?;
+		self.index = Some(if index > 0 { index - 1 } else if self.wrap { self.count - 1 } else { index });
+		self.index
+	}
+
+	/**
+	 * Selects the result at an index, if it exists.
+	 */
+
+	pub fn select(&mut self, index: usize) {
+		if index < self.count { self.index = Some(index); }
+	}
+
+	pub fn selected(&self) -> Option<usize> {
+		self.index
+	}
+
+
+	/**
+	 * Returns a status line describing the result count and selection, such as `2 of 12 results`.
+	 */
+
+	pub fn get_status(&self) -> String {
+		match (self.index, self.count) {
+			(_, 0) => "No results".to_owned(),
+			(Some(index), 1) => format!("{} of 1 result", index + 1),
+			(Some(index), count) => format!("{} of {} results", index + 1, count),
+			(None, count) => format!("{} results", count)
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn navigation_stops_at_the_ends() {
+		let mut selection = Selection::new(false);
+		selection.reset(3);
+		assert_eq!(selection.selected(), Some(0));
+		assert_eq!(selection.select_prev(), Some(0));
+		assert_eq!(selection.select_next(), Some(1));
+		assert_eq!(selection.select_next(), Some(2));
+		assert_eq!(selection.select_next(), Some(2));
+
+		selection.select(7);
+		assert_eq!(selection.selected(), Some(2));
+	}
+
+	#[test]
+	fn navigation_wraps_when_enabled() {
+		let mut selection = Selection::new(true);
+		selection.reset(3);
+		assert_eq!(selection.select_prev(), Some(2));
+		assert_eq!(selection.select_next(), Some(0));
+	}
+
+	#[test]
+	fn status_reports_the_count() {
+		let mut selection = Selection::new(false);
+		assert_eq!(selection.get_status(), "No results");
+		assert_eq!(selection.select_next(), None);
+
+		selection.reset(1);
+		assert_eq!(selection.get_status(), "1 of 1 result");
+		selection.reset(12);
+		selection.select(1);
+		assert_eq!(selection.get_status(), "2 of 12 results");
+	}
+}
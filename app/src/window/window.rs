@@ -4,7 +4,12 @@ use scout_core::{ SearchResult };
 // use glib::translate::{ ToGlib, FromGlib };
 
 use super::about;
+use super::feedback;
+use super::hover;
+use super::Feedback;
 use super::style;
+use super::Selection;
+use super::{ KeyAction, get_key_action };
 use super::prefs::PrefsWindow;
 
 use scout_core::Shared;
@@ -13,9 +18,28 @@ use crate::preferences::Preferences;
 
 static DIMENSIONS: (i32, i32) = ( 700, 500 );
 
+/** How long after being hidden the window ignores requests to show it, in microseconds. */
+const HIDE_DELAY: i64 = 250_000;
+
+/**
+ * What happens to the window after a result is activated.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostActivation {
+	Hide,
+	ClearQuery,
+	KeepOpen
+}
+
 pub struct WindowCallbacks {
 	pub on_input: Box<dyn FnMut(&str)>,
 	pub on_submit: Box<dyn FnMut()>,
+	pub on_refresh: Box<dyn FnMut()>,
+	pub on_reload: Box<dyn FnMut()>,
+	pub on_cycle_sort: Box<dyn FnMut()>,
+	pub on_history_prev: Box<dyn FnMut(bool) -> Option<String>>,
+	pub on_history_next: Box<dyn FnMut() -> Option<String>>,
 	// on_clear: Box<dyn FnMut()>,
 	// on_show: Box<dyn FnMut()>,
 	// on_hide: Box<dyn FnMut()>
@@ -26,6 +50,11 @@ impl Default for WindowCallbacks {
 		WindowCallbacks {
 			on_input: Box::new(|_| ()),
 			on_submit: Box::new(|| ()),
+			on_refresh: Box::new(|| ()),
+			on_reload: Box::new(|| ()),
+			on_cycle_sort: Box::new(|| ()),
+			on_history_prev: Box::new(|_| None),
+			on_history_next: Box::new(|| None),
 		}
 	}
 }
@@ -33,21 +62,30 @@ impl Default for WindowCallbacks {
 pub struct Window {
 	window: gtk::ApplicationWindow,
 	search_entry: gtk::Entry,
+	peek_label: gtk::Label,
 	results_box: gtk::Box,
 	results_scroller: gtk::ScrolledWindow,
 	preview_scroller: gtk::ScrolledWindow,
+	status_label: gtk::Label,
 
 	callbacks: Shared<WindowCallbacks>,
 	preferences: Shared<Preferences>,
 
 	results: Vec<Box<dyn SearchResult>>,
+	selection: Selection,
+	hover: hover::Hover,
 
 	pub last_hide: i64
 }
 
 impl Window {
-	pub fn new(gtk: &gtk::Application, styles: &Vec<&'static str>) -> Shared<Self> {
-		let preferences = Preferences::new(None);
+	/**
+	 * Creates the main window. If `hidden` is true, the window is created
+	 * without being shown, and is shown on the next activation.
+	 * The preferences are shared with the app, so reloading them updates the window too.
+	 */
+
+	pub fn new(gtk: &gtk::Application, styles: &Vec<&'static str>, hidden: bool, preferences: Shared<Preferences>) -> Shared<Self> {
 		let window = gtk::ApplicationWindow::new(gtk);
 
 		// Basic window configuration //
@@ -81,6 +119,14 @@ impl Window {
 		search_entry.set_size_request(DIMENSIONS.0, 48);
 		top_layout.put(&search_entry, 0, 0);
 
+		// Shows a plugin's answer to the query, like the application it completes to, at the end of the entry.
+		let peek_label = gtk::Label::new(None);
+		peek_label.set_widget_name("PeekLabel");
+		peek_label.set_xalign(1.0);
+		peek_label.set_ellipsize(pango::EllipsizeMode::Start);
+		peek_label.set_size_request(200, 48);
+		top_layout.put(&peek_label, DIMENSIONS.0 - 256, 0);
+
 		let profile = gtk::Button::new();
 		profile.set_widget_name("ProfileButton");
 		top_layout.put(&profile, DIMENSIONS.0 - 41, 7);
@@ -152,6 +198,11 @@ impl Window {
 		let results_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
 		results_scroller.add(&results_box);
 
+		let status_label = gtk::Label::new(None);
+		status_label.set_widget_name("StatusLabel");
+		status_label.set_xalign(0.0);
+		results_container.pack_end(&status_label, false, false, 0);
+
 		let preview = gtk::Box::new(gtk::Orientation::Vertical, 0);
 		preview.set_widget_name("PreviewContainer");
 		content_container.pack_start(&preview, true, true, 0);
@@ -163,20 +214,27 @@ impl Window {
 		// Final configuration //
 
 		if preferences.borrow().opacity != 100 { Window::enable_transparency(&window); }
-		window.show_all();
+		if hidden { app_container.show_all(); }
+		else { window.show_all(); }
 
 		// Create the window shared object
 
+		let selection = Selection::new(preferences.borrow().wrap_selection);
+
 		let window = Shared::new(Window {
 			window,
 			search_entry,
+			peek_label,
 			results_box,
 			results_scroller,
 			preview_scroller,
+			status_label,
 
 			callbacks: Shared::new(WindowCallbacks::default()),
 			preferences,
 			results: vec![],
+			selection,
+			hover: None,
 
 			last_hide: 0
 		});
@@ -194,6 +252,25 @@ impl Window {
 			else { results_scroller_clone.get_style_context().remove_class("focus"); }
 		});
 
+		// Show the preview of a hovered result after a delay, if enabled
+
+		if window.borrow().preferences.borrow().hover_preview {
+			let results_scroller = window.borrow().results_scroller.clone();
+			results_scroller.add_events(gdk::EventMask::POINTER_MOTION_MASK | gdk::EventMask::LEAVE_NOTIFY_MASK);
+
+			let window_clone = window.clone();
+			results_scroller.connect_motion_notify_event(move |_, event| {
+				if let Some(device) = event.get_device() { Window::hover(&window_clone, &device); }
+				Inhibit(false)
+			});
+
+			let window_clone = window.clone();
+			results_scroller.connect_leave_notify_event(move |_, event| {
+				if event.get_detail() != gdk::NotifyType::Inferior { window_clone.borrow_mut().hover = None; }
+				Inhibit(false)
+			});
+		}
+
 		// Bind search entry functions
 
 		let callbacks_clone = window.borrow().callbacks.clone();
@@ -204,6 +281,78 @@ impl Window {
 		window.borrow().search_entry.connect_activate(move |_|
 			(callbacks_clone.borrow_mut().on_submit)());
 
+		// Recall previous queries with Up when the cursor is at the start, and step forward with Down
+
+		let callbacks_clone = window.borrow().callbacks.clone();
+		window.borrow().search_entry.connect_key_press_event(move |entry, key| {
+			let keyval = key.get_keyval();
+			let query =
+				if keyval == gdk::keys::constants::Up { (callbacks_clone.borrow_mut().on_history_prev)(entry.get_position() == 0) }
+				else if keyval == gdk::keys::constants::Down { (callbacks_clone.borrow_mut().on_history_next)() }
+				else { None };
+
+			match query {
+				Some(query) => {
+					entry.set_text(&query);
+					entry.set_position(-1);
+					Inhibit(true)
+				},
+				None => Inhibit(false)
+			}
+		});
+
+		// Handle the key map, see `keymap.rs`
+
+		let window_clone = window.clone();
+		let callbacks_clone = window.borrow().callbacks.clone();
+		let search_entry_clone = window.borrow().search_entry.clone();
+		window.borrow().window.connect_key_press_event(move |_, key| {
+			let action = match get_key_action(&key.get_keyval(), key.get_state()) {
+				Some(action) => action,
+				None => return Inhibit(false)
+			};
+
+			match action {
+				KeyAction::Activate { keep_open, secondary } => {
+					if !search_entry_clone.has_focus() { return Inhibit(false); }
+					(callbacks_clone.borrow_mut().on_submit)();
+					if window_clone.borrow().preferences.borrow().instant_enter {
+						if !window_clone.borrow_mut().activate_instantly(secondary) { return Inhibit(true); }
+					}
+					else { window_clone.borrow().activate_selected(secondary); }
+					let post_activation = get_post_activation(keep_open, window_clone.borrow().preferences.borrow().close_on_activate);
+					if post_activation != PostActivation::KeepOpen {
+						// Leave the window open long enough for the result to visibly flash.
+						if window_clone.borrow().preferences.borrow().feedback == Feedback::Visual {
+							let window_clone = window_clone.clone();
+							glib::timeout_add_local(feedback::FLASH_DURATION, move || {
+								window_clone.borrow_mut().finish_activation(post_activation);
+								glib::Continue(false)
+							});
+						}
+						else { window_clone.borrow_mut().finish_activation(post_activation); }
+					}
+				},
+				KeyAction::FocusActions => {
+					if !search_entry_clone.has_focus() || !window_clone.borrow().focus_selected_actions() {
+						return Inhibit(false);
+					}
+				},
+				KeyAction::SelectNext => window_clone.borrow_mut().select_next(),
+				KeyAction::SelectPrev => window_clone.borrow_mut().select_prev(),
+				KeyAction::Refresh | KeyAction::Reload | KeyAction::CycleSort => {
+					match action {
+						KeyAction::Refresh => (callbacks_clone.borrow_mut().on_refresh)(),
+						KeyAction::Reload => (callbacks_clone.borrow_mut().on_reload)(),
+						_ => (callbacks_clone.borrow_mut().on_cycle_sort)()
+					}
+					(callbacks_clone.borrow_mut().on_input)(&search_entry_clone.get_text().to_string());
+				}
+			}
+
+			Inhibit(true)
+		});
+
 		// let window_clone = window.clone();
 
 		// let search_clone = widgets.search.clone();
@@ -281,11 +430,7 @@ impl Window {
 			if *first.borrow() {
 				first.replace(false);
 			}
-			else {
-				let mut window = window_clone.borrow_mut();
-				if window.can_show() && !window.is_active() { window.show() }
-				else { window.hide() }
-			}
+			else { window_clone.borrow_mut().toggle(); }
 		});
 
 		if window.borrow().preferences.borrow().hide_on_unfocus {
@@ -303,25 +448,217 @@ impl Window {
 		self.callbacks.replace(callbacks);
 	}
 
+	/**
+	 * Shows a plugin's answer to the query beside the search entry, or hides it if there is none.
+	 */
+
+	pub fn set_peek(&self, answer: Option<&str>) {
+		self.peek_label.set_text(answer.unwrap_or_default());
+	}
+
 	pub fn set_results(&mut self, results: Vec<Box<dyn SearchResult>>) {
 		self.results = results;
+		self.hover = None;
 
 		self.results_box.get_children().iter()
 			.for_each(|c| self.results_box.remove(c));
 		self.preview_scroller.get_children().iter()
 			.for_each(|c| self.preview_scroller.remove(c));
 
-		if self.results.len() > 0 {
-			self.preview_scroller.add(&self.results[0].get_preview_widget());
+		self.selection.reset(self.results.len());
 
+		if self.results.len() > 0 {
 			for (i, res) in self.results.iter().enumerate() {
 				res.set_first(i == 0);
+				res.set_rank(i);
 				self.results_box.pack_start(&res.get_result_widget(), false, false, 0);
 			}
 
 			self.results_box.show_all();
+		}
+
+		self.update_selection(None);
+	}
+
+
+	/**
+	 * Selects the next result, updating the preview and status line.
+	 */
+
+	pub fn select_next(&mut self) {
+		let last = self.selection.selected();
+		self.selection.select_next();
+		self.update_selection(last);
+	}
+
+
+	/**
+	 * Selects the previous result, updating the preview and status line.
+	 */
+
+	pub fn select_prev(&mut self) {
+		let last = self.selection.selected();
+		self.selection.select_prev();
+		self.update_selection(last);
+	}
+
+	pub fn selected(&self) -> Option<&Box<dyn SearchResult>> {
+		self.selection.selected().and_then(|index| self.results.get(index))
+	}
+
+
+	/**
+	 * Activates the selected result, if there is one,
+	 * triggering its secondary action if `secondary` is true.
+	 */
+
+	pub fn activate_selected(&self, secondary: bool) {
+		if let Some(selected) = self.selected() {
+			if secondary { selected.activate_secondary(); }
+			else { selected.activate(); }
+			self.give_feedback(selected);
+		}
+	}
+
+
+	/**
+	 * Flashes an activated result or plays the feedback sound, depending on the `feedback` preference.
+	 */
+
+	fn give_feedback(&self, result: &Box<dyn SearchResult>) {
+		let preferences = self.preferences.borrow();
+		match preferences.feedback {
+			Feedback::None => (),
+			Feedback::Visual => feedback::flash(&result.get_result_widget()),
+			Feedback::Sound => feedback::play_sound(preferences.feedback, &preferences.feedback_sound)
+		}
+	}
+
+
+	/**
+	 * Returns true if the top result of a search scores high enough to be activated by `instant_enter`.
+	 */
+
+	pub fn exceeds_threshold(top_score: Option<usize>, threshold: usize) -> bool {
+		top_score.is_some_and(|score| score >= threshold)
+	}
+
+
+	/**
+	 * Activates the top result if `instant_enter` is enabled and it exceeds the threshold, regardless of the selection.
+	 * Otherwise, the selected result is activated as it would be without `instant_enter`. Returns true if a result was activated.
+	 */
+
+	pub fn activate_instantly(&mut self, secondary: bool) -> bool {
+		// Results may be sorted by something else, so find the highest scoring one.
+		let top = self.results.iter().enumerate().max_by_key(|(_, result)| result.get_score()).map(|(index, _)| index);
+		let threshold = self.preferences.borrow().instant_enter_threshold;
+		if !Window::exceeds_threshold(top.map(|index| self.results[index].get_score()), threshold) {
+			self.activate_selected(secondary);
+			return self.selected().is_some();
+		}
+
+		let last = self.selection.selected();
+		self.selection.select(top.unwrap());
+		self.update_selection(last);
+		self.activate_selected(secondary);
+		true
+	}
+
+
+	/**
+	 * Tracks the result under the pointer, and shows its preview once the pointer rests on it for `preview_delay_ms`.
+	 * Moving to another result or out of the results before then cancels it.
+	 */
+
+	fn hover(window: &Shared<Window>, device: &gdk::Device) {
+		use gdk::WindowExt;
+
+		let index = {
+			let window = window.borrow();
+			let y = match window.results_box.get_window().map(|gdk_window| gdk_window.get_device_position(device)) {
+				Some((_, _, y, _)) => y,
+				None => return
+			};
+			let bounds: Vec<_> = window.results.iter().map(|result| {
+				let allocation = result.get_result_widget().get_allocation();
+				(allocation.y, allocation.height)
+			}).collect();
+			hover::get_hovered(y, &bounds)
+		};
+
+		let mut window_mut = window.borrow_mut();
+		if window_mut.hover.map(|(hovered, _)| hovered) == index { return; }
+		window_mut.hover = index.map(|index| (index, glib::get_monotonic_time()));
+
+		if let Some((index, entered_at)) = window_mut.hover {
+			let delay = window_mut.preferences.borrow().preview_delay_ms;
+			let window_clone = window.clone();
+			glib::timeout_add_local(delay, move || {
+				let window = window_clone.borrow();
+				if hover::should_preview(window.hover, index, entered_at, glib::get_monotonic_time(), delay) {
+					window.show_preview(index);
+				}
+				glib::Continue(false)
+			});
+		}
+	}
+
+
+	/**
+	 * Replaces the preview with the preview of the result at `index`, without changing the selection.
+	 */
+
+	fn show_preview(&self, index: usize) {
+		let result = match self.results.get(index) {
+			Some(result) => result,
+			None => return
+		};
+
+		self.preview_scroller.get_children().iter()
+			.for_each(|c| self.preview_scroller.remove(c));
+		self.preview_scroller.add(&result.get_preview_widget());
+		self.preview_scroller.show_all();
+	}
+
+
+	/**
+	 * Hides the window or clears the query after a result is activated, as decided by `get_post_activation`.
+	 */
+
+	pub fn finish_activation(&mut self, post_activation: PostActivation) {
+		match post_activation {
+			PostActivation::Hide => self.hide(),
+			PostActivation::ClearQuery => self.clear_query(),
+			PostActivation::KeepOpen => ()
+		}
+	}
+
+
+	/**
+	 * Moves focus into the selected result's widget, such as its actions.
+	 * Returns false if the result has nothing to focus.
+	 */
+
+	pub fn focus_selected_actions(&self) -> bool {
+		self.selected().is_some_and(|selected| selected.get_result_widget().child_focus(gtk::DirectionType::TabForward))
+	}
+
+	fn update_selection(&self, last: Option<usize>) {
+		if let Some(last) = last.and_then(|index| self.results.get(index)) {
+			last.get_result_widget().get_style_context().remove_class("selected");
+		}
+
+		self.preview_scroller.get_children().iter()
+			.for_each(|c| self.preview_scroller.remove(c));
+
+		if let Some(selected) = self.selected() {
+			selected.get_result_widget().get_style_context().add_class("selected");
+			self.preview_scroller.add(&selected.get_preview_widget());
 			self.preview_scroller.show_all();
 		}
+
+		self.status_label.set_text(&self.selection.get_status());
 	}
 
 	fn enable_transparency(window: &gtk::ApplicationWindow) {
@@ -345,13 +682,22 @@ impl Window {
 	}
 
 	fn can_show(&self) -> bool {
-		glib::get_monotonic_time() - self.last_hide >= 250_000
+		can_show_since(self.last_hide, glib::get_monotonic_time())
 	}
 
 	fn is_active(&self) -> bool {
 		self.window.get_focus().is_some() && self.window.is_visible()
 	}
 
+	/**
+	 * Shows the window if it is hidden or unfocused, and hides it otherwise.
+	 */
+
+	fn toggle(&mut self) {
+		if should_show(self.can_show(), self.is_active()) { self.show() }
+		else { self.hide() }
+	}
+
 	fn show(&mut self) {
 		if !self.can_show() { return }
 
@@ -365,9 +711,85 @@ impl Window {
 		self.window.hide();
 		self.last_hide = glib::get_monotonic_time();
 
+		self.clear_query();
+	}
+
+	fn clear_query(&self) {
+		// Clear the search once the window is no longer borrowed, as clearing it triggers a search.
 		let search = self.search_entry.clone();
-		drop(self);
+		glib::idle_add_local(move || {
+			search.set_text("");
+			glib::Continue(false)
+		});
+	}
+}
+
+
+/**
+ * Returns whether the window can be shown again at `now`, which it can't for a moment after being hidden,
+ * so that the activation which hid it by unfocusing it doesn't immediately show it again.
+ */
+
+fn can_show_since(last_hide: i64, now: i64) -> bool {
+	now - last_hide >= HIDE_DELAY
+}
+
+
+/**
+ * Returns whether toggling the window should show it, which it does unless it's already active.
+ */
+
+fn should_show(can_show: bool, active: bool) -> bool {
+	can_show && !active
+}
+
+
+/**
+ * Returns what happens to the window after a result is activated. Holding Ctrl keeps it open as it was,
+ * and otherwise it's hidden, or its query is cleared if `close_on_activate` is disabled.
+ */
+
+fn get_post_activation(keep_open: bool, close_on_activate: bool) -> PostActivation {
+	if keep_open { PostActivation::KeepOpen }
+	else if close_on_activate { PostActivation::Hide }
+	else { PostActivation::ClearQuery }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn toggling_shows_inactive_windows_and_hides_active_ones() {
+		assert!(should_show(true, false));
+		assert!(!should_show(true, true));
+		assert!(!should_show(false, false));
+		assert!(!should_show(false, true));
+	}
+
+	#[test]
+	fn windows_only_show_again_after_the_hide_delay() {
+		assert!(can_show_since(0, HIDE_DELAY));
+		assert!(can_show_since(1_000, 1_000 + HIDE_DELAY * 2));
+		assert!(!can_show_since(1_000, 1_000 + HIDE_DELAY - 1));
+		assert!(!can_show_since(1_000, 1_000));
+	}
+
+	#[test]
+	fn activation_hides_or_clears_unless_kept_open() {
+		assert_eq!(get_post_activation(false, true), PostActivation::Hide);
+		assert_eq!(get_post_activation(false, false), PostActivation::ClearQuery);
+		assert_eq!(get_post_activation(true, true), PostActivation::KeepOpen);
+		assert_eq!(get_post_activation(true, false), PostActivation::KeepOpen);
+	}
+
 
-		search.set_text("");
+	#[test]
+	fn only_top_scores_over_the_threshold_activate_instantly() {
+		assert!(Window::exceeds_threshold(Some(150), 100));
+		assert!(Window::exceeds_threshold(Some(100), 100));
+		assert!(!Window::exceeds_threshold(Some(99), 100));
+		assert!(!Window::exceeds_threshold(None, 0));
 	}
 }
@@ -42,6 +42,12 @@ pub use plugin::*;
 mod searchresult;
 pub use searchresult::SearchResult;
 
+mod text;
+pub use text::{ truncate_graphemes, escape_markup };
+
+mod score;
+pub use score::get_name_score;
+
 
 /** The current core scout_core version. */
 pub static CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
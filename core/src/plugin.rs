@@ -48,6 +48,37 @@ pub trait Plugin {
 	fn get_results(&self, query: &str) -> Result<Vec<Box<dyn SearchResult>>>;
 
 
+	/**
+	 * Discards the results found when the plugin was loaded, and discovers them again.
+	 * Plugins without discovery state do not need to implement this.
+	 */
+
+	fn refresh(&mut self) -> Result<()> {
+		Err(InvocationError::DoesNotProvide("refresh".to_owned()))
+	}
+
+
+	/**
+	 * Reads the plugin's preferences again, keeping the results it has discovered.
+	 * If the preferences are invalid, the previous preferences should be kept.
+	 */
+
+	fn reload(&mut self) -> Result<()> {
+		Err(InvocationError::DoesNotProvide("reload".to_owned()))
+	}
+
+
+	/**
+	 * Returns a short answer to the query, such as the value of an expression or the name it completes to, to show beside
+	 * the search entry as the user types. The answer is shown as it is, so a calculator should include a prefix like `= `.
+	 * Returns `None` if the query has no answer. Plugins without answers do not need to implement this.
+	 */
+
+	fn peek(&self, _query: &str) -> Result<Option<String>> {
+		Err(InvocationError::DoesNotProvide("peek".to_owned()))
+	}
+
+
 	// /**
 	//  * Gets a plugin's custom CSS.
 	//  */
@@ -0,0 +1,45 @@
+/**
+ * Scores a query against a name by finding each of the query's letters in order, with letters found
+ * closer to the previous one scoring more. Case and whitespace are ignored, so `vs code` matches `VSCode`.
+ * Returns zero unless every letter of the query is found.
+ */
+
+pub fn get_name_score(query: &str, name: &str) -> usize {
+	let normalize = |string: &str| string.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect::<String>();
+	let name = normalize(name);
+
+	let mut score = 0;
+	let mut name_chars = name.chars();
+	for letter in normalize(query).chars() {
+		match name_chars.position(|c| c == letter) {
+			Some(pos) => score += 10usize.saturating_sub(pos),
+			None => return 0
+		}
+	}
+
+	score
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn letters_are_found_in_order() {
+		assert!(get_name_score("rust", "The Rust Book") > 0);
+		assert_eq!(get_name_score("tsur", "The Rust Book"), 0);
+		assert_eq!(get_name_score("rusty", "The Rust Book"), 0);
+	}
+
+	#[test]
+	fn case_and_whitespace_are_ignored() {
+		assert_eq!(get_name_score("vs code", "VSCode"), get_name_score("vscode", "VSCode"));
+		assert_eq!(get_name_score("GTK", "gtk"), get_name_score("gtk", "gtk"));
+	}
+
+	#[test]
+	fn closer_letters_score_more() {
+		assert!(get_name_score("gtk", "GTK") > get_name_score("gtk", "GNOME Toolkit"));
+	}
+}
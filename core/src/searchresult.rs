@@ -1,6 +1,10 @@
 
+#[cfg(feature = "gui")]
+use gtk::prelude::*;
+
 /**
  * Base search result trait.
+ * The widget methods are only available with the `gui` feature.
  */
 
 pub trait SearchResult {
@@ -13,6 +17,38 @@ pub trait SearchResult {
 	fn get_score(&self) -> usize;
 
 
+	/**
+	 * Returns an identifier for the result that is unique within its plugin,
+	 * and stable between searches, such as an application's desktop file id.
+	 */
+
+	fn get_id(&self) -> String;
+
+
+	/**
+	 * Returns the main name of the result, used when sorting results alphabetically.
+	 */
+
+	fn get_title(&self) -> String;
+
+
+	/**
+	 * Returns the secondary line of text describing the result,
+	 * such as an application's category or a directory's path.
+	 */
+
+	fn get_subtitle(&self) -> String;
+
+
+	/**
+	 * Returns the themed icon name or icon path representing the result, if it has one.
+	 */
+
+	fn get_icon_name(&self) -> Option<String> {
+		None
+	}
+
+
 	/**
 	 * Indicates that this result is the first result displayed,
 	 * which may trigger special focus / display behavior.
@@ -20,7 +56,76 @@ pub trait SearchResult {
 	 * it will instead be triggered with the `activate` method.
 	 */
 
-	fn set_first(&self, first: bool) -> ();
+	#[cfg(feature = "gui")]
+	fn set_first(&self, _first: bool) -> () {}
+
+
+	/**
+	 * Informs the result of its position in the displayed results,
+	 * where the first result has a rank of zero.
+	 */
+
+	fn set_rank(&self, _rank: usize) -> () {}
+
+
+	/**
+	 * Returns how often the result has been used, weighted towards recent uses.
+	 * Results that don't track their usage return zero.
+	 */
+
+	fn get_frequency(&self) -> f64 {
+		0.0
+	}
+
+
+	/**
+	 * Returns the time the result was last used, in seconds since the epoch,
+	 * or `None` if it has never been used or doesn't track its usage.
+	 */
+
+	fn get_last_used(&self) -> Option<u64> {
+		None
+	}
+
+
+	/**
+	 * Returns the category the result is grouped under when results are sorted by category, such as `GAME`.
+	 * Results without a category are listed after the others.
+	 */
+
+	fn get_category(&self) -> Option<String> {
+		None
+	}
+
+
+	/**
+	 * Returns the locale-independent name of the result's category, which `category_order` is matched against,
+	 * such as `AudioVideo` for a category displayed as `AUDIO VIDEO`. Defaults to the displayed category.
+	 */
+
+	fn get_category_key(&self) -> Option<String> {
+		self.get_category()
+	}
+
+
+	/**
+	 * Returns a description of how the result's score was computed for the last query, for debugging.
+	 * Results that don't explain their scores return `None`.
+	 */
+
+	fn explain_score(&self) -> Option<String> {
+		None
+	}
+
+
+	/**
+	 * Returns true if the result can be added to the favorites.
+	 * Transient results, such as calculator evaluations, should return false.
+	 */
+
+	fn is_pinnable(&self) -> bool {
+		true
+	}
 
 
 	/**
@@ -31,16 +136,112 @@ pub trait SearchResult {
 	fn activate(&self) -> ();
 
 
+	/**
+	 * Triggers the secondary action of the result, such as an application's first desktop action.
+	 * This is triggered by middle clicking a result, or with Shift+Enter on the selected result.
+	 */
+
+	fn activate_secondary(&self) -> () {
+		self.activate();
+	}
+
+
 	/**
 	 * Returns a widget representing the result in the results pane.
+	 * Defaults to an empty box, for results that are never displayed.
 	 */
 
-	fn get_result_widget(&self) -> gtk::Widget;
+	#[cfg(feature = "gui")]
+	fn get_result_widget(&self) -> gtk::Widget {
+		gtk::Box::new(gtk::Orientation::Horizontal, 0).upcast()
+	}
 
 
 	/**
 	 * Returns a widget representing the result in the preview pane.
+	 * Defaults to an empty box, for results without a preview.
 	 */
 
-	fn get_preview_widget(&self) -> gtk::Widget;
+	#[cfg(feature = "gui")]
+	fn get_preview_widget(&self) -> gtk::Widget {
+		gtk::Box::new(gtk::Orientation::Horizontal, 0).upcast()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+
+	#[derive(Default)]
+	struct MockResult {
+		secondary: bool,
+		activations: RefCell<Vec<&'static str>>
+	}
+
+	impl SearchResult for MockResult {
+		fn get_score(&self) -> usize { 0 }
+		fn get_id(&self) -> String { String::new() }
+		fn get_title(&self) -> String { String::new() }
+		fn get_subtitle(&self) -> String { String::new() }
+		fn activate(&self) { self.activations.borrow_mut().push("primary"); }
+		fn activate_secondary(&self) {
+			if self.secondary { self.activations.borrow_mut().push("secondary"); }
+			else { self.activate(); }
+		}
+	}
+
+	struct PrimaryOnly(RefCell<usize>);
+
+	impl SearchResult for PrimaryOnly {
+		fn get_score(&self) -> usize { 0 }
+		fn get_id(&self) -> String { String::new() }
+		fn get_title(&self) -> String { String::new() }
+		fn get_subtitle(&self) -> String { String::new() }
+		fn activate(&self) { *self.0.borrow_mut() += 1; }
+	}
+
+	#[test]
+	fn secondary_activation_defaults_to_the_primary_one() {
+		let result = PrimaryOnly(RefCell::new(0));
+		result.activate_secondary();
+		assert_eq!(*result.0.borrow(), 1);
+	}
+
+	#[test]
+	fn overridden_secondary_activation_fires_instead() {
+		let result = MockResult { secondary: true, ..Default::default() };
+		result.activate_secondary();
+		result.activate();
+		assert_eq!(*result.activations.borrow(), vec![ "secondary", "primary" ]);
+
+		let result = MockResult::default();
+		result.activate_secondary();
+		assert_eq!(*result.activations.borrow(), vec![ "primary" ]);
+	}
+
+	#[test]
+	fn defaults_describe_a_pinnable_result_without_a_category() {
+		let result = PrimaryOnly(RefCell::new(0));
+		assert_eq!(result.get_category_key(), None);
+		assert_eq!(result.explain_score(), None);
+		assert!(result.is_pinnable());
+	}
+
+	struct Categorized;
+
+	impl SearchResult for Categorized {
+		fn get_score(&self) -> usize { 0 }
+		fn get_id(&self) -> String { String::new() }
+		fn get_title(&self) -> String { String::new() }
+		fn get_subtitle(&self) -> String { String::new() }
+		fn get_category(&self) -> Option<String> { Some("AUDIO VIDEO".to_owned()) }
+		fn activate(&self) {}
+	}
+
+	#[test]
+	fn category_keys_default_to_the_displayed_category() {
+		assert_eq!(Categorized.get_category_key(), Some("AUDIO VIDEO".to_owned()));
+	}
 }
@@ -0,0 +1,78 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/**
+ * Shortens a display string to at most `max` graphemes, replacing the end with an ellipsis if it is too long.
+ * Graphemes are never split, so multi-byte characters, combining marks, and emoji sequences stay intact.
+ */
+
+pub fn truncate_graphemes(text: &str, max: usize) -> String {
+	if max == 0 { return String::new(); }
+
+	let mut graphemes = text.grapheme_indices(true);
+	match graphemes.nth(max) {
+		None => text.to_owned(),
+		Some(_) => {
+			let end = text.grapheme_indices(true).nth(max - 1).map(|(ind, _)| ind).unwrap_or(text.len());
+			[ &text[..end], "…" ].join("")
+		}
+	}
+}
+
+
+/**
+ * Escapes the characters of a string that have a meaning in Pango markup, so that user provided text,
+ * like a desktop entry's comment, can be placed inside markup spans without breaking them.
+ */
+
+pub fn escape_markup(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&apos;"),
+			_ => escaped.push(c)
+		}
+	}
+	escaped
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn short_strings_are_unchanged() {
+		assert_eq!(truncate_graphemes("Firefox", 7), "Firefox");
+		assert_eq!(truncate_graphemes("Café", 10), "Café");
+		assert_eq!(truncate_graphemes("Firefox", 0), "");
+	}
+
+	#[test]
+	fn multi_byte_strings_are_truncated_by_grapheme() {
+		assert_eq!(truncate_graphemes("Écran de démarrage", 6), "Écran…");
+		assert_eq!(truncate_graphemes("日本語のテキスト", 4), "日本語…");
+		assert_eq!(truncate_graphemes("cafe\u{301} noir", 5), "cafe\u{301}…");
+		assert_eq!(truncate_graphemes("cafe\u{301} noir", 5).graphemes(true).count(), 5);
+	}
+
+	#[test]
+	fn emoji_sequences_are_never_split() {
+		let family = "👨‍👩‍👧‍👦";
+		let text = [ family, family, "🇫🇷", "🇫🇷" ].join("");
+		assert_eq!(truncate_graphemes(&text, 3), [ family, family, "…" ].join(""));
+		assert_eq!(truncate_graphemes(&text, 4), text);
+		assert_eq!(truncate_graphemes("👍🏽👍🏽👍🏽", 2), "👍🏽…");
+	}
+
+
+	#[test]
+	fn markup_characters_are_escaped() {
+		assert_eq!(escape_markup(r#"Tom & Jerry <"Cartoons"> 'Classic'"#), "Tom &amp; Jerry &lt;&quot;Cartoons&quot;&gt; &apos;Classic&apos;");
+		assert_eq!(escape_markup("&amp;"), "&amp;amp;");
+		assert_eq!(escape_markup("Café"), "Café");
+	}
+}
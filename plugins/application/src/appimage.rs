@@ -0,0 +1,97 @@
+/*!
+ * Discovery of AppImage files, which don't install desktop files, in the directories set by `appimage_dirs`.
+ * Their names come from the file name, as reading the embedded desktop file means running or unpacking the image.
+ */
+
+use std::path::{ Path, PathBuf };
+
+use crate::launch;
+
+/** The parts of AppImage file names that name an architecture instead of the application. */
+const ARCHITECTURES: &[&str] = &[ "x86_64", "x64", "amd64", "aarch64", "arm64", "armhf", "i386", "i686" ];
+
+
+/**
+ * Derives an application's name and version from an AppImage file name, like `Kdenlive` and `23.08.1`
+ * for `Kdenlive-23.08.1-x86_64.AppImage`. Words before the version are joined with spaces,
+ * and the whole file name without its extension is the name if it starts with a version.
+ */
+
+pub fn get_name(file_name: &str) -> (String, Option<String>) {
+	let split = file_name.len().saturating_sub(".appimage".len());
+	let stem = match (file_name.get(..split), file_name.get(split..)) {
+		(Some(stem), Some(extension)) if extension.eq_ignore_ascii_case(".appimage") => stem,
+		_ => file_name
+	};
+	let is_version = |part: &str| part.trim_start_matches(|c| c == 'v' || c == 'V').starts_with(|c: char| c.is_ascii_digit());
+
+	// Architectures like `x86_64` contain an underscore, so they're kept whole instead of being split into words.
+	let parts: Vec<&str> = stem.split('-')
+		.flat_map(|part| if ARCHITECTURES.contains(&part.to_lowercase().as_str()) { vec![ part ] } else { part.split('_').collect() })
+		.filter(|part| !part.is_empty()).collect();
+	let end = parts.iter().position(|part| is_version(part) || ARCHITECTURES.contains(&part.to_lowercase().as_str())).unwrap_or(parts.len());
+
+	let version = parts.get(end).filter(|part| is_version(part)).map(|part| part.trim_start_matches(|c| c == 'v' || c == 'V').to_owned());
+	let name = parts[..end].join(" ");
+	(if name.is_empty() { stem.to_owned() } else { name }, version)
+}
+
+
+/**
+ * Finds an icon for an AppImage, which is a `.png` or `.svg` file next to it with the same name if there is one.
+ */
+
+pub fn find_icon(path: &Path) -> Option<PathBuf> {
+	[ "png", "svg" ].iter().map(|extension| path.with_extension(extension)).find(|icon| icon.is_file())
+}
+
+
+/**
+ * Returns the executable `.AppImage` files directly inside the directories, which have `~` and variables expanded.
+ * Directories that don't exist are skipped silently, as the defaults often don't.
+ */
+
+pub fn find(dirs: &[String]) -> Vec<PathBuf> {
+	let mut found = vec![];
+	for dir in dirs.iter().map(|dir| PathBuf::from(launch::expand_env(dir))) {
+		let entries = match std::fs::read_dir(&dir) {
+			Ok(entries) => entries,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+			Err(err) => { println!("[WARN] Failed to read '{}': {}", dir.display(), err); continue; }
+		};
+
+		found.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path())
+			.filter(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("appimage")))
+			.filter(|path| launch::is_executable(path)));
+	}
+	found.sort();
+	found
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_fixture() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/appimages")
+	}
+
+	#[test]
+	fn names_and_versions_come_from_the_file_name() {
+		assert_eq!(get_name("Kdenlive-23.08.1-x86_64.AppImage"), ("Kdenlive".to_owned(), Some("23.08.1".to_owned())));
+		assert_eq!(get_name("Visual_Studio_Code-v1.85.appimage"), ("Visual Studio Code".to_owned(), Some("1.85".to_owned())));
+		assert_eq!(get_name("Obsidian-x86_64.AppImage"), ("Obsidian".to_owned(), None));
+		assert_eq!(get_name("1.0-x86_64.AppImage"), ("1.0-x86_64".to_owned(), Some("1.0".to_owned())));
+		assert_eq!(get_name("tool"), ("tool".to_owned(), None));
+	}
+
+	#[test]
+	fn only_executable_appimages_are_found() {
+		let fixture = get_fixture();
+		assert_eq!(find(&[ fixture.to_string_lossy().into_owned(), "/scout/missing".to_owned() ]),
+			vec![ fixture.join("Kdenlive-23.08.1-x86_64.AppImage"), fixture.join("Obsidian.appimage") ]);
+		assert_eq!(find_icon(&fixture.join("Kdenlive-23.08.1-x86_64.AppImage")), Some(fixture.join("Kdenlive-23.08.1-x86_64.png")));
+		assert_eq!(find_icon(&fixture.join("Obsidian.appimage")), None);
+	}
+}
@@ -0,0 +1,99 @@
+/*!
+ * A bounded cache of the scores of recent queries, so that retyping a query doesn't score every application again.
+ */
+
+use std::collections::VecDeque;
+
+/** The number of queries whose scores are kept. */
+pub const CACHE_CAPACITY: usize = 64;
+
+/**
+ * Stores the index and match score of each result that matched a query, for the most recent queries.
+ * Scores don't include launch history, which can change between queries.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct ScoreCache {
+	entries: VecDeque<(String, Vec<(usize, usize)>)>
+}
+
+impl ScoreCache {
+
+	/**
+	 * Gets the scores of a query, if it is cached.
+	 */
+
+	pub fn get(&self, query: &str) -> Option<&Vec<(usize, usize)>> {
+		self.entries.iter().find(|(cached, _)| cached == query).map(|(_, scores)| scores)
+	}
+
+
+	/**
+	 * Caches the scores of a query, removing the oldest query if the cache is full.
+	 */
+
+	pub fn insert(&mut self, query: &str, scores: Vec<(usize, usize)>) {
+		if self.entries.len() >= CACHE_CAPACITY { self.entries.pop_front(); }
+		self.entries.push_back((query.to_owned(), scores));
+	}
+
+
+	/**
+	 * Gets the scores of a query, scoring it with `score` and caching the scores if it isn't cached.
+	 */
+
+	pub fn get_or_insert_with<F: FnOnce() -> Vec<(usize, usize)>>(&mut self, query: &str, score: F) -> Vec<(usize, usize)> {
+		if let Some(scores) = self.get(query) { return scores.clone(); }
+
+		let scores = score();
+		self.insert(query, scores.clone());
+		scores
+	}
+
+
+	/**
+	 * Removes every cached query, which must be done whenever the results or the preferences change.
+	 */
+
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn repeated_queries_are_only_scored_once() {
+		let mut cache = ScoreCache::default();
+		let scored = std::cell::Cell::new(0);
+		let score = |cache: &mut ScoreCache, query: &str| cache.get_or_insert_with(query, || {
+			scored.set(scored.get() + 1);
+			vec![ (0, query.len()) ]
+		});
+
+		assert_eq!(score(&mut cache, "fire"), vec![ (0, 4) ]);
+		assert_eq!(score(&mut cache, "fire"), vec![ (0, 4) ]);
+		assert_eq!(scored.get(), 1);
+
+		score(&mut cache, "fir");
+		score(&mut cache, "fire");
+		assert_eq!(scored.get(), 2);
+
+		cache.clear();
+		score(&mut cache, "fire");
+		assert_eq!(scored.get(), 3);
+	}
+
+	#[test]
+	fn the_oldest_query_is_removed_when_full() {
+		let mut cache = ScoreCache::default();
+		for ind in 0..=CACHE_CAPACITY { cache.insert(&ind.to_string(), vec![ (ind, 1) ]); }
+
+		assert_eq!(cache.get("0"), None);
+		assert_eq!(cache.get("1"), Some(&vec![ (1, 1) ]));
+		assert_eq!(cache.get(&CACHE_CAPACITY.to_string()), Some(&vec![ (CACHE_CAPACITY, 1) ]));
+	}
+}
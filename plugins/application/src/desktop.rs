@@ -0,0 +1,76 @@
+/*!
+ * Decides whether entries are shown in the current desktop environment, from their `OnlyShowIn` and `NotShowIn` keys.
+ */
+
+use serde::{ Serialize, Deserialize };
+
+use crate::list;
+
+/**
+ * How entries with `OnlyShowIn` are treated when `$XDG_CURRENT_DESKTOP` is unset, as it often is with minimal window managers.
+ * Permissive shows them, so that applications don't vanish, and strict hides them as no desktop is listed.
+ */
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnknownDesktop {
+	#[default]
+	Permissive,
+	Strict
+}
+
+
+/**
+ * Returns the names of the current desktop environment from the colon-separated `$XDG_CURRENT_DESKTOP`,
+ * which is empty if it is unset.
+ */
+
+pub fn get_current_desktops() -> Vec<String> {
+	std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default()
+		.split(':').map(|desktop| desktop.trim()).filter(|desktop| !desktop.is_empty())
+		.map(|desktop| desktop.to_owned()).collect()
+}
+
+
+/**
+ * Returns true if an entry with the `OnlyShowIn` and `NotShowIn` lists should be shown in the desktops, ignoring case.
+ * With no current desktop, `NotShowIn` never hides an entry, and `OnlyShowIn` hides it only if `unknown` is strict.
+ */
+
+pub fn is_shown_in(only_show_in: Option<&str>, not_show_in: Option<&str>, desktops: &[String], unknown: UnknownDesktop) -> bool {
+	let only_show_in = list::parse_list(only_show_in);
+	let not_show_in = list::parse_list(not_show_in);
+	let listed = |list: &[String]| desktops.iter().any(|desktop| list.iter().any(|listed| listed.eq_ignore_ascii_case(desktop)));
+
+	if desktops.is_empty() { return only_show_in.is_empty() || unknown == UnknownDesktop::Permissive; }
+	if listed(&not_show_in) { return false; }
+	only_show_in.is_empty() || listed(&only_show_in)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unset_desktops_show_not_show_in_entries() {
+		assert!(is_shown_in(None, Some("GNOME;KDE;"), &[], UnknownDesktop::Permissive));
+		assert!(is_shown_in(None, Some("GNOME;KDE;"), &[], UnknownDesktop::Strict));
+	}
+
+	#[test]
+	fn unset_desktops_only_hide_only_show_in_entries_when_strict() {
+		assert!(is_shown_in(Some("XFCE;"), None, &[], UnknownDesktop::Permissive));
+		assert!(!is_shown_in(Some("XFCE;"), None, &[], UnknownDesktop::Strict));
+		assert!(is_shown_in(None, None, &[], UnknownDesktop::Strict));
+	}
+
+	#[test]
+	fn current_desktops_are_matched_ignoring_case() {
+		let desktops = vec![ "ubuntu".to_owned(), "GNOME".to_owned() ];
+		assert!(is_shown_in(Some("gnome;"), None, &desktops, UnknownDesktop::Strict));
+		assert!(!is_shown_in(Some("KDE;"), None, &desktops, UnknownDesktop::Permissive));
+		assert!(!is_shown_in(None, Some("Ubuntu;"), &desktops, UnknownDesktop::Permissive));
+		assert!(is_shown_in(None, Some("KDE;"), &desktops, UnknownDesktop::Strict));
+	}
+}
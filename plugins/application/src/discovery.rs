@@ -0,0 +1,352 @@
+/*!
+ * Searching the application directories for desktop entries, and a cache of the entries found,
+ * so that starting Scout doesn't need to search every directory again when nothing was installed or removed.
+ * The cache can be written ahead of time with `scout --prewarm`, which needs no display.
+ */
+
+use freedesktop_entry_parser::{ Entry, AttrSelector };
+use serde::{ Serialize, Deserialize };
+use std::ffi::OsStr;
+use std::path::{ Path, PathBuf };
+
+use crate::error::ScoutError;
+use crate::preferences::Preferences;
+
+/**
+ * Returns the directories applications are discovered in, from `$XDG_DATA_DIRS` and the user's data directory.
+ * Directories that don't exist, like broken symlinks, are skipped.
+ */
+
+pub fn get_search_paths() -> Vec<PathBuf> {
+	let mut search_paths: Vec<PathBuf> = std::env::var("XDG_DATA_DIRS")
+		.map(|string| string.split(':').map(|string| format!("{}/applications", string).into()).collect::<Vec<PathBuf>>())
+		.unwrap_or_else(|_| vec![]);
+	if let Some(dir) = dirs::data_dir() { search_paths.push(dir.join("applications")); }
+	else { search_paths.push(format!("/home/{}/.local/share/applications", &whoami::username()).into()); }
+
+	search_paths.retain(|path| path.is_dir());
+	search_paths
+}
+
+
+/**
+ * Returns the path of the discovery cache in the user's cache directory, if there is one.
+ */
+
+pub fn get_cache_path() -> Option<PathBuf> {
+	dirs::cache_dir().map(|dir| dir.join("scout/applications.json"))
+}
+
+
+/**
+ * The desktop entries found in the application directories, along with the directories that were searched.
+ * It is only valid for the same search paths and limits, and while none of its directories have changed since it was created.
+ */
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscoveryCache {
+	pub search_paths: Vec<PathBuf>,
+	pub max_depth: usize,
+	pub max_files: usize,
+
+	/**
+	 * Every directory that was read, including the search paths, with when it was last modified.
+	 * Adding or removing an entry modifies its directory, which invalidates the cache.
+	 */
+	pub directories: Vec<(PathBuf, std::time::SystemTime)>,
+
+	/** The desktop entries found, in the order they were found. */
+	pub files: Vec<PathBuf>
+}
+
+impl DiscoveryCache {
+
+	/**
+	 * Searches the directories specified for desktop entries, up to `max_depth` directories deep,
+	 * stopping once `max_files` files have been looked at.
+	 */
+
+	pub fn find(search_paths: &[PathBuf], max_depth: usize, max_files: usize) -> Self {
+		let mut cache = DiscoveryCache { search_paths: search_paths.to_vec(), max_depth, max_files, ..Default::default() };
+
+		let mut search_paths: Vec<(PathBuf, usize)> = search_paths.iter().map(|path| (path.clone(), 0)).collect();
+		let mut scanned = 0;
+
+		'search: while let Some((path, depth)) = search_paths.pop() {
+			let (dir_iter, modified) = match std::fs::read_dir(&path).and_then(|dir_iter| Ok((dir_iter, get_modified(&path)?))) {
+				Ok(read) => read,
+				Err(err) => { println!("[WARN] Failed to read '{}': {}", path.display(), err); continue; }
+			};
+			cache.directories.push((path.clone(), modified));
+
+			for entry in dir_iter {
+				scanned += 1;
+				if scanned > max_files {
+					println!("[WARN] Stopped searching for applications after {} files.", max_files);
+					break 'search;
+				}
+
+				let entry = match entry {
+					Ok(entry) => entry,
+					Err(err) => { println!("[WARN] Failed to read an entry of '{}': {}", path.display(), err); continue; }
+				};
+				let path = entry.path();
+
+				if path.is_dir() {
+					if depth < max_depth { search_paths.push((path, depth + 1)); }
+					else { println!("[WARN] Not searching '{}' for applications, it is nested too deeply.", path.display()); }
+				}
+				else if path.extension() == Some(OsStr::new("desktop")) {
+					cache.files.push(path);
+				}
+			}
+		}
+
+		cache
+	}
+
+
+	/**
+	 * Returns true if the cache was created with the same search paths and limits,
+	 * and none of its directories have been modified since.
+	 */
+
+	pub fn is_valid(&self, search_paths: &[PathBuf], max_depth: usize, max_files: usize) -> bool {
+		self.search_paths == search_paths && self.max_depth == max_depth && self.max_files == max_files
+			&& self.directories.iter().all(|(dir, modified)| get_modified(dir).ok().as_ref() == Some(modified))
+	}
+
+
+	/**
+	 * Reads a cache, returning None if it doesn't exist or can't be parsed.
+	 */
+
+	pub fn load(path: &Path) -> Option<Self> {
+		serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+	}
+
+
+	/**
+	 * Writes the cache, creating its directory if needed.
+	 */
+
+	pub fn save(&self, path: &Path) -> std::io::Result<()> {
+		if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+		std::fs::write(path, serde_json::to_string(self)?)
+	}
+}
+
+
+/**
+ * Returns when a directory was last modified.
+ */
+
+fn get_modified(path: &Path) -> std::io::Result<std::time::SystemTime> {
+	std::fs::metadata(path)?.modified()
+}
+
+
+/**
+ * Returns the command of a section of a desktop entry. An empty `Exec=` has nothing to launch, so it is treated as missing.
+ */
+
+pub fn get_exec<'a, T: AsRef<str>>(section: &AttrSelector<'a, T>) -> Option<&'a str> {
+	section.attr("Exec").filter(|exec| !exec.trim().is_empty())
+}
+
+
+/**
+ * Returns true if a desktop entry can be launched, which entries marked `Hidden` never can.
+ * Entries marked `NoDisplay` are hidden from menus, so they are only launchable if `include_no_display` is true.
+ */
+
+pub fn is_launchable<T: AsRef<str>>(section: &AttrSelector<'_, T>, include_no_display: bool) -> bool {
+	let hidden = section.attr("Hidden").unwrap_or("false") != "false";
+	let no_display = section.attr("NoDisplay").unwrap_or("false") != "false";
+	!hidden && (!no_display || include_no_display)
+}
+
+
+/**
+ * Returns the section of a desktop action, like `[Desktop Action new-window]`.
+ */
+
+pub fn get_action_section<'a>(parsed: &'a Entry, action: &str) -> AttrSelector<'a, String> {
+	parsed.section([ "Desktop Action", action ].join(" "))
+}
+
+
+/**
+ * Returns the desktop entries in the application directories, from the cache if it is valid.
+ * Otherwise the directories are searched, and the cache is written for the next start.
+ * Returns an error if there are no application directories, or none of them could be read.
+ */
+
+pub fn discover(search_paths: &[PathBuf], preferences: &Preferences, cache_path: Option<&Path>) -> Result<Vec<PathBuf>, ScoutError> {
+	let (max_depth, max_files) = (preferences.max_scan_depth, preferences.max_scan_files);
+	if search_paths.is_empty() { return Err(ScoutError::Config("There are no application directories to search.".to_owned())); }
+
+	if let Some(cache) = cache_path.and_then(DiscoveryCache::load) {
+		if cache.is_valid(search_paths, max_depth, max_files) { return Ok(cache.files); }
+	}
+
+	let cache = DiscoveryCache::find(search_paths, max_depth, max_files);
+	if !search_paths.iter().any(|path| cache.directories.iter().any(|(dir, _)| dir == path)) {
+		return Err(ScoutError::Io(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "None of the application directories could be read")));
+	}
+
+	if let Some(path) = cache_path {
+		if let Err(err) = cache.save(path) { println!("[WARN] Failed to save '{}': {}", path.display(), err); }
+	}
+	Ok(cache.files)
+}
+
+
+/**
+ * Removes the discovery cache, so that the next discovery searches every directory again
+ * even if the cache looks valid, such as after files were edited without changing their directories.
+ */
+
+pub fn remove_cache(cache_path: &Path) -> std::io::Result<()> {
+	match std::fs::remove_file(cache_path) {
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		result => result
+	}
+}
+
+
+/**
+ * Searches the directories specified and writes the discovery cache, returning the cache that was written.
+ */
+
+pub fn prewarm(search_paths: &[PathBuf], max_depth: usize, max_files: usize, cache_path: &Path) -> Result<DiscoveryCache, ScoutError> {
+	let cache = DiscoveryCache::find(search_paths, max_depth, max_files);
+	cache.save(cache_path)?;
+	Ok(cache)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_fixture() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/applications")
+	}
+
+	fn get_cache_path(name: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("scout-{}-{}/applications.json", name, std::process::id()))
+	}
+
+	#[test]
+	fn prewarm_writes_a_valid_cache() {
+		let search_paths = vec![ get_fixture() ];
+		let cache_path = get_cache_path("prewarm");
+
+		let written = prewarm(&search_paths, 8, 20000, &cache_path).unwrap();
+		let cache = DiscoveryCache::load(&cache_path).unwrap();
+		std::fs::remove_dir_all(cache_path.parent().unwrap()).unwrap();
+
+		assert_eq!(cache, written);
+		assert!(cache.is_valid(&search_paths, 8, 20000));
+
+		let mut files: Vec<_> = cache.files.iter().map(|file| file.strip_prefix(get_fixture()).unwrap().to_owned()).collect();
+		files.sort();
+		assert_eq!(files, vec![ PathBuf::from("editor.desktop"), PathBuf::from("kde/terminal.desktop"), PathBuf::from("viewer.desktop") ]);
+	}
+
+	#[test]
+	fn rescanning_ignores_and_removes_the_cache() {
+		let search_paths = vec![ get_fixture() ];
+		let cache_path = get_cache_path("rescan");
+		let preferences: Preferences = serde_json::from_str("{}").unwrap();
+
+		let mut stale = DiscoveryCache::find(&search_paths, preferences.max_scan_depth, preferences.max_scan_files);
+		stale.files.push(get_fixture().join("removed.desktop"));
+		stale.save(&cache_path).unwrap();
+		assert_eq!(discover(&search_paths, &preferences, Some(&cache_path)).unwrap(), stale.files);
+
+		remove_cache(&cache_path).unwrap();
+		assert!(!cache_path.exists());
+		let files = discover(&search_paths, &preferences, Some(&cache_path)).unwrap();
+		let saved = DiscoveryCache::load(&cache_path).unwrap();
+		std::fs::remove_dir_all(cache_path.parent().unwrap()).unwrap();
+
+		assert_eq!(files.len(), 3);
+		assert_eq!(saved.files, files);
+		assert!(remove_cache(&cache_path).is_ok());
+	}
+
+	#[test]
+	fn file_search_roots_are_skipped() {
+		let file = get_fixture().join("notes.txt");
+		let preferences: Preferences = serde_json::from_str("{}").unwrap();
+
+		let cache = DiscoveryCache::find(&[ file.clone(), get_fixture() ], 8, 20000);
+		assert_eq!(cache.files.len(), 3);
+		assert!(cache.directories.iter().all(|(dir, _)| *dir != file));
+
+		assert_eq!(discover(&[ file.clone(), get_fixture() ], &preferences, None).unwrap().len(), 3);
+		assert!(matches!(discover(&[ file ], &preferences, None), Err(ScoutError::Io(_))));
+	}
+
+	#[test]
+	fn cache_is_invalid_for_other_limits() {
+		let search_paths = vec![ get_fixture() ];
+		let cache = DiscoveryCache::find(&search_paths, 8, 20000);
+
+		assert!(!cache.is_valid(&search_paths, 0, 20000));
+		assert!(!cache.is_valid(&search_paths, 8, 10));
+		assert!(!cache.is_valid(&[], 8, 20000));
+	}
+
+	#[test]
+	fn no_display_entries_are_launchable_when_included() {
+		let no_display = Entry::parse("[Desktop Entry]\nName=Helper\nExec=helper\nNoDisplay=true\n").unwrap();
+		let hidden = Entry::parse("[Desktop Entry]\nName=Removed\nExec=removed\nHidden=true\n").unwrap();
+
+		assert!(!is_launchable(&no_display.section("Desktop Entry"), false));
+		assert!(is_launchable(&no_display.section("Desktop Entry"), true));
+		assert!(!is_launchable(&hidden.section("Desktop Entry"), true));
+	}
+
+	#[test]
+	fn empty_commands_are_missing() {
+		let parsed = Entry::parse("[Desktop Entry]\nName=Empty\nExec=\nActions=blank;spaces;open;\n\n\
+			[Desktop Action blank]\nName=Blank\nExec=\n\n[Desktop Action spaces]\nName=Spaces\nExec=   \n\n\
+			[Desktop Action open]\nName=Open\nExec=empty --open\n").unwrap();
+
+		assert_eq!(get_exec(&parsed.section("Desktop Entry")), None);
+		assert_eq!(get_exec(&get_action_section(&parsed, "blank")), None);
+		assert_eq!(get_exec(&get_action_section(&parsed, "spaces")), None);
+		assert_eq!(get_exec(&get_action_section(&parsed, "open")), Some("empty --open"));
+	}
+
+	#[test]
+	fn limits_are_honoured() {
+		let search_paths = vec![ get_fixture() ];
+		assert_eq!(DiscoveryCache::find(&search_paths, 0, 20000).files.len(), 2);
+		assert!(DiscoveryCache::find(&search_paths, 8, 1).files.len() <= 1);
+	}
+
+	fn find_deep(max_depth: usize, max_files: usize) -> Vec<PathBuf> {
+		let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/deep");
+		let mut files: Vec<_> = DiscoveryCache::find(std::slice::from_ref(&fixture), max_depth, max_files).files.iter()
+			.map(|file| file.strip_prefix(&fixture).unwrap().to_owned()).collect();
+		files.sort();
+		files
+	}
+
+	#[test]
+	fn nested_directories_are_searched_up_to_the_depth_limit() {
+		assert_eq!(find_deep(0, 20000), vec![ PathBuf::from("top.desktop") ]);
+		assert_eq!(find_deep(2, 20000), vec![ PathBuf::from("one/one.desktop"), PathBuf::from("one/two/two.desktop"), PathBuf::from("top.desktop") ]);
+		assert_eq!(find_deep(8, 20000).len(), 4);
+	}
+
+	#[test]
+	fn searching_stops_at_the_file_limit() {
+		assert_eq!(find_deep(8, 0), Vec::<PathBuf>::new());
+		assert!(find_deep(8, 3).len() < 4);
+	}
+}
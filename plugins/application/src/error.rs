@@ -0,0 +1,75 @@
+/*!
+ * The errors that can occur while discovering and launching applications.
+ */
+
+/**
+ * Represents a failure in discovering or launching an application.
+ */
+
+#[derive(Debug)]
+pub enum ScoutError {
+
+	/** Used when a desktop entry or one of its values is malformed. */
+	Parse(String),
+
+	/** Used when a file or directory can't be read or written. */
+	Io(std::io::Error),
+
+	/** Used when a command can't be resolved or spawned. */
+	Launch(String),
+
+	/** Used when the preferences can't be read. */
+	Config(String)
+}
+
+impl std::fmt::Display for ScoutError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ScoutError::Parse(msg) => write!(f, "Parse error: {}", msg),
+			ScoutError::Io(err) => write!(f, "IO error: {}", err),
+			ScoutError::Launch(msg) => write!(f, "Launch error: {}", msg),
+			ScoutError::Config(msg) => write!(f, "Config error: {}", msg)
+		}
+	}
+}
+
+impl std::error::Error for ScoutError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			ScoutError::Io(err) => Some(err),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for ScoutError {
+	fn from(err: std::io::Error) -> ScoutError {
+		ScoutError::Io(err)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::error::Error;
+
+	#[test]
+	fn errors_describe_their_kind() {
+		assert_eq!(ScoutError::Parse("Unbalanced quotes".to_owned()).to_string(), "Parse error: Unbalanced quotes");
+		assert_eq!(ScoutError::Launch("Executable 'foo' not found.".to_owned()).to_string(), "Launch error: Executable 'foo' not found.");
+		assert_eq!(ScoutError::Config("Invalid preferences".to_owned()).to_string(), "Config error: Invalid preferences");
+		assert_eq!(ScoutError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing")).to_string(), "IO error: missing");
+	}
+
+	#[test]
+	fn only_io_errors_have_a_source() {
+		let err = ScoutError::from(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"));
+		assert!(matches!(err, ScoutError::Io(_)));
+		assert_eq!(err.source().unwrap().to_string(), "denied");
+
+		assert!(ScoutError::Parse(String::new()).source().is_none());
+		assert!(ScoutError::Launch(String::new()).source().is_none());
+		assert!(ScoutError::Config(String::new()).source().is_none());
+	}
+}
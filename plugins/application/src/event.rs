@@ -0,0 +1,72 @@
+/*!
+ * Structured activation events, for integrations that want to track launches.
+ * Events are only emitted when enabled in the plugin's preferences.
+ */
+
+use serde::Serialize;
+use std::io::Write;
+
+/**
+ * A record of a single result activation, written as one line of JSON.
+ */
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ActivationEvent<'a> {
+	pub id: &'a str,
+	pub kind: &'a str,
+	pub query: &'a str,
+	pub timestamp: u64,
+	pub rank: usize
+}
+
+impl<'a> ActivationEvent<'a> {
+
+	/**
+	 * Creates a new activation event, timestamped with the current time in seconds since the epoch.
+	 */
+
+	pub fn new(id: &'a str, kind: &'a str, query: &'a str, rank: usize) -> Self {
+		let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+			.map(|time| time.as_secs()).unwrap_or(0);
+		ActivationEvent { id, kind, query, timestamp, rank }
+	}
+
+
+	/**
+	 * Appends the event to the file at `path`, or writes it to stdout if `path` is `-`.
+	 */
+
+	pub fn emit(&self, path: &str) -> std::io::Result<()> {
+		let mut line = serde_json::to_string(&self)?;
+		line.push('\n');
+
+		if path == "-" { std::io::stdout().write_all(line.as_bytes()) }
+		else { std::fs::OpenOptions::new().create(true).append(true).open(path)?.write_all(line.as_bytes()) }
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn emit_appends_one_json_line() {
+		let path = std::env::temp_dir().join(format!("scout-events-{}.jsonl", std::process::id()));
+		let path_str = path.to_str().unwrap();
+
+		ActivationEvent::new("org.gnome.Nautilus", "program", "files", 0).emit(path_str).unwrap();
+		ActivationEvent::new("firefox", "action", "fire", 2).emit(path_str).unwrap();
+		let contents = std::fs::read_to_string(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		let lines: Vec<serde_json::Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(lines.len(), 2);
+		assert_eq!(lines[0]["id"], "org.gnome.Nautilus");
+		assert_eq!(lines[0]["kind"], "program");
+		assert_eq!(lines[0]["query"], "files");
+		assert_eq!(lines[0]["rank"], 0);
+		assert!(lines[0]["timestamp"].as_u64().unwrap() > 0);
+		assert_eq!(lines[1]["rank"], 2);
+	}
+}
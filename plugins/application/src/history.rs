@@ -0,0 +1,176 @@
+/*!
+ * A log of application launches, used to rank frequently and recently used applications higher.
+ */
+
+use std::collections::HashMap;
+use std::io::{ Read, Write };
+
+use scout_core::Shared;
+
+/** The most launches kept for each application. Older launches barely count after decaying, so they are dropped. */
+pub const MAX_LAUNCHES_PER_APP: usize = 100;
+
+/**
+ * Stores the times of the most recent launches of each application, in seconds since the epoch, oldest first.
+ * The history file is an append-only log, which is compacted once it holds twice as many launches as are kept.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+	path: std::path::PathBuf,
+	launches: HashMap<String, Vec<u64>>,
+	logged: usize
+}
+
+impl History {
+
+	/**
+	 * Returns the current time in seconds since the epoch.
+	 */
+
+	pub fn now() -> u64 {
+		std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+			.map(|time| time.as_secs()).unwrap_or(0)
+	}
+
+
+	/**
+	 * Loads the launch history from the file specified, or from `$XDG_DATA_HOME/scout/history`.
+	 * Malformed lines are skipped, and the file is compacted if it has grown too large.
+	 */
+
+	pub fn new(path: Option<&std::path::Path>) -> Shared<Self> {
+		let path = path
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| dirs::data_dir()
+				.map(|dir| dir.join("scout/history"))
+				.unwrap_or_else(|| std::path::Path::new(&[ "/home/", &whoami::username(), "/.local/share/scout/history" ].join(""))
+				.to_owned()));
+
+		let mut contents = String::new();
+		if let Ok(mut file) = std::fs::File::open(&path) { drop(file.read_to_string(&mut contents)); }
+
+		let mut history = History { path, launches: HashMap::new(), logged: 0 };
+		for line in contents.lines() {
+			let mut parts = line.splitn(2, ' ');
+			let timestamp = match parts.next().and_then(|timestamp| timestamp.parse::<u64>().ok()) {
+				Some(timestamp) => timestamp,
+				None => continue
+			};
+			if let Some(id) = parts.next() { history.add(timestamp, id); }
+			history.logged += 1;
+		}
+
+		for launches in history.launches.values_mut() { launches.sort_unstable(); }
+		if let Err(err) = history.compact_if_needed() { println!("[WARN] Failed to compact the launch history: {}", err); }
+		Shared::new(history)
+	}
+
+
+	/**
+	 * Adds a launch, dropping the oldest launch of the application if it has too many.
+	 */
+
+	fn add(&mut self, timestamp: u64, id: &str) {
+		let launches = self.launches.entry(id.to_owned()).or_default();
+		launches.push(timestamp);
+		if launches.len() > MAX_LAUNCHES_PER_APP {
+			launches.sort_unstable();
+			launches.remove(0);
+		}
+	}
+
+
+	/**
+	 * Rewrites the history file with only the launches that are kept, if it logs twice as many launches or more.
+	 */
+
+	fn compact_if_needed(&mut self) -> std::io::Result<()> {
+		let kept: usize = self.launches.values().map(|launches| launches.len()).sum();
+		if self.logged < 2 * kept.max(MAX_LAUNCHES_PER_APP) { return Ok(()); }
+
+		let mut launches: Vec<(u64, &str)> = self.launches.iter()
+			.flat_map(|(id, launches)| launches.iter().map(move |timestamp| (*timestamp, id.as_str()))).collect();
+		launches.sort_unstable();
+
+		let contents: String = launches.iter().map(|(timestamp, id)| format!("{} {}\n", timestamp, id)).collect();
+		std::fs::write(&self.path, contents)?;
+		self.logged = kept;
+		Ok(())
+	}
+
+
+	/**
+	 * Records a launch of the application with the specified id, and appends it to the history file.
+	 */
+
+	pub fn record(&mut self, id: &str) -> std::io::Result<()> {
+		let timestamp = History::now();
+		self.add(timestamp, id);
+
+		if let Some(parent) = self.path.parent() { std::fs::create_dir_all(parent)?; }
+		let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+		file.write_all(format!("{} {}\n", timestamp, id).as_bytes())?;
+		self.logged += 1;
+		self.compact_if_needed()
+	}
+
+
+	/**
+	 * Returns the number of launches of an application, with each launch's weight halving every
+	 * `half_life` seconds. Frequently and recently used applications have the highest frequencies.
+	 */
+
+	pub fn get_frequency(&self, id: &str, now: u64, half_life: f64) -> f64 {
+		self.launches.get(id).map_or(0.0, |launches| launches.iter()
+			.map(|timestamp| 0.5_f64.powf(now.saturating_sub(*timestamp) as f64 / half_life.max(1.0)))
+			.sum())
+	}
+
+
+	/**
+	 * Returns the time of the most recent launch of an application, if it has been launched.
+	 */
+
+	pub fn get_last_launch(&self, id: &str) -> Option<u64> {
+		self.launches.get(id).and_then(|launches| launches.last().cloned())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DAY: u64 = 24 * 60 * 60;
+
+	#[test]
+	fn recent_launches_count_more() {
+		let now = 1000 * DAY;
+		let mut history = History::default();
+		for days in &[ 1, 2, 3 ] { history.add(now - days * DAY, "recent"); }
+		for days in &[ 60, 61, 62 ] { history.add(now - days * DAY, "old"); }
+
+		let half_life = (30 * DAY) as f64;
+		assert!(history.get_frequency("recent", now, half_life) > history.get_frequency("old", now, half_life));
+		assert_eq!(history.get_frequency("never", now, half_life), 0.0);
+		assert_eq!(history.get_last_launch("old"), Some(now - 62 * DAY));
+	}
+
+	#[test]
+	fn launches_are_capped_and_the_log_compacted() {
+		let path = std::env::temp_dir().join(format!("scout-history-{}", std::process::id()));
+		let contents: String = (0..2 * MAX_LAUNCHES_PER_APP as u64).map(|timestamp| format!("{} app\n", timestamp)).collect();
+		std::fs::write(&path, contents).unwrap();
+
+		let history = History::new(Some(&path));
+		let compacted = std::fs::read_to_string(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		let history = history.borrow();
+		assert_eq!(history.launches["app"].len(), MAX_LAUNCHES_PER_APP);
+		assert_eq!(history.get_last_launch("app"), Some(2 * MAX_LAUNCHES_PER_APP as u64 - 1));
+		assert_eq!(compacted.lines().count(), MAX_LAUNCHES_PER_APP);
+		assert_eq!(compacted.lines().next(), Some("100 app"));
+	}
+}
@@ -0,0 +1,130 @@
+/*!
+ * An inverted index of the characters in application names, used to skip scoring results that can't match.
+ */
+
+use std::collections::HashMap;
+
+use crate::ranking;
+
+/**
+ * Maps each character of the normalized names of results to the indices of the results containing it.
+ * A result only scores above zero if one of its names contains every character of the query,
+ * so the results found through the index are every result that could match.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct CharIndex {
+	results: HashMap<char, Vec<usize>>
+}
+
+impl CharIndex {
+
+	/**
+	 * Indexes the names of each result, in the same order as the results.
+	 */
+
+	pub fn new<'a, I: Iterator<Item = Vec<&'a str>>>(names: I) -> Self {
+		let mut results = HashMap::<char, Vec<usize>>::new();
+
+		for (ind, names) in names.enumerate() {
+			for c in names.iter().flat_map(|name| ranking::normalize(name).chars().collect::<Vec<_>>()) {
+				let indices = results.entry(c).or_default();
+				if indices.last() != Some(&ind) { indices.push(ind); }
+			}
+		}
+
+		CharIndex { results }
+	}
+
+
+	/**
+	 * Returns the sorted indices of the results whose names contain every character of the query,
+	 * by intersecting the results of each character, starting with the rarest.
+	 */
+
+	pub fn get_candidates(&self, query: &str) -> Vec<usize> {
+		let mut chars: Vec<char> = ranking::normalize(query).chars().collect();
+		chars.sort_unstable();
+		chars.dedup();
+
+		let mut lists = Vec::with_capacity(chars.len());
+		for c in chars {
+			match self.results.get(&c) {
+				Some(indices) => lists.push(indices),
+				None => return vec![]
+			}
+		}
+		lists.sort_by_key(|indices| indices.len());
+
+		let (rarest, rest) = match lists.split_first() {
+			Some(split) => split,
+			None => return vec![]
+		};
+		rarest.iter().cloned().filter(|ind| rest.iter().all(|indices| indices.binary_search(ind).is_ok())).collect()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const NAMES: &[&str] = &[ "Firefox", "Files", "GNU Image Manipulation Program", "LibreOffice Writer", "Terminal",
+		"Visual Studio Code", "VSCodium", "Text Editor", "System Monitor", "Screenshot" ];
+
+	fn get_index(names: &[String]) -> CharIndex {
+		CharIndex::new(names.iter().map(|name| vec![ name.as_str() ]))
+	}
+
+	#[test]
+	fn candidates_contain_every_character() {
+		let names: Vec<String> = NAMES.iter().map(|name| name.to_string()).collect();
+		let index = get_index(&names);
+
+		assert_eq!(index.get_candidates("fx"), vec![ 0 ]);
+		assert_eq!(index.get_candidates("vsc"), vec![ 5, 6 ]);
+		assert!(index.get_candidates("qz").is_empty());
+		assert!(index.get_candidates("").is_empty());
+	}
+
+	#[test]
+	fn prefilter_never_drops_a_match() {
+		let names: Vec<String> = NAMES.iter().map(|name| name.to_string()).collect();
+		let index = get_index(&names);
+
+		for query in [ "f", "fi", "fire", "gimp", "lo", "term", "vs code", "te", "sys mon", "xyz", "scr" ].iter() {
+			let candidates = index.get_candidates(query);
+			for (ind, name) in names.iter().enumerate() {
+				if scout_core::get_name_score(query, name) > 0 {
+					assert!(candidates.contains(&ind), "'{}' matches '{}' but was filtered out", query, name);
+				}
+			}
+		}
+	}
+
+	/**
+	 * Compares the prefilter against scoring every name, with 5000 generated names.
+	 * Run with `cargo test --release -- --ignored --nocapture`.
+	 */
+
+	#[test]
+	#[ignore]
+	fn bench_prefilter() {
+		let names: Vec<String> = (0..5000).map(|ind| format!("{} {} {}", NAMES[ind % NAMES.len()], ind, NAMES[ind * 7 % NAMES.len()])).collect();
+		let index = get_index(&names);
+		let queries = [ "fire", "gimp", "vs code", "term", "xyz", "lbw" ];
+
+		let start = std::time::Instant::now();
+		let full: usize = queries.iter().map(|query| names.iter()
+			.filter(|name| scout_core::get_name_score(query, name) > 0).count()).sum();
+		let full_time = start.elapsed();
+
+		let start = std::time::Instant::now();
+		let prefiltered: usize = queries.iter().map(|query| index.get_candidates(query).into_iter()
+			.filter(|ind| scout_core::get_name_score(query, &names[*ind]) > 0).count()).sum();
+		let prefiltered_time = start.elapsed();
+
+		assert_eq!(full, prefiltered);
+		println!("Scoring every name: {:?}, with the prefilter: {:?}", full_time, prefiltered_time);
+	}
+}
@@ -0,0 +1,245 @@
+/*!
+ * Results that quit the running processes of an application, shown for queries starting with `kill:`.
+ */
+
+#[cfg(feature = "gui")]
+use gtk::prelude::*;
+
+use std::collections::HashSet;
+
+use scout_core::{ SearchResult, Shared };
+
+use crate::launch;
+use crate::process;
+use crate::result::ApplicationResult;
+
+/** The prefix of queries that search for applications to quit instead of launch. */
+pub const KILL_PREFIX: &str = "kill:";
+
+/**
+ * A result that quits an application's processes. The first activation only asks for confirmation,
+ * the next sends `SIGTERM`, and activating it again sends `SIGKILL` to processes that are still running.
+ */
+
+#[derive(Debug, Clone)]
+pub struct KillResult {
+	id: String,
+	name: String,
+	icon: Option<String>,
+	pids: Vec<u32>,
+
+	/** The processes that have been activated once and will be sent `SIGTERM` on the next activation, shared by every kill result of the plugin. */
+	armed: Shared<HashSet<u32>>,
+
+	/** The processes that have already been sent `SIGTERM`, shared by every kill result of the plugin. */
+	terminated: Shared<HashSet<u32>>,
+
+	#[cfg(feature = "gui")]
+	widget: gtk::Box,
+	#[cfg(feature = "gui")]
+	top_button: gtk::Button,
+
+	score: usize
+}
+
+impl KillResult {
+
+	/**
+	 * Creates a result that quits the processes specified of an application, with the application's score.
+	 */
+
+	pub fn new(application: &ApplicationResult, pids: Vec<u32>, armed: Shared<HashSet<u32>>, terminated: Shared<HashSet<u32>>) -> Self {
+		let id = application.get_id();
+		let name = application.get_title();
+		let icon = application.get_icon_name();
+
+		#[cfg(feature = "gui")]
+		let (widget, top_button) = KillResult::build_result_widget(&name, icon.as_deref(), pids.len());
+
+		let result = KillResult {
+			id, name, icon, pids, armed, terminated,
+			#[cfg(feature = "gui")]
+			widget,
+			#[cfg(feature = "gui")]
+			top_button,
+			score: application.get_score()
+		};
+
+		#[cfg(feature = "gui")]
+		{
+			let result_clone = result.clone();
+			result.top_button.connect_clicked(move |_| result_clone.activate());
+		}
+
+		result
+	}
+
+
+	/**
+	 * Returns true if every process of the result has been activated once, confirming that they should be quit.
+	 */
+
+	fn is_armed(&self) -> bool {
+		let armed = self.armed.borrow();
+		self.pids.iter().all(|pid| armed.contains(pid))
+	}
+
+
+	/**
+	 * Returns true if every process of the result has already been sent `SIGTERM`.
+	 */
+
+	fn is_terminated(&self) -> bool {
+		let terminated = self.terminated.borrow();
+		self.pids.iter().all(|pid| terminated.contains(pid))
+	}
+
+
+	/**
+	 * Describes what activating the result will do.
+	 */
+
+	fn get_description(&self) -> String {
+		let processes = if self.pids.len() == 1 { "process".to_owned() } else { format!("{} processes", self.pids.len()) };
+		if self.is_terminated() { format!("Force quit the {} that didn't exit", processes) }
+		else if self.is_armed() { format!("Activate again to confirm quitting the running {}", processes) }
+		else { format!("Quit the running {}", processes) }
+	}
+
+	#[cfg(feature = "gui")]
+	fn build_result_widget(name: &str, icon: Option<&str>, count: usize) -> (gtk::Box, gtk::Button) {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Application");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		top_button.add(&widget_top);
+
+		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		icon_box.get_style_context().add_class("IconBox");
+		widget_top.pack_start(&icon_box, false, false, 4);
+
+		let icon = ApplicationResult::get_icon(icon, 32);
+		icon.set_size_request(32, 32);
+		icon_box.pack_start(&icon, false, false, 0);
+
+		let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget_top.pack_start(&description_box, true, true, 0);
+
+		let category_label = gtk::Label::new(Some(&format!("<span size='small' weight='bold'>QUIT {}</span>",
+			if count == 1 { "1 PROCESS".to_owned() } else { format!("{} PROCESSES", count) })));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_ellipsize(pango::EllipsizeMode::End);
+		category_label.set_use_markup(true);
+		category_label.set_xalign(0.0);
+		description_box.pack_start(&category_label, false, false, 1);
+
+		let label = gtk::Label::new(Some(name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		label.set_xalign(0.0);
+		description_box.pack_start(&label, false, false, 1);
+
+		(widget, top_button)
+	}
+}
+
+impl SearchResult for KillResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		[ KILL_PREFIX, &self.id ].join("")
+	}
+
+	fn get_title(&self) -> String {
+		self.name.clone()
+	}
+
+	fn get_subtitle(&self) -> String {
+		self.get_description()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		self.icon.clone()
+	}
+
+	fn is_pinnable(&self) -> bool {
+		false
+	}
+
+	#[cfg(feature = "gui")]
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		if !self.is_armed() {
+			self.armed.borrow_mut().extend(self.pids.iter().cloned());
+			launch::notify(&[ "Quit ", &self.name, "?" ].join(""), "Activate the result again to confirm.");
+			return;
+		}
+
+		let force = self.is_terminated();
+		if let Err(err) = process::signal(&self.pids, force) { println!("[WARN] Failed to quit '{}': {}", &self.name, err); }
+		if !force { self.terminated.borrow_mut().extend(self.pids.iter().cloned()); }
+	}
+
+	#[cfg(feature = "gui")]
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	#[cfg(feature = "gui")]
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Application");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = ApplicationResult::get_icon(self.icon.as_deref(), 96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let description = gtk::Label::new(Some(&[ &self.get_description(), "." ].join("")));
+		description.get_style_context().add_class("Description");
+		description.set_line_wrap_mode(pango::WrapMode::WordChar);
+		description.set_justify(gtk::Justification::Center);
+		description.set_max_width_chars(36);
+		description.set_line_wrap(true);
+		widget.pack_start(&description, false, false, 0);
+
+		widget.upcast()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::history::History;
+	use crate::result::ApplicationResultBuilder;
+
+	fn get_application() -> ApplicationResult {
+		ApplicationResultBuilder::new("firefox", "Firefox", "firefox %u",
+			Shared::new(serde_json::from_str("{}").unwrap()), Shared::new(History::default())).build()
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn only_programs_are_pinnable() {
+		let application = get_application();
+		let kill = KillResult::new(&application, vec![ 4321 ], Shared::new(HashSet::new()), Shared::new(HashSet::new()));
+
+		assert!(application.is_pinnable());
+		assert!(!kill.is_pinnable());
+	}
+}
@@ -0,0 +1,518 @@
+/*!
+ * Launching of desktop entry commands.
+ * All of the application plugin's launch paths should go through here.
+ */
+
+use std::os::unix::fs::PermissionsExt;
+
+use crate::error::ScoutError;
+
+/** The range of niceness values that a program can be launched with. */
+const NICE_RANGE: std::ops::RangeInclusive<i32> = -20..=19;
+
+/** Programs that only start another program, so their name says nothing about the application being run. */
+pub const GENERIC_LAUNCHERS: &[&str] = &[ "sh", "bash", "dash", "zsh", "env", "flatpak", "snap", "python", "python2", "python3",
+	"perl", "ruby", "node", "java", "wine", "sudo", "pkexec", "kdesu", "nice", "systemd-run", "gtk-launch", "xdg-open" ];
+
+/**
+ * Options for how a command is spawned, shared by a program and its actions.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+	pub working_dir: Option<String>,
+	pub nice: i32,
+	pub systemd_scope: bool,
+
+	/** The user to run the program as, from KDE's `X-KDE-SubstituteUID` and `X-KDE-Username` keys. */
+	pub substitute_user: Option<String>,
+
+	/** The summary of a notification of the command shown when it is launched, if `notify_on_launch` is enabled. */
+	pub notify_summary: Option<String>
+}
+
+/**
+ * Expands a leading `~`, and `$VAR` or `${VAR}` references, using the process environment.
+ * Undefined variables expand to an empty string.
+ */
+
+pub fn expand_env(value: &str) -> String {
+	let mut expanded = String::new();
+	let mut chars = value.chars().peekable();
+
+	if value == "~" || value.starts_with("~/") {
+		expanded.push_str(&std::env::var("HOME").unwrap_or_default());
+		chars.next();
+	}
+
+	while let Some(c) = chars.next() {
+		if c == '$' { push_variable(&mut chars, &mut expanded); }
+		else { expanded.push(c); }
+	}
+
+	expanded
+}
+
+
+/**
+ * Reads the name of a variable following a `$` and pushes its value.
+ * A `$` that isn't followed by a name is kept as it is.
+ */
+
+fn push_variable(chars: &mut std::iter::Peekable<std::str::Chars>, expanded: &mut String) {
+	let mut name = String::new();
+	if chars.peek() == Some(&'{') {
+		chars.next();
+		for c in chars.by_ref() {
+			if c == '}' { break; }
+			name.push(c);
+		}
+	}
+	else {
+		while let Some(&c) = chars.peek() {
+			if !c.is_ascii_alphanumeric() && c != '_' { break; }
+			name.push(c);
+			chars.next();
+		}
+		if name.is_empty() { expanded.push('$'); return; }
+	}
+
+	expanded.push_str(&std::env::var(&name).unwrap_or_default());
+}
+
+
+/**
+ * Splits a formatted shell command into the arguments it will be spawned with.
+ * Quoted arguments are kept whole, so `sh -c 'foo && bar'` becomes `sh`, `-c`, `foo && bar`.
+ * A shell is never introduced, operators are only interpreted if the command runs one itself.
+ * Environment variables and a leading `~` are expanded while splitting, except inside single quotes or when escaped,
+ * so that `sh -c 'echo $HOME'` leaves the variable to the shell it runs.
+ */
+
+pub fn get_args(exec: &str) -> Result<Vec<String>, ScoutError> {
+	let args = split_expanded(exec).map_err(|err| ScoutError::Parse(format!("Invalid command '{}': {}", exec, err)))?;
+	if args.is_empty() { return Err(ScoutError::Parse("Command is empty.".to_owned())); }
+	Ok(args)
+}
+
+
+/**
+ * Splits a command into arguments following the shell's quoting rules, expanding unquoted and double-quoted variables.
+ */
+
+fn split_expanded(exec: &str) -> Result<Vec<String>, &'static str> {
+	let mut args = Vec::new();
+	let mut arg: Option<String> = None;
+	let mut chars = exec.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			' ' | '\t' | '\n' => args.extend(arg.take()),
+			'\'' => {
+				let arg = arg.get_or_insert_with(String::new);
+				loop {
+					match chars.next() {
+						Some('\'') => break,
+						Some(c) => arg.push(c),
+						None => return Err("missing closing quote")
+					}
+				}
+			},
+			'"' => {
+				let arg = arg.get_or_insert_with(String::new);
+				loop {
+					match chars.next() {
+						Some('"') => break,
+						Some('\\') => match chars.next() {
+							Some('\n') => (),
+							Some(c) if "$`\"\\".contains(c) => arg.push(c),
+							Some(c) => { arg.push('\\'); arg.push(c); },
+							None => return Err("missing closing quote")
+						},
+						Some('$') => push_variable(&mut chars, arg),
+						Some(c) => arg.push(c),
+						None => return Err("missing closing quote")
+					}
+				}
+			},
+			'\\' => match chars.next() {
+				Some('\n') => (),
+				Some(c) => arg.get_or_insert_with(String::new).push(c),
+				None => return Err("missing escaped character")
+			},
+			'~' if arg.is_none() && matches!(chars.peek(), None | Some('/') | Some(' ') | Some('\t') | Some('\n')) =>
+				arg = Some(std::env::var("HOME").unwrap_or_default()),
+			'$' => push_variable(&mut chars, arg.get_or_insert_with(String::new)),
+			c => arg.get_or_insert_with(String::new).push(c)
+		}
+	}
+
+	args.extend(arg);
+	Ok(args)
+}
+
+
+/**
+ * Resolves the program of a command against `PATH`, returning an error if it can't be found.
+ * Programs containing a `/` are paths, which are returned unchanged.
+ */
+
+pub fn resolve_executable(program: &str) -> Result<std::path::PathBuf, ScoutError> {
+	resolve_executable_in(program, &std::env::var_os("PATH").unwrap_or_default())
+}
+
+
+/**
+ * Resolves the program of a command like `resolve_executable`, against the directories of a `PATH` value.
+ */
+
+pub fn resolve_executable_in(program: &str, path: &std::ffi::OsStr) -> Result<std::path::PathBuf, ScoutError> {
+	if program.contains('/') { return Ok(program.into()); }
+
+	std::env::split_paths(path)
+		.map(|dir| dir.join(program))
+		.find(|path| is_executable(path))
+		.ok_or_else(|| ScoutError::Launch(format!("Executable '{}' not found.", program)))
+}
+
+
+/**
+ * Returns true if the program, by its file name, is one of the `GENERIC_LAUNCHERS`.
+ */
+
+pub fn is_generic_launcher(program: &str) -> bool {
+	let name = std::path::Path::new(program).file_name().and_then(|name| name.to_str()).unwrap_or(program);
+	GENERIC_LAUNCHERS.contains(&name)
+}
+
+
+/**
+ * Returns true if the path is a file that can be executed.
+ */
+
+pub fn is_executable(path: &std::path::Path) -> bool {
+	path.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+
+/**
+ * Finds every executable in the directories of `PATH`.
+ * Executables with the same name as one in an earlier directory are skipped, since they can't be run by name.
+ */
+
+pub fn find_executables() -> Vec<std::path::PathBuf> {
+	find_executables_in(&std::env::var_os("PATH").unwrap_or_default())
+}
+
+
+/**
+ * Finds every executable like `find_executables`, in the directories of a `PATH` value.
+ */
+
+pub fn find_executables_in(path: &std::ffi::OsStr) -> Vec<std::path::PathBuf> {
+	let mut names = std::collections::HashSet::new();
+
+	std::env::split_paths(path)
+		.filter_map(|dir| std::fs::read_dir(dir).ok())
+		.flat_map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<_>>())
+		.filter(|path| is_executable(path))
+		.filter(|path| path.file_name().is_some_and(|name| names.insert(name.to_owned())))
+		.collect()
+}
+
+
+/**
+ * Parses a niceness value, such as from the `X-Scout-Nice` key,
+ * returning `None` if it isn't a number between -20 and 19.
+ */
+
+pub fn parse_nice(value: Option<&str>) -> Option<i32> {
+	value?.trim().parse::<i32>().ok().filter(|nice| valid_nice(*nice))
+}
+
+pub fn valid_nice(nice: i32) -> bool {
+	NICE_RANGE.contains(&nice)
+}
+
+
+/**
+ * Prefixes arguments with `nice -n`, if the niceness is not zero.
+ */
+
+pub fn with_nice(args: Vec<String>, nice: i32) -> Vec<String> {
+	if nice == 0 { return args; }
+	[ "nice".to_owned(), "-n".to_owned(), nice.to_string() ].iter().cloned().chain(args).collect()
+}
+
+
+/**
+ * Parses KDE's `X-KDE-SubstituteUID` and `X-KDE-Username` keys into the user a program should run as.
+ * Returns `None` unless `X-KDE-SubstituteUID` is true, and the user is `root` if no username is given.
+ */
+
+pub fn parse_substitute_user(substitute_uid: Option<&str>, username: Option<&str>) -> Option<String> {
+	if substitute_uid.map(|value| value.trim()) != Some("true") { return None; }
+	Some(username.map(|name| name.trim()).filter(|name| !name.is_empty()).unwrap_or("root").to_owned())
+}
+
+
+/** The variables passed through `pkexec`, which clears the environment, so that graphical programs can find the display. */
+const DISPLAY_VARIABLES: &[&str] = &[ "DISPLAY", "XAUTHORITY", "WAYLAND_DISPLAY", "XDG_RUNTIME_DIR" ];
+
+/**
+ * Prefixes arguments with `kdesu`, which KDE's `X-KDE-SubstituteUID` entries are written for, to run them as another user.
+ * If `kdesu` isn't installed, `pkexec` is used instead, passing the display through with `env`.
+ * Arguments are returned unchanged if there is no user, or if neither program can be found.
+ */
+
+pub fn with_substitute_user(args: Vec<String>, user: Option<&str>) -> Vec<String> {
+	let user = match user {
+		Some(user) => user,
+		None => return args
+	};
+
+	let prefix: Vec<String> = if resolve_executable("kdesu").is_ok() {
+		[ "kdesu", "-u", user, "--" ].iter().map(|arg| arg.to_string()).collect()
+	}
+	else if resolve_executable("pkexec").is_ok() {
+		[ "pkexec", "--user", user, "env" ].iter().map(|arg| arg.to_string())
+			.chain(DISPLAY_VARIABLES.iter().filter_map(|name| std::env::var(name).ok().map(|value| [ name, "=", &value ].join(""))))
+			.collect()
+	}
+	else {
+		println!("[WARN] Can't find 'kdesu' or 'pkexec', launching as the current user.");
+		return args;
+	};
+
+	prefix.into_iter().chain(args).collect()
+}
+
+
+/**
+ * Prefixes arguments with `systemd-run --user --scope`, if enabled,
+ * so that the program runs in its own transient scope instead of Scout's.
+ * Arguments are returned unchanged if `systemd-run` can't be found.
+ */
+
+pub fn with_systemd_scope(args: Vec<String>, systemd_scope: bool) -> Vec<String> {
+	if !systemd_scope { return args; }
+	if resolve_executable("systemd-run").is_err() {
+		println!("[WARN] Can't find 'systemd-run', launching without a scope.");
+		return args;
+	}
+
+	in_systemd_scope(args)
+}
+
+
+/**
+ * Prefixes arguments with `systemd-run --user --scope`, without checking that it's installed.
+ */
+
+fn in_systemd_scope(args: Vec<String>) -> Vec<String> {
+	[ "systemd-run", "--user", "--scope", "--quiet", "--" ].iter().map(|arg| arg.to_string()).chain(args).collect()
+}
+
+
+/**
+ * Shows a desktop notification with `notify-send`, silently doing nothing if it isn't installed.
+ */
+
+pub fn notify(summary: &str, body: &str) {
+	drop(std::process::Command::new("notify-send").args(&[ "--app-name=Scout", "--", summary, body ])
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn());
+}
+
+
+/**
+ * Returns the summary and body of the notification shown when a command is launched,
+ * if the options have a notification summary. The body is the arguments spawned, quoted as a shell command.
+ */
+
+pub fn get_notification<'a>(options: &'a LaunchOptions, args: &[String]) -> Option<(&'a str, String)> {
+	options.notify_summary.as_deref().map(|summary| (summary, shell_words::join(args)))
+}
+
+
+/**
+ * Splits a formatted shell command into the arguments spawned for it, resolving the program
+ * and prefixing it with the niceness, user, and scope in the options specified.
+ */
+
+pub fn get_command(exec: &str, options: &LaunchOptions) -> Result<Vec<String>, ScoutError> {
+	let mut args = get_args(exec)?;
+	args[0] = resolve_executable(&args[0])?.to_string_lossy().into_owned();
+	Ok(with_systemd_scope(with_substitute_user(with_nice(args, options.nice), options.substitute_user.as_deref()), options.systemd_scope))
+}
+
+
+/**
+ * Splits a formatted shell command into arguments and spawns it with the options specified,
+ * returning an error instead of panicking if the command is malformed.
+ */
+
+pub fn launch(exec: &str, options: &LaunchOptions) -> Result<(), ScoutError> {
+	println!("Executing '{}'", exec);
+	let args = get_command(exec, options)?;
+
+	let mut command = std::process::Command::new(&args[0]);
+	command.args(&args[1..]).stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+	if let Some(working_dir) = options.working_dir.as_deref().map(expand_env).filter(|dir| !dir.is_empty()) {
+		command.current_dir(working_dir);
+	}
+	command.spawn().map_err(|err| ScoutError::Launch(format!("Failed to spawn '{}': {}", &args[0], err)))?;
+
+	if let Some((summary, body)) = get_notification(options, &args) { notify(summary, &body); }
+
+	Ok(())
+}
+
+
+/**
+ * Opens a URL with `xdg-open`.
+ */
+
+#[cfg(feature = "gui")]
+pub fn open(url: &str) -> Result<(), ScoutError> {
+	println!("Opening '{}'", url);
+	std::process::Command::new("xdg-open").arg(url)
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn()
+		.map_err(|err| ScoutError::Launch(format!("Failed to spawn 'xdg-open': {}", err)))?;
+
+	Ok(())
+}
+
+
+/**
+ * Attempts to raise an existing window with the specified `StartupWMClass`.
+ * On X11 this uses `wmctrl`. Wayland compositors don't allow clients to raise
+ * other clients' windows, so this always fails there and the caller should launch instead.
+ */
+
+pub fn raise(wm_class: &str) -> bool {
+	if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+		println!("Can't raise '{}' on Wayland, launching instead.", wm_class);
+		return false;
+	}
+
+	std::process::Command::new("wmctrl").args(&[ "-x", "-a", wm_class ])
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status()
+		.map(|status| status.success()).unwrap_or(false)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unbalanced_quotes_are_an_error() {
+		assert!(matches!(get_args("firefox --new-window 'https://example.com"), Err(ScoutError::Parse(_))));
+		assert!(matches!(get_args("sh -c \"echo hi"), Err(ScoutError::Parse(_))));
+		assert!(matches!(launch("firefox --private-window 'unclosed", &LaunchOptions::default()), Err(ScoutError::Parse(_))));
+	}
+
+	#[test]
+	fn shell_commands_stay_one_argument() {
+		assert_eq!(get_args("sh -c 'cd ~/src && make'").unwrap(), vec![ "sh", "-c", "cd ~/src && make" ]);
+		assert_eq!(get_args("bash -c \"foo && bar\" --").unwrap(), vec![ "bash", "-c", "foo && bar", "--" ]);
+		assert_eq!(get_args("foo && bar").unwrap(), vec![ "foo", "&&", "bar" ]);
+	}
+
+	#[test]
+	fn home_and_variables_are_expanded() {
+		let home = std::env::var("HOME").unwrap_or_default();
+		assert_eq!(get_args("~/bin/tool --config=~/x ~").unwrap(), vec![ format!("{}/bin/tool", home), "--config=~/x".to_owned(), home.clone() ]);
+		assert_eq!(get_args("tool \"$HOME/my data\" ${HOME}/x a~b").unwrap(),
+			vec![ "tool".to_owned(), format!("{}/my data", home), format!("{}/x", home), "a~b".to_owned() ]);
+		assert_eq!(get_args("tool $SCOUT_UNDEFINED_VARIABLE/x $ 5$").unwrap(), vec![ "tool", "/x", "$", "5$" ]);
+
+		assert_eq!(expand_env("~/Projects"), format!("{}/Projects", home));
+		assert_eq!(expand_env("$HOME/Projects"), format!("{}/Projects", home));
+		assert_eq!(expand_env("~user/Projects"), "~user/Projects");
+	}
+
+	#[test]
+	fn single_quoted_and_escaped_variables_are_left_alone() {
+		assert_eq!(get_args("sh -c 'echo $HOME ~'").unwrap(), vec![ "sh", "-c", "echo $HOME ~" ]);
+		assert_eq!(get_args("echo \\$HOME \"\\$HOME\" '~'").unwrap(), vec![ "echo", "$HOME", "$HOME", "~" ]);
+	}
+
+	#[test]
+	fn nice_levels_prefix_the_command() {
+		let args = vec![ "blender".to_owned(), "--factory-startup".to_owned() ];
+		assert_eq!(with_nice(args.clone(), 10), vec![ "nice", "-n", "10", "blender", "--factory-startup" ]);
+		assert_eq!(with_nice(args.clone(), -5), vec![ "nice", "-n", "-5", "blender", "--factory-startup" ]);
+		assert_eq!(with_nice(args.clone(), 0), args);
+	}
+
+	#[test]
+	fn nice_levels_outside_the_range_are_invalid() {
+		assert_eq!(parse_nice(Some(" 19 ")), Some(19));
+		assert_eq!(parse_nice(Some("-20")), Some(-20));
+		assert_eq!(parse_nice(Some("20")), None);
+		assert_eq!(parse_nice(Some("-21")), None);
+		assert_eq!(parse_nice(Some("high")), None);
+		assert_eq!(parse_nice(None), None);
+	}
+
+	/**
+	 * Creates a directory to use as `PATH`, containing an executable `tool` and a file `notes` that isn't executable.
+	 */
+
+	fn get_path_dir(name: &str) -> std::path::PathBuf {
+		let dir = std::env::temp_dir().join(format!("scout-path-{}-{}", std::process::id(), name));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("tool"), "#!/bin/sh\n").unwrap();
+		std::fs::set_permissions(dir.join("tool"), std::fs::Permissions::from_mode(0o755)).unwrap();
+		std::fs::write(dir.join("notes"), "").unwrap();
+		dir
+	}
+
+	#[test]
+	fn executables_are_resolved_against_the_path() {
+		let dir = get_path_dir("resolve");
+		let path = std::env::join_paths([ std::path::Path::new("/scout/missing"), &dir ]).unwrap();
+
+		assert_eq!(resolve_executable_in("tool", &path).unwrap(), dir.join("tool"));
+		assert_eq!(resolve_executable_in("/opt/tool/bin/tool", &path).unwrap(), std::path::Path::new("/opt/tool/bin/tool"));
+		assert_eq!(resolve_executable_in("./tool", &path).unwrap(), std::path::Path::new("./tool"));
+		assert!(matches!(resolve_executable_in("notes", &path), Err(ScoutError::Launch(_))));
+		assert!(matches!(resolve_executable_in("scout-missing-program", &path), Err(ScoutError::Launch(_))));
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn systemd_scopes_wrap_the_command() {
+		let args = vec![ "blender".to_owned(), "--factory-startup".to_owned() ];
+		assert_eq!(in_systemd_scope(args.clone()), vec![ "systemd-run", "--user", "--scope", "--quiet", "--", "blender", "--factory-startup" ]);
+		assert_eq!(in_systemd_scope(with_nice(args.clone(), 5))[5..], [ "nice", "-n", "5", "blender", "--factory-startup" ]);
+		assert_eq!(with_systemd_scope(args.clone(), false), args);
+	}
+
+
+	#[test]
+	fn executables_are_found_once_in_path_order() {
+		let (first, second) = (get_path_dir("find-first"), get_path_dir("find-second"));
+		let path = std::env::join_paths([ &first, std::path::Path::new("/scout/missing"), &second ]).unwrap();
+
+		assert_eq!(find_executables_in(&path), vec![ first.join("tool") ]);
+
+		std::fs::remove_dir_all(&first).unwrap();
+		std::fs::remove_dir_all(&second).unwrap();
+	}
+
+
+	#[test]
+	fn substitute_users_default_to_root() {
+		assert_eq!(parse_substitute_user(Some("true"), Some("admin")), Some("admin".to_owned()));
+		assert_eq!(parse_substitute_user(Some(" true "), None), Some("root".to_owned()));
+		assert_eq!(parse_substitute_user(Some("true"), Some(" ")), Some("root".to_owned()));
+		assert_eq!(parse_substitute_user(Some("false"), Some("admin")), None);
+		assert_eq!(parse_substitute_user(None, Some("admin")), None);
+
+		let args = vec![ "partitionmanager".to_owned() ];
+		assert_eq!(with_substitute_user(args.clone(), None), args);
+	}
+}
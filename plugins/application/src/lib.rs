@@ -1,75 +1,135 @@
-use std::env;
-use std::ffi::OsStr;
-use std::path::PathBuf;
-
 use freedesktop_entry_parser::parse_entry;
 
 use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
 
+pub mod error;
+mod cache;
+mod discovery;
+mod event;
+mod index;
+mod kill;
+mod process;
+mod launch;
+mod list;
+mod locale;
+mod desktop;
+mod appimage;
+mod mime;
+mod metainfo;
+mod placement;
+mod popularity;
+pub mod validate;
+mod ranking;
+mod snippets;
+
+mod history;
+use history::History;
+
+mod preferences;
+use preferences::Preferences;
+
 mod result;
-use result::{ Action, ApplicationResult };
+use result::{ Action, ApplicationResult, ApplicationResultBuilder };
+
+pub use error::ScoutError;
+
+use index::CharIndex;
+use cache::ScoreCache;
+use kill::{ KillResult, KILL_PREFIX };
 
 pub struct ApplicationPlugin {
 	bindings: Shared<Box<dyn PluginBindings>>,
-	results: Vec<ApplicationResult>
+	preferences: Shared<Preferences>,
+	history: Shared<History>,
+	files: Vec<std::path::PathBuf>,
+	results: Vec<ApplicationResult>,
+	index: CharIndex,
+	cache: Shared<ScoreCache>,
+	defaults: Shared<mime::DefaultCache>,
+	armed: Shared<std::collections::HashSet<u32>>,
+	terminated: Shared<std::collections::HashSet<u32>>
+}
+
+/**
+ * Searches the application directories with the plugin's preferences and writes the discovery cache,
+ * without opening a window or needing a display, for `scout --prewarm`. Returns the number of desktop entries found.
+ */
+
+pub fn prewarm() -> Result<usize, ScoutError> {
+	let cache_path = discovery::get_cache_path()
+		.ok_or_else(|| ScoutError::Config("There is no cache directory.".to_owned()))?;
+	let preferences = Preferences::new(None);
+	let preferences = preferences.borrow();
+
+	let cache = discovery::prewarm(&discovery::get_search_paths(), preferences.max_scan_depth, preferences.max_scan_files, &cache_path)?;
+	Ok(cache.files.len())
+}
+
+
+/**
+ * Removes the discovery cache, so that refreshing searches every application directory again.
+ */
+
+fn clear_cache() -> std::io::Result<()> {
+	discovery::get_cache_path().map_or(Ok(()), |path| discovery::remove_cache(&path))
+}
+
+/**
+ * The values that every desktop entry is read with, which are only looked up once for each discovery.
+ */
+
+struct EntryContext {
+	strings: preferences::Strings,
+	nice: i32,
+	locales: Vec<String>,
+	desktops: Vec<String>,
+	popularity: popularity::Popularity
+}
+
+impl EntryContext {
+	fn new(preferences: &Preferences) -> Self {
+		EntryContext {
+			strings: preferences.strings.clone(),
+			nice: Some(preferences.nice).filter(|nice| launch::valid_nice(*nice)).unwrap_or_else(|| {
+				println!("[WARN] Ignoring the default niceness, it must be between -20 and 19.");
+				0
+			}),
+			locales: locale::get_locales(),
+			desktops: desktop::get_current_desktops(),
+			popularity: popularity::Popularity::load(preferences.popularity_file.as_deref())
+		}
+	}
 }
 
 impl ApplicationPlugin {
-	fn find_applications() -> Vec<ApplicationResult> {
-		let mut search_paths = env::var("XDG_DATA_DIRS")
-			.and_then(|string| Ok(string.split(":").map(|string| format!("{}/applications", string).into()).collect::<Vec<PathBuf>>()))
-			.unwrap_or_else(|_| vec![]);
-		if let Some(dir) = dirs::data_dir() { search_paths.push(format!("{}/applications", dir.to_str().unwrap()).into()); }
-		else { search_paths.push(format!("/home/{}/.local/share/applications", &whoami::username()).into()); }
-
-		let mut found = Vec::<ApplicationResult>::new();
-
-		while search_paths.len() != 0 {
-			let path = search_paths.pop().unwrap();
-			let dir_iter = scout_core::or_continue!(std::fs::read_dir(&path));
-
-			for entry in dir_iter {
-				let entry = scout_core::or_continue!(entry);
-				let path = entry.path();
-
-				if path.is_dir() {
-					search_paths.push(path);
-					continue;
-				}
-
-				if path.extension() == Some(OsStr::new("desktop")) {
-					let parsed = scout_core::or_continue!(parse_entry(path));
-					let entry = parsed.section("Desktop Entry");
-
-					let show = entry.attr("NoDisplay").unwrap_or("false") == "false" && entry.attr("Hidden").unwrap_or("false") == "false";
-
-					let action_names = entry.attr("Actions").and_then(|s| Some(s.split(';')
-						.filter(|s| !s.is_empty()).collect())).unwrap_or_else(|| vec![]);
-					let actions = if action_names.len() > 0 {
-						Some(action_names.iter().map(|name| {
-							let entry = parsed.section(["Desktop Action", name].join(" "));
-							Action {
-								name: entry.attr("Name").unwrap_or("Unnamed Action").to_owned(),
-								exec: entry.attr("Exec").unwrap().to_owned(),
-							}
-						}).collect())
-					} else { None };
-
-					let exec = entry.attr("Exec");
-					if exec.is_none() { continue; }
-
-					if show {
-						found.push(ApplicationResult::new(
-							entry.attr("Name").unwrap_or("Unnamed Application"),
-							entry.attr("Comment").unwrap_or(""),
-							&ApplicationResult::choose_category(entry.attr("Categories")),
-							exec.unwrap(),
-							entry.attr("Icon"),
-							actions
-						))
-					}
-				}
-			}
+	/**
+	 * Finds the desktop entries in the application directories. Failing to search the directories at all is an error.
+	 */
+
+	fn find_files(preferences: &Shared<Preferences>) -> Result<Vec<std::path::PathBuf>, ScoutError> {
+		discovery::discover(&discovery::get_search_paths(), &preferences.borrow(), discovery::get_cache_path().as_deref())
+	}
+
+
+	/**
+	 * Reads the applications from the desktop entries found, along with snippets, AppImages, and executables if they are enabled.
+	 * Malformed desktop entries are skipped with a warning.
+	 */
+
+	fn read_applications(files: &[std::path::PathBuf], preferences: &Shared<Preferences>, history: &Shared<History>) -> Vec<ApplicationResult> {
+		let context = EntryContext::new(&preferences.borrow());
+		let mut found: Vec<ApplicationResult> = files.iter()
+			.filter_map(|path| ApplicationPlugin::read_entry(path, &context, preferences, history)).collect();
+
+		found.extend(ApplicationPlugin::find_snippets(preferences, history, &snippets::get_path()));
+
+		if preferences.borrow().appimages {
+			let appimages = ApplicationPlugin::find_appimages(preferences, history, &found);
+			found.extend(appimages);
+		}
+		if preferences.borrow().path_executables {
+			let executables = ApplicationPlugin::find_executables(preferences, history, &found, &launch::find_executables());
+			found.extend(executables);
 		}
 
 		found.sort();
@@ -77,29 +137,302 @@ impl ApplicationPlugin {
 		found
 	}
 
+
+	/**
+	 * Reads the results again from the desktop entries already found, rebuilding the index and clearing the cached scores.
+	 */
+
+	fn rebuild_results(&mut self) {
+		self.results = ApplicationPlugin::read_applications(&self.files, &self.preferences, &self.history);
+		self.index = CharIndex::new(self.results.iter().map(|result| result.get_search_names()));
+		self.cache.borrow_mut().clear();
+	}
+
+
+	/**
+	 * Reads a desktop entry into a result, or returns None if it can't be parsed, has nothing to launch,
+	 * or isn't shown because it is hidden, in a category that isn't allowed, or meant for another desktop.
+	 */
+
+	fn read_entry(path: &std::path::Path, context: &EntryContext, preferences: &Shared<Preferences>, history: &Shared<History>) -> Option<ApplicationResult> {
+		let parsed = match parse_entry(path) {
+			Ok(parsed) => parsed,
+			Err(err) => { println!("[WARN] Failed to parse '{}': {}", path.display(), err); return None; }
+		};
+		let entry = parsed.section("Desktop Entry");
+		let strings = &context.strings;
+
+		let show = discovery::is_launchable(&entry, preferences.borrow().treat_nodisplay_as_launchable)
+			&& ApplicationResult::is_category_allowed(entry.attr("Categories"), &preferences.borrow().allowed_categories)
+			&& desktop::is_shown_in(entry.attr("OnlyShowIn"), entry.attr("NotShowIn"), &context.desktops, preferences.borrow().unknown_desktop);
+		if !show { return None; }
+		let exec = discovery::get_exec(&entry)?;
+
+		let action_names = list::parse_list(entry.attr("Actions"));
+		let action_order = list::parse_list(entry.attr("X-Scout-ActionOrder"));
+		let profiles = Action::from_profiles(entry.attr("X-Scout-Profiles"), exec, &strings.profile_action);
+		let actions = if !action_names.is_empty() || !profiles.is_empty() {
+			let mut actions: Vec<_> = action_names.iter().filter_map(|name| {
+				let entry = discovery::get_action_section(&parsed, name);
+				Some(Action {
+					id: name.to_string(),
+					#[cfg(feature = "gui")]
+					name: locale::get_localized(&entry, "Name", &context.locales).unwrap_or(&strings.unnamed_action).to_owned(),
+					exec: discovery::get_exec(&entry)?.to_owned(),
+				})
+			}).chain(profiles).collect();
+			Action::sort_by_order(&mut actions, &action_order);
+			Some(actions)
+		} else { None };
+
+		let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+		let metainfo = if preferences.borrow().rich_metadata { metainfo::find(id) } else { None }.unwrap_or_default();
+
+		Some(ApplicationResultBuilder::new(
+			id,
+			locale::get_localized(&entry, "Name", &context.locales).unwrap_or(&strings.unnamed_application),
+			exec,
+			preferences.clone(),
+			history.clone()
+		)
+			.source_path(path)
+			.unlocalized_name(entry.attr("Name"))
+			.generic_name(locale::get_localized(&entry, "GenericName", &context.locales))
+			.description(locale::get_localized(&entry, "Comment", &context.locales)
+				.or_else(|| metainfo.summary.as_deref()).unwrap_or(""))
+			.keywords(metainfo.keywords)
+			.category(&ApplicationResult::override_category(entry.attr("X-Scout-Category"))
+				.unwrap_or_else(|| ApplicationResult::choose_category(entry.attr("Categories"), &strings.category)))
+			.category_key(ApplicationResult::choose_category_key(entry.attr("Categories"), entry.attr("X-Scout-Category")))
+			.categories(ApplicationResult::get_display_categories(entry.attr("Categories")))
+			.icon(entry.attr("Icon"))
+			.working_dir(entry.attr("Path"))
+			.nice(launch::parse_nice(entry.attr("X-Scout-Nice")).unwrap_or(context.nice))
+			.systemd_scope(preferences.borrow().use_systemd_scope)
+			.substitute_user(launch::parse_substitute_user(entry.attr("X-KDE-SubstituteUID"), entry.attr("X-KDE-Username")))
+			.actions(actions)
+			.wm_class(entry.attr("StartupWMClass"))
+			.popularity(context.popularity.get_bonus(id))
+			.version(entry.attr("X-AppVersion"))
+			.monitor(placement::parse_monitor(entry.attr("X-Scout-Monitor")))
+			.window_state(placement::WindowState::parse(entry.attr("X-Scout-WindowState")))
+			.mime_types(entry.attr("MimeType"))
+			.doc_url(entry.attr("X-DocumentationURL").or_else(|| entry.attr("X-GNOME-DocPath")))
+			.build())
+	}
+
+	/**
+	 * Creates results for the snippets in a snippets file, logging a warning if the file is invalid.
+	 */
+
+	fn find_snippets(preferences: &Shared<Preferences>, history: &Shared<History>, path: &std::path::Path) -> Vec<ApplicationResult> {
+		let snippets = snippets::load(path).unwrap_or_else(|err| {
+			println!("[WARN] Failed to read snippets from '{}': {}", path.display(), err);
+			vec![]
+		});
+
+		let nice = Some(preferences.borrow().nice).filter(|nice| launch::valid_nice(*nice)).unwrap_or(0);
+		snippets.into_iter().map(|snippet| {
+			ApplicationResultBuilder::new(&[ "snippet:", &snippet.name ].join(""), &snippet.name, &snippet.exec,
+				preferences.clone(), history.clone())
+				.source_path(path)
+				.category("SNIPPET")
+				.description(&snippet.description)
+				.keywords(snippet.keywords)
+				.icon(Some(snippet.icon.as_deref().unwrap_or("utilities-terminal")))
+				.nice(nice)
+				.systemd_scope(preferences.borrow().use_systemd_scope)
+				.build()
+		}).collect()
+	}
+
+	/**
+	 * Creates results for the AppImages in the `appimage_dirs`, which are run directly,
+	 * skipping those that a desktop file already launches, like ones integrated by AppImageLauncher.
+	 */
+
+	fn find_appimages(preferences: &Shared<Preferences>, history: &Shared<History>, found: &[ApplicationResult]) -> Vec<ApplicationResult> {
+		let programs: std::collections::HashSet<String> = found.iter().filter_map(|result| result.get_program()).collect();
+
+		let dirs = preferences.borrow().appimage_dirs.clone();
+		let strings = preferences.borrow().strings.clone();
+		let nice = Some(preferences.borrow().nice).filter(|nice| launch::valid_nice(*nice)).unwrap_or(0);
+		appimage::find(&dirs).iter().filter_map(|path| {
+			if programs.contains(path.to_str()?) { return None; }
+			let (name, version) = appimage::get_name(path.file_name()?.to_str()?);
+			let icon = appimage::find_icon(path);
+
+			Some(ApplicationResultBuilder::new(&[ "appimage:", &path.to_string_lossy() ].join(""), &name,
+				&shell_words::quote(&path.to_string_lossy()), preferences.clone(), history.clone())
+				.source_path(path)
+				.category(&strings.appimage_category)
+				.version(version.as_deref())
+				.icon(Some(icon.as_ref().and_then(|icon| icon.to_str()).unwrap_or("application-x-executable")))
+				.nice(nice)
+				.systemd_scope(preferences.borrow().use_systemd_scope)
+				.build())
+		}).collect()
+	}
+
+	/**
+	 * Creates results for executables, usually the ones in `PATH`, skipping programs that were already found through a desktop file.
+	 */
+
+	fn find_executables(preferences: &Shared<Preferences>, history: &Shared<History>, found: &[ApplicationResult],
+		executables: &[std::path::PathBuf]) -> Vec<ApplicationResult> {
+		let file_name = |path: &str| std::path::Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned());
+		let programs: std::collections::HashSet<String> = found.iter()
+			.filter_map(|result| result.get_program().and_then(|program| file_name(&program))).collect();
+
+		let nice = Some(preferences.borrow().nice).filter(|nice| launch::valid_nice(*nice)).unwrap_or(0);
+		executables.iter().filter_map(|path| {
+			let name = path.file_name()?.to_str()?;
+			if programs.contains(name) { return None; }
+
+			Some(ApplicationResultBuilder::new(&[ "path:", name ].join(""), name, &shell_words::quote(&path.to_string_lossy()),
+				preferences.clone(), history.clone())
+				.source_path(path)
+				.category("COMMAND")
+				.nice(nice)
+				.systemd_scope(preferences.borrow().use_systemd_scope)
+				.build())
+		}).collect()
+	}
+
+	/**
+	 * If the query is the path to an existing file, returns a result for each program that can open it,
+	 * with the default program for its type ranked first.
+	 */
+
+	fn get_open_with_results(&self, query: &str) -> Vec<ApplicationResult> {
+		let file = std::path::PathBuf::from(launch::expand_env(query.trim()));
+		if query.trim().is_empty() || !file.is_file() { return vec![]; }
+
+		let mime = match mime::guess(&file) {
+			Some(mime) => mime,
+			None => return vec![]
+		};
+
+		let default = self.defaults.borrow_mut().get(&mime);
+		ApplicationPlugin::get_openers(&self.results, &file, &mime, default.as_deref())
+	}
+
+
+	/**
+	 * Returns a result opening the file for each program that can open files of type `mime`,
+	 * with the default program, by its id, ranked first.
+	 */
+
+	fn get_openers(results: &[ApplicationResult], file: &std::path::Path, mime: &str, default: Option<&str>) -> Vec<ApplicationResult> {
+		results.iter()
+			.filter(|result| result.handles_mime(mime))
+			.map(|result| result.open_with(file, if default == Some(&result.get_id()) { 200 } else { 100 }))
+			.collect()
+	}
+
+	/**
+	 * Returns a result for each matching application with running processes, to quit them.
+	 * The query should have the `kill:` prefix removed.
+	 */
+
+	fn get_kill_results(&self, query: &str) -> Vec<KillResult> {
+		if query.trim().is_empty() { return vec![]; }
+		let processes = process::list(std::path::Path::new("/proc"));
+
+		self.results.iter().filter_map(|res| {
+			let mut result = res.clone();
+			result.set_score_from_query(query);
+			if result.get_score() == 0 { return None; }
+
+			let exe = result.get_program().and_then(|program| process::resolve_program(&program));
+			let pids = process::find_matching(&processes, exe.as_deref(), result.get_wm_class());
+			if pids.is_empty() { None } else { Some(KillResult::new(&result, pids, self.armed.clone(), self.terminated.clone())) }
+		}).collect()
+	}
+
 	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		#[cfg(feature = "gui")]
 		gtk::init().unwrap();
 
+		let preferences = Preferences::new(None);
+		let history = History::new(None);
+		let files = ApplicationPlugin::find_files(&preferences).unwrap_or_else(|err| {
+			println!("[WARN] Failed to find applications: {}", err);
+			vec![]
+		});
+		let results = ApplicationPlugin::read_applications(&files, &preferences, &history);
+		let index = CharIndex::new(results.iter().map(|result| result.get_search_names()));
+
 		Box::new(ApplicationPlugin {
 			bindings,
-			results: ApplicationPlugin::find_applications()
+			preferences,
+			history,
+			files,
+			results,
+			index,
+			cache: Shared::new(ScoreCache::default()),
+			defaults: Shared::new(mime::DefaultCache::default()),
+			armed: Shared::new(std::collections::HashSet::new()),
+			terminated: Shared::new(std::collections::HashSet::new())
 		})
 	}
 }
 
 impl Plugin for ApplicationPlugin {
 	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
-		let query = query.to_lowercase().replace(' ', "");
-		Ok(self.results.iter()
-			.map(|res| {
-				let mut result = res.clone();
-				result.set_score_from_query(&query);
+		if let Some(query) = query.trim_start().strip_prefix(KILL_PREFIX) {
+			return Ok(self.get_kill_results(query).into_iter().map(|result| Box::new(result) as Box<dyn SearchResult>).collect());
+		}
+
+		let scores = self.cache.borrow_mut().get_or_insert_with(query, || {
+			// Typos can match names without any of the query's characters, so they can't use the index.
+			let prefilter = self.preferences.borrow().prefilter && !self.preferences.borrow().typo_tolerance;
+			let candidates = if prefilter { self.index.get_candidates(query) } else { (0..self.results.len()).collect() };
+
+			candidates.into_iter()
+				.map(|ind| (ind, self.results[ind].get_query_score(query)))
+				.filter(|(_, score)| *score > 0)
+				.collect()
+		});
+
+		Ok(scores.into_iter()
+			.map(|(ind, score)| {
+				let mut result = self.results[ind].clone();
+				result.set_query_score(query, score);
 				Box::new(result) as Box<dyn SearchResult>
 			})
-			.filter(|result| result.get_score() > 0)
+			.chain(self.get_open_with_results(query).into_iter().map(|result| Box::new(result) as Box<dyn SearchResult>))
 			.collect::<Vec<Box<dyn SearchResult>>>()
 		)
 	}
+
+	// Completes the query to the name of the best matching application that starts with it, like `fire` to `Firefox`.
+	fn peek(&self, query: &str) -> scout_core::Result<Option<String>> {
+		let query = query.trim();
+		if query.chars().count() < 2 || query.starts_with(KILL_PREFIX) { return Ok(None); }
+
+		let lowercase_query = query.to_lowercase();
+		Ok(self.results.iter()
+			.filter(|result| { let name = result.get_title().to_lowercase(); name.starts_with(&lowercase_query) && name != lowercase_query })
+			.max_by_key(|result| result.get_query_score(query))
+			.map(|result| result.get_title()))
+	}
+
+	// Rescanning is for when the applications have changed, so the discovery cache is discarded rather than trusted.
+	fn refresh(&mut self) -> scout_core::Result<()> {
+		if let Err(err) = clear_cache() { println!("[WARN] Failed to clear the discovery cache: {}", err); }
+		self.files = ApplicationPlugin::find_files(&self.preferences)?;
+		self.rebuild_results();
+		self.defaults.borrow_mut().clear();
+		Ok(())
+	}
+
+	fn reload(&mut self) -> scout_core::Result<()> {
+		// The results copy many of the preferences when they are read, so they are read again from the entries already found.
+		self.preferences.borrow_mut().reload()?;
+		self.rebuild_results();
+		Ok(())
+	}
 }
 
 #[allow(improper_ctypes_definitions)]
@@ -111,3 +444,272 @@ extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
 }
 
 scout_core::export_plugin!(register);
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_context() -> EntryContext {
+		EntryContext { strings: Default::default(), nice: 0, locales: vec![], desktops: vec![], popularity: Default::default() }
+	}
+
+	/**
+	 * Reads a desktop entry with the contents specified, written to a temporary file named after its id.
+	 */
+
+	fn read_with(id: &str, contents: &str, preferences: &str, context: &EntryContext) -> Option<ApplicationResult> {
+		let dir = std::env::temp_dir().join(format!("scout-entries-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let path = dir.join([ id, ".desktop" ].join(""));
+		std::fs::write(&path, contents).unwrap();
+
+		let preferences = Shared::new(serde_json::from_str(preferences).unwrap());
+		let result = ApplicationPlugin::read_entry(&path, context, &preferences, &Shared::new(History::default()));
+		std::fs::remove_file(&path).unwrap();
+		result
+	}
+
+	fn read(id: &str, contents: &str) -> Option<ApplicationResult> {
+		read_with(id, contents, "{}", &get_context())
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn startup_wm_class_is_stored() {
+		let result = read("firefox", "[Desktop Entry]\nName=Firefox\nExec=firefox %u\nStartupWMClass=firefox-esr\n").unwrap();
+		assert_eq!(result.get_wm_class(), Some("firefox-esr"));
+		assert_eq!(read("editor", "[Desktop Entry]\nName=Editor\nExec=editor\n").unwrap().get_wm_class(), None);
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn custom_fallback_strings_are_used() {
+		let context = EntryContext {
+			strings: serde_json::from_str(r#"{ "unnamed_application": "Untitled Program", "category": "Misc" }"#).unwrap(),
+			..get_context()
+		};
+
+		let result = read_with("nameless", "[Desktop Entry]\nExec=nameless\nCategories=X-Vendor;\n", "{}", &context).unwrap();
+		assert_eq!(result.get_title(), "Untitled Program");
+		assert_eq!(result.get_category(), Some("Misc".to_owned()));
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn localized_and_c_names_both_match() {
+		let context = EntryContext { locales: vec![ "de_DE".to_owned(), "de".to_owned() ], ..get_context() };
+		let result = read_with("files", "[Desktop Entry]\nName=Files\nName[de]=Dateien\nExec=nautilus\n", "{}", &context).unwrap();
+
+		assert_eq!(result.get_title(), "Dateien");
+		assert!(result.get_query_score("dateien") > 0);
+		assert!(result.get_query_score("files") > 0);
+		assert_eq!(result.get_query_score("xyz"), 0);
+	}
+
+	#[test]
+	fn entries_with_an_empty_exec_are_skipped() {
+		assert!(read("empty-exec", "[Desktop Entry]\nName=Empty\nExec=\n").is_none());
+		assert!(read("blank-exec", "[Desktop Entry]\nName=Blank\nExec=  \n").is_none());
+		assert!(read("no-exec", "[Desktop Entry]\nName=Missing\n").is_none());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn documentation_is_only_offered_when_present() {
+		let url = read("browser", "[Desktop Entry]\nName=Browser\nExec=browser\nX-DocumentationURL=https://example.com/help\n").unwrap();
+		let path = read("gedit", "[Desktop Entry]\nName=Gedit\nExec=gedit\nX-GNOME-DocPath=gedit/gedit.xml\n").unwrap();
+		let none = read("plain", "[Desktop Entry]\nName=Plain\nExec=plain\nX-DocumentationURL= \n").unwrap();
+
+		assert_eq!(url.get_doc_url(), Some("https://example.com/help"));
+		assert_eq!(path.get_doc_url(), Some("help:gedit/gedit.xml"));
+		assert_eq!(none.get_doc_url(), None);
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn image_paths_are_opened_by_image_programs() {
+		let results = vec![
+			read("viewer", "[Desktop Entry]\nName=Image Viewer\nExec=viewer %f\nMimeType=image/png;image/jpeg;\n").unwrap(),
+			read("editor", "[Desktop Entry]\nName=Text Editor\nExec=editor %f\nMimeType=text/plain;\n").unwrap(),
+			read("gimp", "[Desktop Entry]\nName=GIMP\nExec=gimp %U\nMimeType=image/*;\n").unwrap()
+		];
+		let file = std::path::Path::new("/tmp/photo.png");
+		let mime = mime::guess(file).unwrap();
+		assert_eq!(mime, "image/png");
+
+		let openers = ApplicationPlugin::get_openers(&results, file, &mime, Some("gimp"));
+		let openers = openers.iter().map(|result| (result.get_id(), result.get_score())).collect::<Vec<_>>();
+		assert_eq!(openers, vec![ ("viewer".to_owned(), 100), ("gimp".to_owned(), 200) ]);
+		assert!(ApplicationPlugin::get_openers(&results, file, "application/pdf", None).is_empty());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn only_allowed_categories_are_discovered() {
+		let entries = [
+			("supertux", "[Desktop Entry]\nName=SuperTux\nExec=supertux2\nCategories=Game;ArcadeGame;\n"),
+			("writer", "[Desktop Entry]\nName=Writer\nExec=lowriter\nCategories=Office;WordProcessor;\n"),
+			("chess", "[Desktop Entry]\nName=Chess\nExec=gnome-chess\nCategories=GNOME;GTK;game;BoardGame;\n"),
+			("uncategorized", "[Desktop Entry]\nName=Tool\nExec=tool\n")
+		];
+		let discover = |preferences| entries.iter()
+			.filter_map(|(id, contents)| read_with(id, contents, preferences, &get_context()))
+			.map(|result| result.get_id()).collect::<Vec<_>>();
+
+		assert_eq!(discover(r#"{ "allowed_categories": [ "Game" ] }"#), vec![ "supertux", "chess" ]);
+		assert_eq!(discover("{}").len(), 4);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn executables_become_results_unless_already_found() {
+		let preferences = Shared::new(serde_json::from_str("{}").unwrap());
+		let history = Shared::new(History::default());
+		let found = vec![ read("editor", "[Desktop Entry]\nName=Text Editor\nExec=/usr/bin/editor %F\n").unwrap() ];
+		let executables = [ std::path::PathBuf::from("/usr/bin/editor"), std::path::PathBuf::from("/usr/bin/tool") ];
+
+		let results = ApplicationPlugin::find_executables(&preferences, &history, &found, &executables);
+		assert_eq!(results.iter().map(|result| result.get_id()).collect::<Vec<_>>(), vec![ "path:tool" ]);
+		assert_eq!(results[0].get_title(), "tool");
+		assert_eq!(results[0].get_program(), Some("/usr/bin/tool".to_owned()));
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn spec_versions_are_not_shown_as_the_program_version() {
+		let spec = read("blender", "[Desktop Entry]\nVersion=1.5\nName=Blender\nExec=blender %f\n").unwrap();
+		assert!(!spec.get_preview_labels().version);
+
+		let program = read("blender", "[Desktop Entry]\nVersion=1.5\nName=Blender\nExec=blender %f\nX-AppVersion=4.2\n").unwrap();
+		assert!(program.get_preview_labels().version);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn kde_substitute_uid_entries_run_as_another_user() {
+		let result = read("partitionmanager", "[Desktop Entry]\nName=Partition Manager\nExec=partitionmanager\nX-KDE-SubstituteUID=true\n").unwrap();
+		assert_eq!(result.get_substitute_user(), Some("root"));
+
+		let result = read("partitionmanager", "[Desktop Entry]\nName=Partition Manager\nExec=partitionmanager\n").unwrap();
+		assert_eq!(result.get_substitute_user(), None);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn snippets_become_ranked_results() {
+		let path = std::env::temp_dir().join(format!("scout-snippets-{}.toml", std::process::id()));
+		std::fs::write(&path, "[[snippet]]\nname = \"Work Server\"\nkeywords = [ \"ssh\" ]\nexec = \"gnome-terminal -- ssh work\"\n\n\
+			[[snippet]]\nname = \"Docker Cleanup\"\nexec = \"docker system prune -f\"\nicon = \"docker\"\n").unwrap();
+
+		let preferences = Shared::new(serde_json::from_str("{}").unwrap());
+		let mut results = ApplicationPlugin::find_snippets(&preferences, &Shared::new(History::default()), &path);
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(results.iter().map(|result| result.get_id()).collect::<Vec<_>>(), vec![ "snippet:Work Server", "snippet:Docker Cleanup" ]);
+		assert_eq!(results[0].get_icon_name(), Some("utilities-terminal".to_owned()));
+		assert_eq!(results[1].get_icon_name(), Some("docker".to_owned()));
+
+		for result in results.iter_mut() { result.set_score_from_query("ssh"); }
+		assert!(results[0].get_score() > 0);
+		assert_eq!(results[1].get_score(), 0);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn popularity_shifts_the_order_of_equal_matches() {
+		let read_browsers = |context: &EntryContext| {
+			let mut results: Vec<_> = [ "alpha", "omega" ].iter()
+				.map(|id| read_with(id, &[ "[Desktop Entry]\nName=Browser ", id, "\nExec=browser\n" ].join(""), "{}", context).unwrap())
+				.collect();
+			for result in results.iter_mut() { result.set_score_from_query("browser"); }
+			(results[0].get_score(), results[1].get_score())
+		};
+
+		let (alpha, omega) = read_browsers(&get_context());
+		assert_eq!(alpha, omega);
+
+		let context = EntryContext { popularity: popularity::Popularity::parse("omega 100\nalpha 10\n"), ..get_context() };
+		let (alpha, omega) = read_browsers(&context);
+		assert!(omega > alpha);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn appimages_become_named_results() {
+		let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/appimages");
+		let preferences = Shared::new(serde_json::from_str(&serde_json::json!({ "appimage_dirs": [ fixture ] }).to_string()).unwrap());
+		let results = ApplicationPlugin::find_appimages(&preferences, &Shared::new(History::default()), &[]);
+
+		assert_eq!(results.iter().map(|result| result.get_title()).collect::<Vec<_>>(), vec![ "Kdenlive", "Obsidian" ]);
+		assert_eq!(results[0].get_icon_name(), Some(fixture.join("Kdenlive-23.08.1-x86_64.png").to_string_lossy().into_owned()));
+		assert_eq!(results[0].get_program(), Some(fixture.join("Kdenlive-23.08.1-x86_64.AppImage").to_string_lossy().into_owned()));
+		assert_eq!(results[1].get_icon_name(), Some("application-x-executable".to_owned()));
+	}
+
+	struct NoBindings;
+
+	impl PluginBindings for NoBindings {
+		fn register(&mut self, _name: &str, _function: Box<dyn Plugin>) {}
+		fn add_stylesheet(&mut self, _stylesheet: &'static str) {}
+		fn get_plugin(&self) -> scout_core::Result<(&str, Shared<Box<dyn Plugin>>)> {
+			Err(scout_core::InvocationError::Other("There are no plugins in tests.".to_owned()))
+		}
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn reloading_reads_the_found_entries_with_the_new_preferences() {
+		let dir = std::env::temp_dir().join(format!("scout-reload-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("preferences.json");
+		let files = vec![ dir.join("writer.desktop"), dir.join("chess.desktop") ];
+		std::fs::write(&files[0], "[Desktop Entry]\nName=Scout Writer\nExec=writer\nCategories=Office;\n").unwrap();
+		std::fs::write(&files[1], "[Desktop Entry]\nName=Scout Chess\nExec=chess\nCategories=Game;\n").unwrap();
+
+		let mut preferences = serde_json::from_str::<Preferences>("{}").unwrap();
+		preferences.path = path.clone();
+		let preferences = Shared::new(preferences);
+		let history = Shared::new(History::default());
+		let results = ApplicationPlugin::read_applications(&files, &preferences, &history);
+		let mut plugin = ApplicationPlugin {
+			bindings: Shared::new(Box::new(NoBindings) as Box<dyn PluginBindings>),
+			index: CharIndex::new(results.iter().map(|result| result.get_search_names())),
+			preferences,
+			history,
+			files,
+			results,
+			cache: Shared::new(ScoreCache::default()),
+			defaults: Shared::new(mime::DefaultCache::default()),
+			armed: Shared::new(std::collections::HashSet::new()),
+			terminated: Shared::new(std::collections::HashSet::new())
+		};
+		let titles = |plugin: &ApplicationPlugin| {
+			let mut titles: Vec<String> = plugin.get_results("scout").unwrap().iter().map(|result| result.get_title()).collect();
+			titles.sort();
+			titles
+		};
+		assert_eq!(titles(&plugin), vec![ "Scout Chess", "Scout Writer" ]);
+
+		std::fs::write(&path, r#"{ "allowed_categories": [ "Game" ] }"#).unwrap();
+		plugin.reload().unwrap();
+		assert_eq!(titles(&plugin), vec![ "Scout Chess" ]);
+
+		std::fs::write(&path, r#"{ "allowed_categories": [ "Game", "Office" ] }"#).unwrap();
+		plugin.reload().unwrap();
+		assert_eq!(titles(&plugin), vec![ "Scout Chess", "Scout Writer" ]);
+
+		std::fs::write(&path, r#"{ "allowed_categories": "#).unwrap();
+		assert!(plugin.reload().is_err());
+		assert_eq!(titles(&plugin), vec![ "Scout Chess", "Scout Writer" ]);
+		assert_eq!(plugin.preferences.borrow().path, path);
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,50 @@
+/*!
+ * Parsing of the semicolon separated lists used by desktop entry keys like `Categories` and `MimeType`.
+ */
+
+/**
+ * Splits a list value into its items, trimming each and dropping empty ones, such as after the trailing `;`.
+ * `\;` is a literal semicolon inside an item, and `\\` is a literal backslash.
+ */
+
+pub fn parse_list(value: Option<&str>) -> Vec<String> {
+	let mut items = vec![];
+	let mut item = String::new();
+	let mut chars = value.unwrap_or("").chars();
+
+	while let Some(c) = chars.next() {
+		match c {
+			';' => items.push(std::mem::take(&mut item)),
+			'\\' => match chars.next() {
+				Some(';') => item.push(';'),
+				Some('\\') => item.push('\\'),
+				Some(other) => { item.push('\\'); item.push(other); },
+				None => item.push('\\')
+			},
+			_ => item.push(c)
+		}
+	}
+	items.push(item);
+
+	items.into_iter().map(|item| item.trim().to_owned()).filter(|item| !item.is_empty()).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn items_are_trimmed_without_empty_ones() {
+		assert_eq!(parse_list(Some("Utility;TextEditor;")), vec![ "Utility", "TextEditor" ]);
+		assert_eq!(parse_list(Some(" text ; ;notes")), vec![ "text", "notes" ]);
+		assert_eq!(parse_list(Some(";")), Vec::<String>::new());
+		assert_eq!(parse_list(None), Vec::<String>::new());
+	}
+
+	#[test]
+	fn escaped_semicolons_stay_in_the_item() {
+		assert_eq!(parse_list(Some(r"rock\;roll;music;")), vec![ "rock;roll", "music" ]);
+		assert_eq!(parse_list(Some(r"C:\\\;D:\n;end\")), vec![ r"C:\;D:\n", r"end\" ]);
+	}
+}
@@ -0,0 +1,42 @@
+/*!
+ * Lookup of localized desktop entry values, following the desktop entry specification's locale matching.
+ */
+
+use freedesktop_entry_parser::AttrSelector;
+
+/**
+ * Returns the locale names that localized keys are matched against, from most to least specific.
+ * For a locale of `lang_COUNTRY@MODIFIER`, these are `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, and `lang`.
+ */
+
+pub fn get_locales() -> Vec<String> {
+	let locale = [ "LC_ALL", "LC_MESSAGES", "LANG" ].iter()
+		.filter_map(|var| std::env::var(var).ok())
+		.find(|locale| !locale.is_empty())
+		.unwrap_or_default();
+
+	let locale = locale.split('.').next().unwrap_or("");
+	if locale.is_empty() || locale == "C" || locale == "POSIX" { return vec![]; }
+
+	let (locale, modifier) = match locale.find('@') {
+		Some(ind) => (&locale[..ind], Some(&locale[ind..])),
+		None => (locale, None)
+	};
+	let lang = locale.split('_').next().unwrap_or(locale);
+
+	let mut locales = vec![];
+	if let Some(modifier) = modifier { locales.push([ locale, modifier ].join("")); }
+	if locale != lang { locales.push(locale.to_owned()); }
+	if let Some(modifier) = modifier { if locale != lang { locales.push([ lang, modifier ].join("")); } }
+	locales.push(lang.to_owned());
+	locales
+}
+
+
+/**
+ * Gets the value of a key for the first matching locale, or the unlocalized value if there are none.
+ */
+
+pub fn get_localized<'a, T: AsRef<str>>(entry: &'a AttrSelector<T>, key: &str, locales: &[String]) -> Option<&'a str> {
+	locales.iter().find_map(|locale| entry.attr_with_param(key, locale)).or_else(|| entry.attr(key))
+}
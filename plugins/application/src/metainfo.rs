@@ -0,0 +1,123 @@
+/*!
+ * Reading of AppStream metainfo files, which can describe applications in more detail than their desktop entries.
+ * Only the unlocalized summary and keywords are read, using a minimal parser rather than a full XML parser.
+ */
+
+/** The directories that metainfo files are installed to. */
+const METAINFO_DIRS: [&str; 2] = [ "/usr/share/metainfo", "/usr/share/appdata" ];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metainfo {
+	pub id: Option<String>,
+	pub summary: Option<String>,
+	pub keywords: Vec<String>
+}
+
+
+/**
+ * Replaces the predefined XML entities in a string.
+ */
+
+fn unescape(text: &str) -> String {
+	text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+
+/**
+ * Returns the text of every element with the tag specified that isn't localized with `xml:lang`.
+ */
+
+fn get_elements(contents: &str, tag: &str) -> Vec<String> {
+	let open = [ "<", tag ].join("");
+	let close = [ "</", tag, ">" ].join("");
+
+	let mut elements = vec![];
+	let mut rest = contents;
+
+	while let Some(start) = rest.find(&open) {
+		rest = &rest[start + open.len()..];
+		let tag_end = match rest.find('>') { Some(ind) => ind, None => break };
+		let attrs = &rest[..tag_end];
+
+		// Skip tags that only share a prefix, like `<keywords>` when looking for `<keyword>`.
+		if !attrs.is_empty() && !attrs.starts_with(char::is_whitespace) { continue; }
+
+		rest = &rest[tag_end + 1..];
+		let end = match rest.find(&close) { Some(ind) => ind, None => break };
+		if !attrs.contains("xml:lang") { elements.push(unescape(rest[..end].trim())); }
+		rest = &rest[end + close.len()..];
+	}
+
+	elements
+}
+
+
+/**
+ * Parses the contents of a metainfo file.
+ */
+
+pub fn parse(contents: &str) -> Metainfo {
+	Metainfo {
+		id: get_elements(contents, "id").into_iter().next(),
+		summary: get_elements(contents, "summary").into_iter().next(),
+		keywords: get_elements(contents, "keyword")
+	}
+}
+
+
+/**
+ * Finds and parses the metainfo file for the desktop entry id specified.
+ * Returns `None` if there isn't one, or its component id doesn't match the desktop entry.
+ */
+
+pub fn find(id: &str) -> Option<Metainfo> {
+	let desktop_id = [ id, ".desktop" ].join("");
+
+	METAINFO_DIRS.iter()
+		.flat_map(|dir| [ "metainfo.xml", "appdata.xml" ].iter().map(move |ext| format!("{}/{}.{}", dir, id, ext)))
+		.filter_map(|path| std::fs::read_to_string(path).ok())
+		.map(|contents| parse(&contents))
+		.find(|metainfo| metainfo.id.as_deref().is_some_and(|component| component == id || component == desktop_id))
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scout_core::Shared;
+	use crate::history::History;
+	use crate::result::ApplicationResultBuilder;
+
+	const METAINFO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<component type="desktop-application">
+  <id>org.kde.krita</id>
+  <name>Krita</name>
+  <summary>Digital Painting, Creative Freedom</summary>
+  <summary xml:lang="de">Digitales Malen, kreative Freiheit</summary>
+  <keywords>
+    <keyword>paint</keyword>
+    <keyword xml:lang="de">malen</keyword>
+    <keyword>sketch &amp; draw</keyword>
+  </keywords>
+</component>"#;
+
+	#[test]
+	fn unlocalized_summaries_and_keywords_are_read() {
+		assert_eq!(parse(METAINFO), Metainfo {
+			id: Some("org.kde.krita".to_owned()),
+			summary: Some("Digital Painting, Creative Freedom".to_owned()),
+			keywords: vec![ "paint".to_owned(), "sketch & draw".to_owned() ]
+		});
+		assert_eq!(parse("<component></component>"), Metainfo::default());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn keywords_make_results_match() {
+		let build = |keywords| ApplicationResultBuilder::new("org.kde.krita", "Krita", "krita %F",
+			Shared::new(serde_json::from_str("{}").unwrap()), Shared::new(History::default())).keywords(keywords).build();
+
+		assert_eq!(build(vec![]).get_query_score("paint"), 0);
+		assert!(build(parse(METAINFO).keywords).get_query_score("paint") > 0);
+	}
+}
@@ -0,0 +1,63 @@
+/*!
+ * Matching of files to the applications that can open them, using the `MimeType` key.
+ */
+
+/**
+ * Guesses the MIME type of a file from its extension.
+ */
+
+pub fn guess(path: &std::path::Path) -> Option<String> {
+	mime_guess::from_path(path).first_raw().map(|mime| mime.to_owned())
+}
+
+
+/**
+ * Returns true if an application with the `MimeType` values specified can open files of type `mime`.
+ * A wildcard subtype matches every subtype of its media type.
+ */
+
+pub fn handles(mime_types: &[String], mime: &str) -> bool {
+	let media_type = mime.split('/').next().unwrap_or("");
+	mime_types.iter().any(|handled| handled == mime
+		|| handled.strip_suffix("/*").is_some_and(|handled_type| handled_type == media_type))
+}
+
+
+/**
+ * Gets the id of the default application for a MIME type from `xdg-mime`, without the `.desktop` extension.
+ */
+
+pub fn get_default(mime: &str) -> Option<String> {
+	let output = std::process::Command::new("xdg-mime").args(&[ "query", "default", mime ])
+		.stderr(std::process::Stdio::null()).output().ok()?;
+
+	let id = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+	let id = id.strip_suffix(".desktop").unwrap_or(&id).to_owned();
+	if id.is_empty() { None } else { Some(id) }
+}
+
+
+/**
+ * The default application of each MIME type that has been looked up, so that `xdg-mime` is only run once per type.
+ * It should be cleared when applications are discovered again, since the defaults may have changed.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct DefaultCache {
+	defaults: std::collections::HashMap<String, Option<String>>
+}
+
+impl DefaultCache {
+
+	/**
+	 * Gets the id of the default application for a MIME type, running `xdg-mime` if it hasn't been looked up yet.
+	 */
+
+	pub fn get(&mut self, mime: &str) -> Option<String> {
+		self.defaults.entry(mime.to_owned()).or_insert_with(|| get_default(mime)).clone()
+	}
+
+	pub fn clear(&mut self) {
+		self.defaults.clear();
+	}
+}
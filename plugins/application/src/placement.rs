@@ -0,0 +1,374 @@
+/*!
+ * Best-effort placement of launched windows, using the `X-Scout-Monitor` and `X-Scout-WindowState` keys.
+ * This needs `wmctrl` and an EWMH window manager on X11, and `xdotool` to minimize windows, and silently does nothing otherwise.
+ * Only windows that appear after launching are placed, so a program's existing windows are left where they are.
+ * Launched windows can also be focused with a configurable command, like `swaymsg` or `xdotool`.
+ */
+
+/** How long to wait for a launched program's window to appear. */
+const WINDOW_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/** How often to check whether a launched program's window has appeared. */
+const WINDOW_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/**
+ * The position and size of a monitor, in the screen's coordinates.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+	pub x: i32,
+	pub y: i32,
+	pub width: i32,
+	pub height: i32
+}
+
+/**
+ * The state a launched program's window is put in, from the `X-Scout-WindowState` key.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowState {
+	Normal,
+	Maximized,
+	Minimized,
+	Fullscreen
+}
+
+impl WindowState {
+
+	/**
+	 * Parses the value of an `X-Scout-WindowState` key, ignoring case.
+	 */
+
+	pub fn parse(value: Option<&str>) -> Option<WindowState> {
+		match value?.trim().to_ascii_lowercase().as_str() {
+			"normal" => Some(WindowState::Normal),
+			"maximized" => Some(WindowState::Maximized),
+			"minimized" => Some(WindowState::Minimized),
+			"fullscreen" => Some(WindowState::Fullscreen),
+			_ => None
+		}
+	}
+
+
+	/**
+	 * Returns the EWMH `_NET_WM_STATE` change that puts a window in this state, in `wmctrl -b` syntax.
+	 * Minimizing has no hint, since the hidden state can only be set by the window manager, so it returns `None`.
+	 */
+
+	pub fn get_hint(&self) -> Option<&'static str> {
+		match self {
+			WindowState::Normal => Some("remove,maximized_vert,maximized_horz"),
+			WindowState::Maximized => Some("add,maximized_vert,maximized_horz"),
+			WindowState::Minimized => None,
+			WindowState::Fullscreen => Some("add,fullscreen")
+		}
+	}
+
+
+	/**
+	 * Returns the command that puts a window in this state, given its id from `wmctrl -l`.
+	 * Windows are minimized with `xdotool windowminimize`, which needs the id in decimal, and the other states are set with `wmctrl -b`.
+	 */
+
+	pub fn get_command(&self, id: &str) -> Option<Vec<String>> {
+		match self.get_hint() {
+			Some(hint) => Some(vec![ "wmctrl".to_owned(), "-i".to_owned(), "-r".to_owned(), id.to_owned(), "-b".to_owned(), hint.to_owned() ]),
+			None => {
+				let id = u64::from_str_radix(id.trim_start_matches("0x"), 16).ok()?;
+				Some(vec![ "xdotool".to_owned(), "windowminimize".to_owned(), id.to_string() ])
+			}
+		}
+	}
+}
+
+
+/**
+ * Parses the zero-based monitor index of an `X-Scout-Monitor` key.
+ */
+
+pub fn parse_monitor(value: Option<&str>) -> Option<usize> {
+	value?.trim().parse::<usize>().ok()
+}
+
+
+/**
+ * Returns the position a window is moved to, to place it on a monitor.
+ * Returns `None` if the monitor doesn't exist.
+ */
+
+pub fn get_target_position(monitor: usize, monitors: &[Geometry]) -> Option<(i32, i32)> {
+	monitors.get(monitor).map(|geometry| (geometry.x, geometry.y))
+}
+
+
+/**
+ * Gets the geometry of every monitor of the default display.
+ */
+
+#[cfg(feature = "gui")]
+pub fn get_monitors() -> Vec<Geometry> {
+	let display = match gdk::Display::get_default() {
+		Some(display) => display,
+		None => return vec![]
+	};
+
+	(0..display.get_n_monitors()).filter_map(|ind| display.get_monitor(ind)).map(|monitor| {
+		let rect = monitor.get_geometry();
+		Geometry { x: rect.x, y: rect.y, width: rect.width, height: rect.height }
+	}).collect()
+}
+
+#[cfg(not(feature = "gui"))]
+pub fn get_monitors() -> Vec<Geometry> {
+	vec![]
+}
+
+
+/**
+ * A window that is placed once a launched program maps it. The windows that already exist are recorded before launching,
+ * so that only a new window with the program's `StartupWMClass` is moved, rather than one the program already had open.
+ */
+
+#[derive(Debug, Clone)]
+pub struct Placement {
+	wm_class: String,
+	position: Option<(i32, i32)>,
+	state: Option<WindowState>,
+	existing: Vec<String>
+}
+
+impl Placement {
+
+	/**
+	 * Prepares to move a program's window to a monitor and put it in a window state, if they are specified.
+	 * This must be created before the program is launched. Returns `None` on Wayland or if there is nothing to do.
+	 */
+
+	pub fn new(wm_class: &str, monitor: Option<usize>, state: Option<WindowState>) -> Option<Placement> {
+		if std::env::var_os("WAYLAND_DISPLAY").is_some() { return None; }
+
+		let position = monitor.and_then(|monitor| get_target_position(monitor, &get_monitors()));
+		if position.is_none() && state.is_none() { return None; }
+
+		let existing = list_windows().ok()?.into_iter().map(|(id, _)| id).collect();
+		Some(Placement { wm_class: wm_class.to_owned(), position, state, existing })
+	}
+
+
+	/**
+	 * Returns the commands that place the window with the id specified, moving it before changing its state.
+	 */
+
+	pub fn get_commands(&self, id: &str) -> Vec<Vec<String>> {
+		let position = self.position.map(|(x, y)| vec![ "wmctrl".to_owned(), "-i".to_owned(), "-r".to_owned(), id.to_owned(),
+			"-e".to_owned(), format!("0,{},{},-1,-1", x, y) ]);
+		position.into_iter().chain(self.state.and_then(|state| state.get_command(id))).collect()
+	}
+
+
+	/**
+	 * Waits in the background for a new window with the program's `StartupWMClass` to appear, then places it.
+	 */
+
+	pub fn apply(self) {
+		std::thread::spawn(move || {
+			let start = std::time::Instant::now();
+			while start.elapsed() < WINDOW_TIMEOUT {
+				std::thread::sleep(WINDOW_POLL_INTERVAL);
+
+				let windows = match list_windows() {
+					Ok(windows) => windows,
+					Err(_) => break
+				};
+				if let Some(id) = find_new_window(&windows, &self.existing, &self.wm_class) {
+					for command in self.get_commands(&id) { run_command(&command); }
+					break;
+				}
+			}
+		});
+	}
+}
+
+
+/**
+ * Parses the output of `wmctrl -lx` into the id and `WM_CLASS` of each window, like `navigator.Firefox`.
+ */
+
+pub fn parse_window_list(output: &str) -> Vec<(String, String)> {
+	output.lines().filter_map(|line| {
+		let mut columns = line.split_whitespace();
+		let id = columns.next()?;
+		let class = columns.nth(1)?;
+		Some((id.to_owned(), class.to_owned()))
+	}).collect()
+}
+
+
+/**
+ * Returns the id of the first window that isn't one of the existing windows,
+ * and whose `WM_CLASS` instance or class name is the `StartupWMClass`, ignoring case.
+ */
+
+pub fn find_new_window(windows: &[(String, String)], existing: &[String], wm_class: &str) -> Option<String> {
+	windows.iter()
+		.filter(|(id, _)| !existing.contains(id))
+		.find(|(_, class)| class.split('.').any(|name| name.eq_ignore_ascii_case(wm_class)))
+		.map(|(id, _)| id.clone())
+}
+
+
+/**
+ * Lists the id and `WM_CLASS` of every window with `wmctrl -lx`.
+ */
+
+fn list_windows() -> std::io::Result<Vec<(String, String)>> {
+	let output = std::process::Command::new("wmctrl").arg("-lx").stderr(std::process::Stdio::null()).output()?;
+	if !output.status.success() { return Err(std::io::Error::new(std::io::ErrorKind::Other, "wmctrl failed to list windows")); }
+	Ok(parse_window_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+
+/**
+ * Builds the arguments of a `focus_command`, replacing `%w` in each argument with the window's class or app id.
+ * The template is split into arguments first, so the class is never interpreted by a shell. Returns `None` if it is empty or malformed.
+ */
+
+pub fn get_focus_command(template: &str, wm_class: &str) -> Option<Vec<String>> {
+	let args = shell_words::split(template).ok()?;
+	if args.is_empty() { return None; }
+	Some(args.iter().map(|arg| arg.replace("%w", wm_class)).collect())
+}
+
+
+/**
+ * Runs the `focus_command` in the background until it succeeds, so that the launched program's window
+ * is focused once it appears instead of Scout keeping the focus. Gives up after the window timeout.
+ */
+
+pub fn focus_window(template: &str, wm_class: &str) {
+	let args = match get_focus_command(template, wm_class) {
+		Some(args) => args,
+		None => { println!("[WARN] Ignoring the focus command '{}', it is empty or malformed.", template); return; }
+	};
+
+	std::thread::spawn(move || {
+		let start = std::time::Instant::now();
+		while start.elapsed() < WINDOW_TIMEOUT {
+			std::thread::sleep(WINDOW_POLL_INTERVAL);
+
+			let status = std::process::Command::new(&args[0]).args(&args[1..])
+				.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status();
+			match status {
+				Ok(status) if status.success() => break,
+				Ok(_) => continue,
+				Err(err) => { println!("[WARN] Failed to run the focus command '{}': {}", &args[0], err); break }
+			}
+		}
+	});
+}
+
+
+/**
+ * Runs a placement command, ignoring failures since placement is best-effort.
+ */
+
+fn run_command(args: &[String]) {
+	drop(std::process::Command::new(&args[0]).args(&args[1..])
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status());
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MONITORS: [Geometry; 2] = [
+		Geometry { x: 0, y: 0, width: 1920, height: 1080 },
+		Geometry { x: 1920, y: 0, width: 2560, height: 1440 }
+	];
+
+	const WINDOW_LIST: &str = "0x03a00003  0 navigator.Firefox     laptop Mozilla Firefox\n\
+		0x04200007 -1 xfce4-panel.Xfce4-panel  laptop xfce4-panel\n\
+		0x05000001  1 navigator.Firefox     laptop New Tab - Mozilla Firefox\n\
+		0x06000002  0 mpv.mpv  laptop video.mkv - mpv\n\
+		malformed\n";
+
+	#[test]
+	fn monitors_are_parsed_and_located() {
+		assert_eq!(parse_monitor(Some(" 1 ")), Some(1));
+		assert_eq!(parse_monitor(Some("-1")), None);
+		assert_eq!(parse_monitor(Some("left")), None);
+		assert_eq!(parse_monitor(None), None);
+
+		assert_eq!(get_target_position(0, &MONITORS), Some((0, 0)));
+		assert_eq!(get_target_position(1, &MONITORS), Some((1920, 0)));
+		assert_eq!(get_target_position(2, &MONITORS), None);
+	}
+
+	#[test]
+	fn window_lists_are_parsed_into_ids_and_classes() {
+		assert_eq!(parse_window_list(WINDOW_LIST), vec![
+			("0x03a00003".to_owned(), "navigator.Firefox".to_owned()),
+			("0x04200007".to_owned(), "xfce4-panel.Xfce4-panel".to_owned()),
+			("0x05000001".to_owned(), "navigator.Firefox".to_owned()),
+			("0x06000002".to_owned(), "mpv.mpv".to_owned())
+		]);
+	}
+
+	#[test]
+	fn only_new_windows_of_the_program_are_found() {
+		let windows = parse_window_list(WINDOW_LIST);
+		let existing = vec![ "0x03a00003".to_owned() ];
+
+		assert_eq!(find_new_window(&windows, &existing, "firefox"), Some("0x05000001".to_owned()));
+		assert_eq!(find_new_window(&windows, &existing, "Navigator"), Some("0x05000001".to_owned()));
+		assert_eq!(find_new_window(&windows, &[], "firefox"), Some("0x03a00003".to_owned()));
+		assert_eq!(find_new_window(&windows, &existing, "fire"), None);
+		assert_eq!(find_new_window(&windows, &[ "0x06000002".to_owned() ], "mpv"), None);
+	}
+
+	#[test]
+	fn windows_are_moved_to_the_target_monitor() {
+		let placement = Placement { wm_class: "mpv".to_owned(), position: get_target_position(1, &MONITORS), state: None, existing: vec![] };
+		assert_eq!(placement.get_commands("0x06000002"), vec![ vec![ "wmctrl", "-i", "-r", "0x06000002", "-e", "0,1920,0,-1,-1" ] ]);
+	}
+
+
+	#[test]
+	fn window_states_are_parsed_into_hints() {
+		assert_eq!(WindowState::parse(Some(" Maximized ")), Some(WindowState::Maximized));
+		assert_eq!(WindowState::parse(Some("FULLSCREEN")), Some(WindowState::Fullscreen));
+		assert_eq!(WindowState::parse(Some("hidden")), None);
+		assert_eq!(WindowState::parse(None), None);
+
+		assert_eq!(WindowState::Normal.get_hint(), Some("remove,maximized_vert,maximized_horz"));
+		assert_eq!(WindowState::Maximized.get_hint(), Some("add,maximized_vert,maximized_horz"));
+		assert_eq!(WindowState::Fullscreen.get_hint(), Some("add,fullscreen"));
+		assert_eq!(WindowState::Minimized.get_hint(), None);
+	}
+
+	#[test]
+	fn window_states_are_applied_with_wmctrl_or_xdotool() {
+		assert_eq!(WindowState::Fullscreen.get_command("0x06000002").unwrap(), vec![ "wmctrl", "-i", "-r", "0x06000002", "-b", "add,fullscreen" ]);
+		assert_eq!(WindowState::Minimized.get_command("0x06000002").unwrap(), vec![ "xdotool", "windowminimize", "100663298" ]);
+		assert_eq!(WindowState::Minimized.get_command("malformed"), None);
+
+		let placement = Placement { wm_class: "mpv".to_owned(), position: Some((1920, 0)), state: Some(WindowState::Maximized), existing: vec![] };
+		assert_eq!(placement.get_commands("0x06000002"), vec![
+			vec![ "wmctrl", "-i", "-r", "0x06000002", "-e", "0,1920,0,-1,-1" ],
+			vec![ "wmctrl", "-i", "-r", "0x06000002", "-b", "add,maximized_vert,maximized_horz" ]
+		]);
+	}
+
+
+	#[test]
+	fn focus_commands_substitute_the_window_class() {
+		assert_eq!(get_focus_command(r#"swaymsg '[app_id="%w"] focus'"#, "firefox").unwrap(), vec![ "swaymsg", r#"[app_id="firefox"] focus"# ]);
+		assert_eq!(get_focus_command("xdotool search --class %w windowactivate", "Navigator; rm -rf ~").unwrap(),
+			vec![ "xdotool", "search", "--class", "Navigator; rm -rf ~", "windowactivate" ]);
+		assert_eq!(get_focus_command("", "firefox"), None);
+		assert_eq!(get_focus_command("swaymsg 'unclosed", "firefox"), None);
+	}
+}
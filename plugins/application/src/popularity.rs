@@ -0,0 +1,96 @@
+/*!
+ * Optional system-wide popularity data, like popularity-contest counts, used to rank popular applications slightly higher.
+ * The file has one application per line, its desktop file id followed by its count, like `org.gnome.Nautilus 5231`.
+ */
+
+use std::collections::HashMap;
+
+/** The bonus given to the most popular application, with the others scaled by their counts. */
+pub const MAX_POPULARITY_BONUS: usize = 10;
+
+/**
+ * The popularity counts of applications, by desktop file id.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct Popularity {
+	counts: HashMap<String, f64>,
+	max: f64
+}
+
+impl Popularity {
+
+	/**
+	 * Parses popularity data, skipping malformed lines and `#` comments.
+	 * Ids may include their `.desktop` suffix.
+	 */
+
+	pub fn parse(contents: &str) -> Self {
+		let counts: HashMap<String, f64> = contents.lines()
+			.filter(|line| !line.trim_start().starts_with('#'))
+			.filter_map(|line| {
+				let mut parts = line.split_whitespace();
+				let id = parts.next()?;
+				let count = parts.next()?.parse::<f64>().ok().filter(|count| *count >= 0.0)?;
+				Some((id.strip_suffix(".desktop").unwrap_or(id).to_owned(), count))
+			})
+			.collect();
+
+		let max = counts.values().cloned().fold(0.0, f64::max);
+		Popularity { counts, max }
+	}
+
+
+	/**
+	 * Reads popularity data from the file specified. There is no popularity data
+	 * if no file is configured or it can't be read, so rankings are unchanged.
+	 */
+
+	pub fn load(path: Option<&str>) -> Self {
+		let path = match path.filter(|path| !path.is_empty()) {
+			Some(path) => path,
+			None => return Popularity::default()
+		};
+
+		match std::fs::read_to_string(path) {
+			Ok(contents) => Popularity::parse(&contents),
+			Err(err) => {
+				println!("[WARN] Failed to read popularity data from '{}': {}", path, err);
+				Popularity::default()
+			}
+		}
+	}
+
+
+	/**
+	 * Returns the ranking bonus of an application, proportional to its count.
+	 */
+
+	pub fn get_bonus(&self, id: &str) -> usize {
+		if self.max <= 0.0 { return 0; }
+		self.counts.get(id).map_or(0, |count| (count / self.max * MAX_POPULARITY_BONUS as f64).round() as usize)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bonuses_are_proportional_to_the_most_popular() {
+		let popularity = Popularity::parse("# id count\norg.gnome.Nautilus.desktop 400\nfirefox 200\nmalformed\ngimp -5\n");
+		assert_eq!(popularity.get_bonus("org.gnome.Nautilus"), MAX_POPULARITY_BONUS);
+		assert_eq!(popularity.get_bonus("firefox"), MAX_POPULARITY_BONUS / 2);
+		assert_eq!(popularity.get_bonus("gimp"), 0);
+		assert_eq!(popularity.get_bonus("missing"), 0);
+	}
+
+	#[test]
+	fn missing_files_have_no_popularity() {
+		assert_eq!(Popularity::load(None).get_bonus("firefox"), 0);
+		assert_eq!(Popularity::load(Some("")).get_bonus("firefox"), 0);
+		assert_eq!(Popularity::load(Some("/scout/missing/popularity")).get_bonus("firefox"), 0);
+		assert_eq!(Popularity::parse("firefox 0\n").get_bonus("firefox"), 0);
+	}
+}
@@ -0,0 +1,396 @@
+use serde::{ Serialize, Deserialize };
+use std::io::{ Read, Write };
+
+use scout_core::Shared;
+
+use crate::error::ScoutError;
+use crate::desktop::UnknownDesktop;
+
+fn default_unnamed_application() -> String { "Unnamed Application".to_owned() }
+
+fn default_unnamed_action() -> String { "Unnamed Action".to_owned() }
+
+fn default_category() -> String { "APPLICATION".to_owned() }
+
+fn default_profile_action() -> String { "{} Profile".to_owned() }
+
+fn default_launching() -> String { "Launching {}".to_owned() }
+
+fn default_appimage_category() -> String { "APPIMAGE".to_owned() }
+
+/** Strings shown in results, like the fallbacks for values a desktop entry is missing, which can be changed for localization. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Strings {
+
+	#[serde(default = "default_unnamed_application")]
+	pub unnamed_application: String,
+
+	#[serde(default = "default_unnamed_action")]
+	pub unnamed_action: String,
+
+	/** The category of entries without a displayable one, shown exactly as written. */
+	#[serde(default = "default_category")]
+	pub category: String,
+
+	/** The name of an action launching an `X-Scout-Profiles` profile, with `{}` replaced by the profile's name. */
+	#[serde(default = "default_profile_action")]
+	pub profile_action: String,
+
+	/** The summary of the notification shown when a program is launched, with `{}` replaced by the program's name. */
+	#[serde(default = "default_launching")]
+	pub launching: String,
+
+	/** The category of AppImages found in the `appimage_dirs`. */
+	#[serde(default = "default_appimage_category")]
+	pub appimage_category: String
+}
+
+impl Default for Strings {
+	fn default() -> Self {
+		serde_json::from_str("{}").unwrap()
+	}
+}
+
+fn default_name_weight() -> f64 { 1.0 }
+
+fn default_generic_name_weight() -> f64 { 0.5 }
+
+fn default_keywords_weight() -> f64 { 0.5 }
+
+fn default_id_weight() -> f64 { 0.33 }
+
+fn default_exec_weight() -> f64 { 0.25 }
+
+/**
+ * The multipliers of the score of a match in each field of an entry. The best weighted match is used.
+ * Weights are clamped between 0 and 1 when scoring, and no other field can weigh more than the name, see `clamped`.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct FieldWeights {
+
+	/** Applies to the localized and unlocalized names, including without their stop words. */
+	#[serde(default = "default_name_weight")]
+	pub name: f64,
+
+	#[serde(default = "default_generic_name_weight")]
+	pub generic_name: f64,
+
+	#[serde(default = "default_keywords_weight")]
+	pub keywords: f64,
+
+	/** Applies to the desktop file ID and its dot-separated components. */
+	#[serde(default = "default_id_weight")]
+	pub id: f64,
+
+	/** Applies to the name of the program in the `Exec` command. */
+	#[serde(default = "default_exec_weight")]
+	pub exec: f64
+}
+
+impl Default for FieldWeights {
+	fn default() -> Self {
+		serde_json::from_str("{}").unwrap()
+	}
+}
+
+impl FieldWeights {
+
+	/**
+	 * Returns the weights clamped between 0 and 1, with every other field's weight at most the name's,
+	 * so that a match in another field never outranks an equally good name match.
+	 */
+
+	pub fn clamped(&self) -> FieldWeights {
+		let name = if self.name.is_nan() { 1.0 } else { self.name.clamp(0.0, 1.0) };
+		let clamp = |weight: f64| if weight.is_nan() { 0.0 } else { weight.clamp(0.0, name) };
+		FieldWeights { name, generic_name: clamp(self.generic_name), keywords: clamp(self.keywords), id: clamp(self.id), exec: clamp(self.exec) }
+	}
+}
+
+fn default_emit_events() -> bool { false }
+
+fn default_events_path() -> String { "-".to_owned() }
+
+fn default_typo_tolerance() -> bool { false }
+
+fn default_treat_nodisplay_as_launchable() -> bool { false }
+
+fn default_history_half_life_days() -> f64 { 30.0 }
+
+fn default_raise_running() -> bool { false }
+
+fn default_actions_min_query_length() -> usize { 0 }
+
+fn default_show_category() -> bool { true }
+
+fn default_show_version() -> bool { true }
+
+fn default_show_all_categories() -> bool { false }
+
+fn default_compact() -> bool { false }
+
+fn default_length_penalty_weight() -> f64 { 0.5 }
+
+fn default_nice() -> i32 { 0 }
+
+fn default_rich_metadata() -> bool { false }
+
+fn default_word_separators() -> String { "-_.".to_owned() }
+
+fn default_split_camel_case() -> bool { true }
+
+fn default_prefilter() -> bool { true }
+
+fn default_path_executables() -> bool { false }
+
+fn default_use_systemd_scope() -> bool { false }
+
+fn default_appimages() -> bool { false }
+
+fn default_appimage_dirs() -> Vec<String> { vec![ "~/Applications".to_owned(), "~/Downloads".to_owned() ] }
+
+fn default_max_scan_depth() -> usize { 8 }
+
+fn default_max_scan_files() -> usize { 20000 }
+
+fn default_strip_stop_words() -> bool { false }
+
+fn default_notify_on_launch() -> bool { false }
+
+fn default_penalized_terms() -> Vec<String> { vec![ "uninstall".to_owned(), "installer".to_owned(), "setup".to_owned() ] }
+
+fn default_stop_words() -> Vec<String> { vec![ "the".to_owned(), "a".to_owned(), "an".to_owned() ] }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Preferences {
+
+	#[serde(skip_serializing, default)]
+	pub path: std::path::PathBuf,
+
+	/** Emits an activation event each time a result is launched. */
+	#[serde(default = "default_emit_events")]
+	pub emit_events: bool,
+
+	/** The file that activation events are appended to, or `-` for stdout. */
+	#[serde(default = "default_events_path")]
+	pub events_path: String,
+
+	/** Matches queries containing small typos when nothing else matches. */
+	#[serde(default = "default_typo_tolerance")]
+	pub typo_tolerance: bool,
+
+	/** Includes entries marked `NoDisplay`, which are hidden from menus but can still be launched. */
+	#[serde(default = "default_treat_nodisplay_as_launchable")]
+	pub treat_nodisplay_as_launchable: bool,
+
+	/** The number of days after which a past launch counts half as much towards ranking. */
+	#[serde(default = "default_history_half_life_days")]
+	pub history_half_life_days: f64,
+
+	/** Raises an application's existing window instead of launching it again, when one can be found. */
+	#[serde(default = "default_raise_running")]
+	pub raise_running: bool,
+
+	/** Desktop actions are only shown in results once the query is longer than this many characters. */
+	#[serde(default = "default_actions_min_query_length")]
+	pub actions_min_query_length: usize,
+
+	/** Shows results on a single line, with their actions only in the preview. */
+	#[serde(default = "default_compact")]
+	pub compact: bool,
+
+	/** The most desktop actions shown in a result, with the rest only shown in its preview. All are shown if unset. */
+	#[serde(default)]
+	pub max_inline_actions: Option<usize>,
+
+	/** Shows the category label above the name in results and previews. */
+	#[serde(default = "default_show_category")]
+	pub show_category: bool,
+
+	/** Shows the program's version below the description in previews, when the desktop entry has one. */
+	#[serde(default = "default_show_version")]
+	pub show_version: bool,
+
+	/** Shows every category of an application as a row of badges in its preview, instead of only the one chosen for results. */
+	#[serde(default = "default_show_all_categories")]
+	pub show_all_categories: bool,
+
+	/** The score lost for each character of a name that a query doesn't match, up to a small limit. */
+	#[serde(default = "default_length_penalty_weight")]
+	pub length_penalty_weight: f64,
+
+	/** The niceness programs are launched with, unless overridden by `X-Scout-Nice`. Must be between -20 and 19. */
+	#[serde(default = "default_nice")]
+	pub nice: i32,
+
+	/** Also finds the executables in `PATH` that don't have a desktop file, such as command line tools. */
+	#[serde(default = "default_path_executables")]
+	pub path_executables: bool,
+
+	/** Also finds the executable AppImage files in `appimage_dirs`, which are named after their file names. */
+	#[serde(default = "default_appimages")]
+	pub appimages: bool,
+
+	/** The directories searched for AppImages, not including their subdirectories. */
+	#[serde(default = "default_appimage_dirs")]
+	pub appimage_dirs: Vec<String>,
+
+	/** Launches programs in their own transient scope with `systemd-run --user --scope`, when it is available. */
+	#[serde(default = "default_use_systemd_scope")]
+	pub use_systemd_scope: bool,
+
+	/** A command retried after launching until it focuses the window, like `swaymsg '[app_id="%w"] focus'`, where `%w` is the `StartupWMClass` or desktop file ID. */
+	#[serde(default)]
+	pub focus_command: Option<String>,
+
+	/** Shows a notification with the application's name and the exact command run whenever it is launched. */
+	#[serde(default = "default_notify_on_launch")]
+	pub notify_on_launch: bool,
+
+	/** Reads applications' AppStream metainfo, matching its keywords and using its summary when there is no comment. */
+	#[serde(default = "default_rich_metadata")]
+	pub rich_metadata: bool,
+
+	/** How much a match in each field counts, so that a keyword match never outranks an equally good name match. */
+	#[serde(default)]
+	pub field_weights: FieldWeights,
+
+	/** The characters besides whitespace that separate the words of a name, for matching queries to acronyms. */
+	#[serde(default = "default_word_separators")]
+	pub word_separators: String,
+
+	/** Treats each capitalized part of a CamelCase name as a word, for matching queries to acronyms. */
+	#[serde(default = "default_split_camel_case")]
+	pub split_camel_case: bool,
+
+	/** Also matches names without their leading `stop_words`, so that `gimp` matches the start of `The GIMP`. */
+	#[serde(default = "default_strip_stop_words")]
+	pub strip_stop_words: bool,
+
+	/** The words ignored at the start of names when `strip_stop_words` is enabled. */
+	#[serde(default = "default_stop_words")]
+	pub stop_words: Vec<String>,
+
+	/** Halves the score of applications whose names contain any of these terms, unless the query contains it too. */
+	#[serde(default = "default_penalized_terms")]
+	pub penalized_terms: Vec<String>,
+
+	/** A file of application popularity counts, like popularity-contest data, that gives popular applications a small bonus. */
+	#[serde(default)]
+	pub popularity_file: Option<String>,
+
+	/** Whether entries with `OnlyShowIn` are shown when `$XDG_CURRENT_DESKTOP` is unset, `permissive` or `strict`. */
+	#[serde(default)]
+	pub unknown_desktop: UnknownDesktop,
+
+	/** If not empty, only applications in at least one of these categories, like `Game`, are found. */
+	#[serde(default)]
+	pub allowed_categories: Vec<String>,
+
+	/** Skips scoring applications whose names don't contain every character of the query. Not used with `typo_tolerance`. */
+	#[serde(default = "default_prefilter")]
+	pub prefilter: bool,
+
+	/** How many directories deep desktop files are searched for below each `applications` directory. */
+	#[serde(default = "default_max_scan_depth")]
+	pub max_scan_depth: usize,
+
+	/** The most files that are looked at while searching for desktop files, after which searching stops. */
+	#[serde(default = "default_max_scan_files")]
+	pub max_scan_files: usize,
+
+	/** Shows the raw contents of an application's desktop file in its preview. Set by the app's `developer` preference, not this file. */
+	#[serde(skip)]
+	pub developer_mode: bool,
+
+	#[serde(default)]
+	pub strings: Strings
+}
+
+impl Preferences {
+	pub fn new(path: Option<&std::path::Path>) -> Shared<Self> {
+		let path = path
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| dirs::config_dir()
+				.map(|dir| dir.join("scout_application.conf"))
+				.unwrap_or_else(|| std::path::Path::new(&[ "/home/", &whoami::username(), "/.config/scout_application.conf" ].join(""))
+				.to_owned()));
+
+		let mut contents = String::new();
+		// The file is only read here, and created if it's missing. Saving truncates it before writing, so no old bytes are left.
+		match std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path) {
+			Ok(mut file) => drop(file.read_to_string(&mut contents)),
+			Err(err) => println!("[WARN] Failed to open '{}', using the default preferences: {}", path.display(), err)
+		}
+
+		let prefs = Shared::new(match serde_json::from_str::<Preferences>(&contents) {
+			Ok(json) => json,
+			Err(err) => {
+				println!("Error reading application plugin config file, resetting to default. {:?}", err);
+				serde_json::from_str("{}").unwrap()
+			}
+		});
+
+		let mut prefs_mut = prefs.borrow_mut();
+		prefs_mut.path = path;
+		prefs_mut.developer_mode = Preferences::read_developer_mode();
+		if let Err(err) = prefs_mut.save() { println!("[WARN] Failed to save '{}': {}", prefs_mut.path.display(), err); }
+		drop(prefs_mut);
+
+		prefs
+	}
+
+	/**
+	 * Reads the config file again, keeping the current preferences if it can't be read or parsed.
+	 */
+
+	pub fn reload(&mut self) -> Result<(), ScoutError> {
+		let contents = std::fs::read_to_string(&self.path)?;
+		let mut prefs = serde_json::from_str::<Preferences>(&contents)
+			.map_err(|err| ScoutError::Config(format!("Invalid preferences in '{}': {}", self.path.display(), err)))?;
+
+		prefs.path = std::mem::take(&mut self.path);
+		prefs.developer_mode = Preferences::read_developer_mode();
+		*self = prefs;
+		Ok(())
+	}
+
+
+	/**
+	 * Reads the app's `developer` preference from `scout.conf`, so that developer mode is only set in one place.
+	 * It is disabled if the file can't be read.
+	 */
+
+	fn read_developer_mode() -> bool {
+		let path = dirs::config_dir().map(|dir| dir.join("scout.conf"))
+			.unwrap_or_else(|| std::path::PathBuf::from([ "/home/", &whoami::username(), "/.config/scout.conf" ].join("")));
+
+		std::fs::read_to_string(path).ok()
+			.and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+			.and_then(|config| config.get("developer")?.as_bool())
+			.unwrap_or(false)
+	}
+
+	pub fn save(&self) -> Result<(), ScoutError> {
+		let mut file = std::fs::OpenOptions::new().write(true).create(true).truncate(true).open(&self.path)?;
+		file.write_all(serde_json::to_string(&self).map_err(|err| ScoutError::Config(err.to_string()))?.as_bytes())?;
+
+		Ok(())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn field_weights_never_exceed_the_name() {
+		let weights: FieldWeights = serde_json::from_str(r#"{ "name": 0.8, "keywords": 2.0, "id": -1.0 }"#).unwrap();
+		let clamped = weights.clamped();
+		assert_eq!((clamped.name, clamped.generic_name, clamped.keywords, clamped.id, clamped.exec), (0.8, 0.5, 0.8, 0.0, 0.25));
+
+		let clamped = FieldWeights { name: f64::NAN, keywords: f64::NAN, ..FieldWeights::default() }.clamped();
+		assert_eq!((clamped.name, clamped.keywords), (1.0, 0.0));
+	}
+}
@@ -0,0 +1,145 @@
+/*!
+ * Finding and signalling the running processes of an application, through `/proc`.
+ */
+
+use std::path::{ Path, PathBuf };
+
+use crate::error::ScoutError;
+use crate::launch;
+
+/**
+ * A running process, with its name from `comm` and the executable it is running from the `exe` link.
+ * The executable is `None` if the link can't be read, such as for another user's processes.
+ */
+
+#[derive(Debug, Clone)]
+pub struct Process {
+	pub pid: u32,
+	pub name: String,
+	pub exe: Option<PathBuf>
+}
+
+/**
+ * Lists the processes in a `/proc`-like directory. Processes that exit while being read are skipped.
+ */
+
+pub fn list(proc_dir: &Path) -> Vec<Process> {
+	let entries = match std::fs::read_dir(proc_dir) {
+		Ok(entries) => entries,
+		Err(err) => { println!("[WARN] Failed to read '{}': {}", proc_dir.display(), err); return vec![]; }
+	};
+
+	entries.filter_map(|entry| {
+		let entry = entry.ok()?;
+		let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+		let name = std::fs::read_to_string(entry.path().join("comm")).ok()?.trim_end().to_owned();
+		let exe = std::fs::read_link(entry.path().join("exe")).ok();
+		Some(Process { pid, name, exe })
+	}).collect()
+}
+
+
+/**
+ * Resolves the program of a command to the canonical path its processes' `exe` links will point to.
+ * Returns `None` for generic launchers like `sh` or `flatpak`, since their processes could be running anything.
+ */
+
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+	if launch::is_generic_launcher(program) { return None; }
+	launch::resolve_executable(program).ok()?.canonicalize().ok()
+}
+
+
+/**
+ * Returns the ids of the processes running the exact executable specified, or whose name matches the `StartupWMClass`.
+ * Names that are generic launchers are never matched, and process names are truncated to 15 characters by the kernel.
+ */
+
+pub fn find_matching(processes: &[Process], exe: Option<&Path>, wm_class: Option<&str>) -> Vec<u32> {
+	let comm: Option<String> = wm_class.filter(|wm_class| !launch::is_generic_launcher(&wm_class.to_lowercase()))
+		.map(|wm_class| wm_class.chars().take(15).collect());
+
+	processes.iter().filter(|process| (exe.is_some() && process.exe.as_deref() == exe)
+		|| comm.as_deref().is_some_and(|comm| comm.eq_ignore_ascii_case(&process.name)))
+		.map(|process| process.pid).collect()
+}
+
+
+/**
+ * Sends `SIGTERM` to the processes specified, or `SIGKILL` if `force` is true.
+ */
+
+pub fn signal(pids: &[u32], force: bool) -> Result<(), ScoutError> {
+	if pids.is_empty() { return Ok(()); }
+	println!("Sending {} to {:?}", if force { "SIGKILL" } else { "SIGTERM" }, pids);
+
+	let status = std::process::Command::new("kill").arg(if force { "-KILL" } else { "-TERM" })
+		.args(pids.iter().map(|pid| pid.to_string()))
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status()
+		.map_err(|err| ScoutError::Launch(format!("Failed to spawn 'kill': {}", err)))?;
+
+	if status.success() { Ok(()) } else { Err(ScoutError::Launch(format!("Failed to signal {:?}.", pids))) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/**
+	 * Creates a `/proc`-like directory with a process for each pid, name, and executable specified.
+	 */
+
+	fn get_proc_dir(name: &str, processes: &[(u32, &str, Option<&str>)]) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("scout-proc-{}-{}", std::process::id(), name));
+		std::fs::create_dir_all(dir.join("self")).unwrap();
+		std::fs::create_dir_all(dir.join("999")).unwrap();
+
+		for (pid, name, exe) in processes {
+			let process_dir = dir.join(pid.to_string());
+			std::fs::create_dir_all(&process_dir).unwrap();
+			std::fs::write(process_dir.join("comm"), [ name, "\n" ].join("")).unwrap();
+			if let Some(exe) = exe { std::os::unix::fs::symlink(exe, process_dir.join("exe")).unwrap(); }
+		}
+		dir
+	}
+
+	fn find(processes: &[Process], exe: Option<&str>, wm_class: Option<&str>) -> Vec<u32> {
+		let mut pids = find_matching(processes, exe.map(Path::new), wm_class);
+		pids.sort();
+		pids
+	}
+
+	#[test]
+	fn processes_are_listed_from_proc() {
+		let dir = get_proc_dir("list", &[ (10, "firefox", Some("/usr/lib/firefox/firefox")), (20, "kworker", None) ]);
+		let mut processes = list(&dir);
+		processes.sort_by_key(|process| process.pid);
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(processes.iter().map(|process| (process.pid, process.name.as_str())).collect::<Vec<_>>(), vec![ (10, "firefox"), (20, "kworker") ]);
+		assert_eq!(processes[0].exe.as_deref(), Some(Path::new("/usr/lib/firefox/firefox")));
+		assert_eq!(processes[1].exe, None);
+	}
+
+	#[test]
+	fn processes_match_by_executable_or_wm_class() {
+		let dir = get_proc_dir("match", &[
+			(10, "firefox", Some("/usr/lib/firefox/firefox")),
+			(11, "Web Content", Some("/usr/lib/firefox/firefox")),
+			(20, "firefox-bin", Some("/opt/firefox-nightly/firefox")),
+			(30, "sh", Some("/usr/bin/sh")),
+			(40, "gnome-text-edit", None),
+			(50, "Navigator", None)
+		]);
+		let processes = list(&dir);
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(find(&processes, Some("/usr/lib/firefox/firefox"), None), vec![ 10, 11 ]);
+		assert_eq!(find(&processes, Some("/usr/lib/firefox/firefox"), Some("navigator")), vec![ 10, 11, 50 ]);
+		assert_eq!(find(&processes, None, Some("gnome-text-editor")), vec![ 40 ]);
+		assert_eq!(find(&processes, None, Some("sh")), Vec::<u32>::new());
+		assert_eq!(find(&processes, Some("/usr/bin/firefox"), None), Vec::<u32>::new());
+		assert_eq!(find(&processes, None, None), Vec::<u32>::new());
+	}
+}
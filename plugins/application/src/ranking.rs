@@ -0,0 +1,418 @@
+/*!
+ * Helpers for ranking application results against a search query.
+ */
+
+/** The largest edit distance at which a mistyped query is still considered a match. */
+pub const MAX_TYPO_DISTANCE: usize = 2;
+
+/** The score added when the query is the whole name. Larger than any prefix and frequency bonus combined. */
+pub const EXACT_MATCH_BONUS: usize = 100;
+
+/** The score added when the query is the start of the name. */
+pub const PREFIX_MATCH_BONUS: usize = 40;
+
+/** The score added when the query is the first letter of each word of the name, like `lo` for `LibreOffice`. */
+pub const ACRONYM_MATCH_BONUS: usize = 30;
+
+/** The score added for each letter of a query that matches the capital letters of a name, like `vsc` for `VSCodium`. */
+pub const CAPITAL_MATCH_WEIGHT: usize = 8;
+
+/** The score added for each recent launch of an application. */
+pub const FREQUENCY_WEIGHT: f64 = 5.0;
+
+/** The largest score that launch history can add to a result. */
+pub const MAX_FREQUENCY_SCORE: usize = 50;
+
+/** The largest score that a long name can lose to the length penalty. */
+pub const MAX_LENGTH_PENALTY: usize = 10;
+
+
+/**
+ * The parts of a result's score for a query, for explaining why results are ranked the way they are.
+ * The score is the sum of `fuzzy`, `bonus`, `frequency`, and `popularity`, minus `field_penalty` and `term_penalty`.
+ */
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RankingBreakdown {
+
+	/** The name or keyword that scored highest. */
+	pub name: String,
+
+	/** The letter matching score, or the typo score if no letters matched, after the length penalty. */
+	pub fuzzy: usize,
+
+	/** The score that was removed from `fuzzy` for the letters of the name the query doesn't cover. */
+	pub length_penalty: usize,
+
+	/** The exact, prefix, acronym, or capital letter match bonus. */
+	pub bonus: usize,
+
+	/** The score removed by the weight of the field that matched, like a keyword instead of a name. */
+	pub field_penalty: usize,
+
+	/** The score removed because the name contains a penalized term, like `uninstall`, that the query doesn't. */
+	pub term_penalty: usize,
+
+	/** The score added for the launch history. */
+	pub frequency: usize,
+
+	/** The score added for the system-wide popularity of the application. */
+	pub popularity: usize
+}
+
+impl RankingBreakdown {
+	pub fn get_score(&self) -> usize {
+		(self.fuzzy + self.bonus + self.frequency + self.popularity).saturating_sub(self.field_penalty + self.term_penalty)
+	}
+
+
+	/**
+	 * Adds up the breakdowns of each term of a query, joining the names they matched with spaces.
+	 */
+
+	pub fn sum(breakdowns: &[RankingBreakdown]) -> RankingBreakdown {
+		breakdowns.iter().fold(RankingBreakdown::default(), |sum, breakdown| RankingBreakdown {
+			name: if sum.name.is_empty() { breakdown.name.clone() } else { [ sum.name.as_str(), &breakdown.name ].join(" ") },
+			fuzzy: sum.fuzzy + breakdown.fuzzy,
+			length_penalty: sum.length_penalty + breakdown.length_penalty,
+			bonus: sum.bonus + breakdown.bonus,
+			field_penalty: sum.field_penalty + breakdown.field_penalty,
+			term_penalty: sum.term_penalty + breakdown.term_penalty,
+			frequency: sum.frequency + breakdown.frequency,
+			popularity: sum.popularity + breakdown.popularity
+		})
+	}
+}
+
+
+/**
+ * Scales a field's match by its weight from `FieldWeights`, clamped between 0 and 1, recording the score removed as the field penalty.
+ */
+
+pub fn apply_weight(mut breakdown: RankingBreakdown, weight: f64) -> RankingBreakdown {
+	let score = breakdown.get_score();
+	breakdown.field_penalty += score - (score as f64 * weight.clamp(0.0, 1.0)).round() as usize;
+	breakdown
+}
+
+
+/**
+ * Returns the name of the program an `Exec` command runs, like `nautilus` for `/usr/bin/nautilus --new-window`.
+ * Environment assignments, options, and generic launchers like `env` or `flatpak run` are skipped,
+ * so `env GDK_BACKEND=x11 gimp` is `gimp`. Field codes are never names.
+ */
+
+pub fn get_exec_name(exec: &str) -> Option<&str> {
+	let mut launched = false;
+	let name = exec.split_whitespace()
+		.map(|arg| arg.trim_matches(|c| c == '"' || c == '\'').rsplit('/').next().unwrap_or(""))
+		.find(|name| {
+			if name.contains('=') || name.starts_with('-') { return false; }
+			if crate::launch::is_generic_launcher(name) { launched = true; return false; }
+			!(launched && *name == "run")
+		})?;
+	Some(name).filter(|name| !name.is_empty() && !name.starts_with('%'))
+}
+
+
+/**
+ * Lowercases a query or name and removes all of its whitespace,
+ * so that multi-word queries match names regardless of their spacing.
+ */
+
+pub fn normalize(string: &str) -> String {
+	let mut normalized = string.to_lowercase();
+	normalized.retain(|c| !c.is_whitespace());
+	normalized
+}
+
+
+/**
+ * Returns true if the name contains any of the penalized terms, ignoring case, that the query doesn't.
+ * The query should already be normalized.
+ */
+
+pub fn is_penalized(query: &str, name: &str, terms: &[String]) -> bool {
+	let name = normalize(name);
+	terms.iter().map(|term| normalize(term)).any(|term| !term.is_empty() && name.contains(&term) && !query.contains(&term))
+}
+
+
+/**
+ * Computes the optimal string alignment distance between two strings,
+ * counting insertions, deletions, substitutions, and adjacent transpositions.
+ */
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+
+	let mut dist = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+	for (i, row) in dist.iter_mut().enumerate() { row[0] = i; }
+	for (j, cell) in dist[0].iter_mut().enumerate() { *cell = j; }
+
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+			dist[i][j] = (dist[i - 1][j] + 1).min(dist[i][j - 1] + 1).min(dist[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+			}
+		}
+	}
+
+	dist[a.len()][b.len()]
+}
+
+
+/**
+ * Scores a query that may contain typos against a normalized name,
+ * by comparing it to the prefixes of the name around the query's length.
+ * Returns zero if the query is further than `MAX_TYPO_DISTANCE` from every prefix.
+ */
+
+pub fn typo_score(query: &str, name: &str) -> usize {
+	let query_len = query.chars().count();
+	if query_len <= MAX_TYPO_DISTANCE { return 0; }
+
+	let distance = (query_len - 1 ..= query_len + 1)
+		.map(|len| edit_distance(query, &name.chars().take(len).collect::<String>()))
+		.min().unwrap_or(usize::MAX);
+
+	if distance > MAX_TYPO_DISTANCE { 0 } else { query_len * (10 - 4 * distance) }
+}
+
+
+/**
+ * Converts a decayed launch frequency into a score bonus.
+ */
+
+pub fn frequency_score(frequency: f64) -> usize {
+	std::cmp::min((frequency * FREQUENCY_WEIGHT) as usize, MAX_FREQUENCY_SCORE)
+}
+
+
+/**
+ * Returns the score removed from a match for the characters of the name that the query doesn't cover,
+ * so that shorter names rank slightly higher than longer names with an equally good match.
+ * Each unmatched character costs `weight`, up to `MAX_LENGTH_PENALTY`.
+ */
+
+pub fn length_penalty(query: &str, name: &str, weight: f64) -> usize {
+	let unmatched = name.chars().count().saturating_sub(query.chars().count());
+	std::cmp::min((unmatched as f64 * weight.max(0.0)) as usize, MAX_LENGTH_PENALTY)
+}
+
+
+/**
+ * Returns a bonus for queries that match the whole name or the start of it.
+ * Both strings should already be normalized.
+ */
+
+pub fn match_bonus(query: &str, name: &str) -> usize {
+	if query.is_empty() { 0 }
+	else if query == name { EXACT_MATCH_BONUS }
+	else if name.starts_with(query) { PREFIX_MATCH_BONUS }
+	else { 0 }
+}
+
+
+/**
+ * Splits a name into words at whitespace, at any of the `separators`,
+ * and between a lowercase and an uppercase letter if `camel_case` is true.
+ */
+
+pub fn split_words(name: &str, separators: &str, camel_case: bool) -> Vec<String> {
+	let mut words = vec![];
+	let mut word = String::new();
+	let mut last: Option<char> = None;
+
+	for c in name.chars() {
+		if c.is_whitespace() || separators.contains(c) {
+			if !word.is_empty() { words.push(std::mem::take(&mut word)); }
+		}
+		else {
+			if camel_case && c.is_uppercase() && last.is_some_and(|last| last.is_lowercase()) && !word.is_empty() {
+				words.push(std::mem::take(&mut word));
+			}
+			word.push(c);
+		}
+		last = Some(c);
+	}
+
+	if !word.is_empty() { words.push(word); }
+	words
+}
+
+
+/**
+ * Removes any leading stop words from a name, ignoring case. Returns `None` if the name doesn't start
+ * with a stop word, or if it is made of only stop words, so that the original name is used instead.
+ */
+
+pub fn strip_stop_words(name: &str, stop_words: &[String]) -> Option<String> {
+	let mut words = name.split_whitespace().peekable();
+	let mut stripped = false;
+	while words.peek().is_some_and(|word| stop_words.iter().any(|stop_word| stop_word.eq_ignore_ascii_case(word))) {
+		words.next();
+		stripped = true;
+	}
+
+	let rest = words.collect::<Vec<_>>().join(" ");
+	Some(rest).filter(|rest| stripped && !rest.is_empty())
+}
+
+
+/**
+ * Returns the parts of a desktop file ID that queries are matched against: the whole ID and, for IDs with dots,
+ * each dot-separated component except a leading reverse domain like `org`. `org.gnome.Nautilus` gives
+ * `org.gnome.Nautilus`, `gnome`, and `Nautilus`. IDs with a colon, like `path:` results, aren't desktop file IDs.
+ */
+
+pub fn get_id_components(id: &str) -> Vec<&str> {
+	if id.is_empty() || id.contains(':') { return vec![]; }
+
+	let components: Vec<&str> = id.split('.').filter(|component| !component.is_empty()).collect();
+	let skip = if components.len() > 2 { 1 } else { 0 };
+	std::iter::once(id).chain(components.into_iter().skip(skip).filter(|component| *component != id)).collect()
+}
+
+
+/**
+ * Returns a bonus for queries made of the first letter of each word of the name.
+ * The query should already be normalized, and names with fewer than two words never match.
+ */
+
+pub fn acronym_bonus(query: &str, name: &str, separators: &str, camel_case: bool) -> usize {
+	let words = split_words(name, separators, camel_case);
+	if words.len() < 2 { return 0; }
+
+	let acronym: String = words.iter().filter_map(|word| word.chars().next()).flat_map(|c| c.to_lowercase()).collect();
+	if query == acronym { ACRONYM_MATCH_BONUS } else { 0 }
+}
+
+
+/**
+ * Returns a bonus for queries that match the start of the capital letters of the name, in order,
+ * so that `obs` matches `OBS Studio` and `vsc` matches `VSCodium` strongly.
+ * The name must not be normalized, and queries shorter than two letters never match.
+ */
+
+pub fn capital_bonus(query: &str, name: &str) -> usize {
+	let query_len = query.chars().count();
+	if query_len < 2 { return 0; }
+
+	let capitals: String = name.chars().filter(|c| c.is_uppercase()).flat_map(|c| c.to_lowercase()).collect();
+	if capitals.starts_with(query) { query_len * CAPITAL_MATCH_WEIGHT } else { 0 }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn single_typos_score_less_than_a_correct_query() {
+		let correct = typo_score("firefox", "firefox");
+		let transposed = typo_score("firefxo", "firefox");
+		let substituted = typo_score("firefix", "firefox");
+
+		assert_eq!(edit_distance("firefxo", "firefox"), 1);
+		assert_eq!(edit_distance("firefix", "firefox"), 1);
+		assert!(transposed > 0 && transposed < correct);
+		assert!(substituted > 0 && substituted < correct);
+	}
+
+	#[test]
+	fn distant_and_short_queries_do_not_typo_match() {
+		assert_eq!(typo_score("chromium", "firefox"), 0);
+		assert_eq!(typo_score("fx", "firefox"), 0);
+	}
+
+	#[test]
+	fn length_penalties_are_small_and_capped() {
+		assert_eq!(length_penalty("code", "code", 0.5), 0);
+		assert_eq!(length_penalty("code", "codeblocks", 0.5), 3);
+		assert_eq!(length_penalty("code", "codeblocks", 0.0), 0);
+		assert_eq!(length_penalty("code", "codeblocks", -1.0), 0);
+		assert_eq!(length_penalty("c", &"c".repeat(1000), 1.0), MAX_LENGTH_PENALTY);
+	}
+
+	#[test]
+	fn underscores_only_separate_acronyms_when_configured() {
+		assert_eq!(split_words("foo_bar_baz", "-_.", false), vec![ "foo", "bar", "baz" ]);
+		assert_eq!(acronym_bonus("fbb", "foo_bar_baz", "-_.", false), ACRONYM_MATCH_BONUS);
+		assert_eq!(acronym_bonus("fbb", "foo_bar_baz", "-.", false), 0);
+		assert_eq!(acronym_bonus("gfm", "GNOME Font-Viewer", "-", false), 0);
+		assert_eq!(acronym_bonus("gfv", "GNOME Font-Viewer", "-", false), ACRONYM_MATCH_BONUS);
+	}
+
+	#[test]
+	fn camel_case_splitting_is_optional() {
+		assert_eq!(split_words("KeePassXC", "", true), vec![ "Kee", "Pass", "XC" ]);
+		assert_eq!(split_words("KeePassXC", "", false), vec![ "KeePassXC" ]);
+		assert_eq!(acronym_bonus("kpx", "KeePassXC", "", true), ACRONYM_MATCH_BONUS);
+		assert_eq!(acronym_bonus("kpx", "KeePassXC", "", false), 0);
+	}
+
+
+	#[test]
+	fn queries_matching_capital_letters_get_a_bonus() {
+		assert_eq!(capital_bonus("obs", "OBS Studio"), 3 * CAPITAL_MATCH_WEIGHT);
+		assert_eq!(capital_bonus("vsc", "VSCodium"), 3 * CAPITAL_MATCH_WEIGHT);
+		assert_eq!(capital_bonus("kp", "KeePassXC"), 2 * CAPITAL_MATCH_WEIGHT);
+		assert_eq!(capital_bonus("vsc", &normalize("VSCodium")), 0);
+		assert_eq!(capital_bonus("sc", "VSCodium"), 0);
+		assert_eq!(capital_bonus("v", "VSCodium"), 0);
+	}
+
+
+	#[test]
+	fn leading_stop_words_are_stripped() {
+		let stop_words = vec![ "the".to_owned(), "a".to_owned(), "an".to_owned() ];
+		assert_eq!(strip_stop_words("The GIMP", &stop_words), Some("GIMP".to_owned()));
+		assert_eq!(strip_stop_words("A  Note Taker", &stop_words), Some("Note Taker".to_owned()));
+		assert_eq!(strip_stop_words("Notes The App", &stop_words), None);
+		assert_eq!(strip_stop_words("The A", &stop_words), None);
+		assert_eq!(strip_stop_words("The GIMP", &[]), None);
+	}
+
+
+	#[test]
+	fn penalized_terms_only_apply_when_the_query_lacks_them() {
+		let terms = vec![ "uninstall".to_owned(), "Setup".to_owned(), "".to_owned() ];
+		assert!(is_penalized("libre", "Uninstall LibreOffice", &terms));
+		assert!(is_penalized("game", "Game SETUP", &terms));
+		assert!(!is_penalized("uninstall libre", "Uninstall LibreOffice", &terms));
+		assert!(!is_penalized("libre", "LibreOffice Writer", &terms));
+	}
+
+
+	#[test]
+	fn ids_are_split_into_components() {
+		assert_eq!(get_id_components("org.gnome.Nautilus"), vec![ "org.gnome.Nautilus", "gnome", "Nautilus" ]);
+		assert_eq!(get_id_components("firefox"), vec![ "firefox" ]);
+		assert_eq!(get_id_components("vlc.desktop-player"), vec![ "vlc.desktop-player", "vlc", "desktop-player" ]);
+		assert_eq!(get_id_components("path:tool"), Vec::<&str>::new());
+		assert_eq!(get_id_components(""), Vec::<&str>::new());
+	}
+
+
+	#[test]
+	fn weights_become_field_penalties() {
+		let breakdown = RankingBreakdown { fuzzy: 50, bonus: 10, ..RankingBreakdown::default() };
+		assert_eq!(apply_weight(breakdown.clone(), 0.5).get_score(), 30);
+		assert_eq!(apply_weight(breakdown.clone(), 0.5).field_penalty, 30);
+		assert_eq!(apply_weight(breakdown.clone(), 2.0).get_score(), 60);
+		assert_eq!(apply_weight(breakdown, -1.0).get_score(), 0);
+	}
+
+	#[test]
+	fn exec_names_skip_launchers_and_arguments() {
+		assert_eq!(get_exec_name("/usr/bin/code --new-window %F"), Some("code"));
+		assert_eq!(get_exec_name("env GDK_BACKEND=x11 \"/opt/Telegram/telegram\" -- %u"), Some("telegram"));
+		assert_eq!(get_exec_name("flatpak run --branch=stable org.gimp.GIMP %U"), Some("org.gimp.GIMP"));
+		assert_eq!(get_exec_name("sh -c %f"), None);
+		assert_eq!(get_exec_name(""), None);
+	}
+}
@@ -1,7 +1,28 @@
+#[cfg(feature = "gui")]
 use gtk::prelude::*;
 use convert_case::{ Case, Casing };
 
-use scout_core::SearchResult;
+use scout_core::{ SearchResult, Shared };
+
+use crate::list;
+use crate::mime;
+use crate::placement;
+use crate::placement::{ Placement, WindowState };
+use crate::launch;
+use crate::launch::LaunchOptions;
+use crate::ranking;
+use crate::ranking::RankingBreakdown;
+use crate::history::History;
+use crate::event::ActivationEvent;
+use crate::preferences::Preferences;
+
+#[cfg(feature = "gui")]
+mod widget;
+
+
+/** The most graphemes of a description shown in a result's tooltip. */
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+const MAX_TOOLTIP_DESCRIPTION: usize = 240;
 
 
 /**
@@ -25,16 +46,383 @@ const EXCLUDED_CATEGORIES: [&str; 11] = [
 ];
 
 
+/**
+ * The main categories defined by the desktop menu specification.
+ * Category overrides must name one of these to be accepted.
+ */
+
+const MAIN_CATEGORIES: [&str; 13] = [
+	"AUDIOVIDEO",
+	"AUDIO",
+	"VIDEO",
+	"DEVELOPMENT",
+	"EDUCATION",
+	"GAME",
+	"GRAPHICS",
+	"NETWORK",
+	"OFFICE",
+	"SCIENCE",
+	"SETTINGS",
+	"SYSTEM",
+	"UTILITY"
+];
+
+
 /**
  * Represents a desktop action.
  */
 
 #[derive(Debug, Clone)]
 pub struct Action {
+	pub id: String,
+	/** The name shown on the action's button, which is only needed to build widgets. */
+	#[cfg(feature = "gui")]
 	pub name: String,
 	pub exec: String
 }
 
+impl Action {
+
+	/**
+	 * Reorders actions so that the ids listed in `order` come first, in that order,
+	 * such as from the `X-Scout-ActionOrder` key. Unlisted actions keep their order after the listed ones.
+	 */
+
+	pub fn sort_by_order(actions: &mut [Action], order: &[String]) {
+		actions.sort_by_key(|action| order.iter().position(|id| *id == action.id).unwrap_or(usize::MAX));
+	}
+
+
+	/**
+	 * Creates an action for each profile in an `X-Scout-Profiles` key, like `Work:-P work;Personal:-P personal;`,
+	 * which launches the program's command with the profile's arguments appended.
+	 * Actions are named by `label`, with `{}` replaced by the profile's name. Profiles without a name or arguments are skipped.
+	 */
+
+	pub fn from_profiles(value: Option<&str>, exec: &str, #[cfg_attr(not(feature = "gui"), allow(unused_variables))] label: &str) -> Vec<Action> {
+		list::parse_list(value).iter().filter_map(|profile| {
+			let mut parts = profile.splitn(2, ':');
+			let name = parts.next()?.trim();
+			let args = parts.next()?.trim();
+			if name.is_empty() || args.is_empty() { return None; }
+
+			Some(Action {
+				id: [ "profile-", name ].join(""),
+				#[cfg(feature = "gui")]
+				name: label.replace("{}", name),
+				exec: [ exec, args ].join(" ")
+			})
+		}).collect()
+	}
+}
+
+
+/**
+ * The search that produced a result, shared between its clones
+ * so that widget callbacks can see the latest query and rank.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchContext {
+	pub query: String,
+	pub rank: usize
+}
+
+
+/**
+ * The optional labels of an application's preview, which are left out when their preference is disabled
+ * or the entry has nothing to show in them.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub struct PreviewLabels {
+	/** The generic name, shown above the category badges. */
+	pub generic_name: bool,
+	/** A badge for each of the entry's categories. */
+	pub badges: bool,
+	/** The single category label, shown when the badges aren't. */
+	pub category: bool,
+	/** The program's version. */
+	pub version: bool
+}
+
+
+/**
+ * The layout of an application's result widget, which depends on the layout preferences and the entry's actions.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub struct ResultLayout {
+	/** Puts the icon, name, and subtitle on one line, leaving every action to the preview. */
+	pub compact: bool,
+	/** The subtitle label, above the name or after it in compact results. */
+	pub subtitle: bool,
+	/** The number of actions shown below the name, with the rest only shown in the preview. */
+	pub inline_actions: usize
+}
+
+
+/**
+ * Builds an Application result from the values in a desktop entry.
+ * Only the id, name, and exec are required, the rest have defaults.
+ */
+
+#[derive(Debug, Clone)]
+pub struct ApplicationResultBuilder {
+	id: String,
+	source_path: std::path::PathBuf,
+	name: String,
+	unlocalized_name: Option<String>,
+	keywords: Vec<String>,
+	generic_name: Option<String>,
+	category: String,
+	category_key: Option<String>,
+	categories: Vec<String>,
+	description: String,
+	version: Option<String>,
+	icon: Option<String>,
+
+	exec: String,
+	launch_options: LaunchOptions,
+	actions: Option<Vec<Action>>,
+	wm_class: Option<String>,
+	popularity: usize,
+	monitor: Option<usize>,
+	window_state: Option<WindowState>,
+	doc_url: Option<String>,
+	mime_types: Vec<String>,
+	file: Option<std::path::PathBuf>,
+
+	preferences: Shared<Preferences>,
+	history: Shared<History>
+}
+
+impl ApplicationResultBuilder {
+	pub fn new(id: &str, name: &str, exec: &str, preferences: Shared<Preferences>, history: Shared<History>) -> Self {
+		ApplicationResultBuilder {
+			id: id.to_owned(),
+			source_path: std::path::PathBuf::new(),
+			name: name.to_owned(),
+			unlocalized_name: None,
+			keywords: vec![],
+			generic_name: None,
+			category: "APPLICATION".to_owned(),
+			category_key: None,
+			categories: vec![],
+			description: String::new(),
+			version: None,
+			icon: None,
+			exec: exec.to_owned(),
+			launch_options: LaunchOptions::default(),
+			actions: None,
+			wm_class: None,
+			popularity: 0,
+			monitor: None,
+			window_state: None,
+			doc_url: None,
+			mime_types: vec![],
+			file: None,
+			preferences,
+			history
+		}
+	}
+
+	/**
+	 * Sets the path of the desktop file the result was created from.
+	 */
+
+	pub fn source_path(mut self, source_path: &std::path::Path) -> Self {
+		self.source_path = source_path.to_owned();
+		self
+	}
+
+	/**
+	 * Sets the `Name` key's value in the C locale, which is matched in addition to the localized name.
+	 */
+
+	pub fn unlocalized_name(mut self, unlocalized_name: Option<&str>) -> Self {
+		self.unlocalized_name = unlocalized_name.filter(|s| *s != self.name).map(str::to_owned);
+		self
+	}
+
+	/**
+	 * Sets extra words the result can be found by, which score lower than its names.
+	 */
+
+	pub fn keywords(mut self, keywords: Vec<String>) -> Self {
+		self.keywords = keywords;
+		self
+	}
+
+	/**
+	 * Sets the `GenericName` key's value, which is shown instead of the category if it differs from the name.
+	 */
+
+	pub fn generic_name(mut self, generic_name: Option<&str>) -> Self {
+		self.generic_name = generic_name.map(|s| s.trim())
+			.filter(|s| !s.is_empty() && s.to_lowercase() != self.name.to_lowercase())
+			.map(str::to_owned);
+		self
+	}
+
+	pub fn category(mut self, category: &str) -> Self {
+		self.category = category.to_owned();
+		self
+	}
+
+	/**
+	 * Sets the main category as it is written in the desktop entry, like `AudioVideo`, which `category_order` is matched against.
+	 */
+
+	pub fn category_key(mut self, category_key: Option<String>) -> Self {
+		self.category_key = category_key;
+		self
+	}
+
+	/**
+	 * Sets every displayable category of the entry, which are shown as badges in the preview if `show_all_categories` is enabled.
+	 */
+
+	pub fn categories(mut self, categories: Vec<String>) -> Self {
+		self.categories = categories;
+		self
+	}
+
+	pub fn description(mut self, description: &str) -> Self {
+		self.description = description.to_owned();
+		self
+	}
+
+	pub fn icon(mut self, icon: Option<&str>) -> Self {
+		self.icon = icon.map(str::to_owned);
+		self
+	}
+
+	/**
+	 * Sets the directory the program is launched in, from the `Path` key.
+	 */
+
+	pub fn working_dir(mut self, working_dir: Option<&str>) -> Self {
+		self.launch_options.working_dir = working_dir.map(str::to_owned);
+		self
+	}
+
+	/**
+	 * Sets the niceness the program is launched with, from the `X-Scout-Nice` key or the preferences.
+	 */
+
+	pub fn nice(mut self, nice: i32) -> Self {
+		self.launch_options.nice = nice;
+		self
+	}
+
+	/**
+	 * Sets whether the program is launched in a transient systemd scope.
+	 */
+
+	pub fn systemd_scope(mut self, systemd_scope: bool) -> Self {
+		self.launch_options.systemd_scope = systemd_scope;
+		self
+	}
+
+	/**
+	 * Sets the user the program is launched as, from KDE's `X-KDE-SubstituteUID` and `X-KDE-Username` keys.
+	 */
+
+	pub fn substitute_user(mut self, user: Option<String>) -> Self {
+		self.launch_options.substitute_user = user;
+		self
+	}
+
+	pub fn actions(mut self, actions: Option<Vec<Action>>) -> Self {
+		self.actions = actions;
+		self
+	}
+
+	pub fn wm_class(mut self, wm_class: Option<&str>) -> Self {
+		self.wm_class = wm_class.map(str::to_owned);
+		self
+	}
+
+	/**
+	 * Sets the version of the program, from the `X-AppVersion` key.
+	 * The `Version` key is the version of the desktop entry spec, not the program, so it isn't used.
+	 */
+
+	pub fn version(mut self, version: Option<&str>) -> Self {
+		self.version = version.map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| s.to_owned());
+		self
+	}
+
+	/**
+	 * Sets the ranking bonus the program gets for its system-wide popularity.
+	 */
+
+	pub fn popularity(mut self, popularity: usize) -> Self {
+		self.popularity = popularity;
+		self
+	}
+
+	/**
+	 * Sets the monitor the program's window is moved to, from the `X-Scout-Monitor` key.
+	 * This only has an effect if the program also has a `StartupWMClass`.
+	 */
+
+	pub fn monitor(mut self, monitor: Option<usize>) -> Self {
+		self.monitor = monitor;
+		self
+	}
+
+	/**
+	 * Sets the state the program's window is put in, from the `X-Scout-WindowState` key.
+	 * This only has an effect if the program also has a `StartupWMClass`.
+	 */
+
+	pub fn window_state(mut self, window_state: Option<WindowState>) -> Self {
+		self.window_state = window_state;
+		self
+	}
+
+	/**
+	 * Sets the program's documentation, from a `X-GNOME-DocPath` or `X-DocumentationURL` key.
+	 * Values without a scheme are treated as `help:` documents.
+	 */
+
+	pub fn doc_url(mut self, doc_url: Option<&str>) -> Self {
+		self.doc_url = doc_url.map(|s| s.trim()).filter(|s| !s.is_empty())
+			.map(|s| if s.contains(':') { s.to_owned() } else { [ "help:", s ].join("") });
+		self
+	}
+
+	/**
+	 * Sets the MIME types the program can open, from the `MimeType` key.
+	 */
+
+	pub fn mime_types(mut self, mime_types: Option<&str>) -> Self {
+		self.mime_types = list::parse_list(mime_types);
+		self
+	}
+
+	/**
+	 * Sets a file for the program to open when it is activated.
+	 */
+
+	pub fn file(mut self, file: &std::path::Path) -> Self {
+		self.file = Some(file.to_owned());
+		self
+	}
+
+	/**
+	 * Creates the result and its result widget.
+	 */
+
+	pub fn build(self) -> ApplicationResult {
+		ApplicationResult::new(self)
+	}
+}
+
 
 /**
  * A program search result, created from a desktop entry.
@@ -43,16 +431,40 @@ pub struct Action {
 
 #[derive(Debug, Clone)]
 pub struct ApplicationResult {
+	id: String,
+	source_path: std::path::PathBuf,
 	name: String,
+	unlocalized_name: Option<String>,
+	keywords: Vec<String>,
+	generic_name: Option<String>,
 	category: String,
+	category_key: Option<String>,
+	categories: Vec<String>,
 	description: String,
+	version: Option<String>,
 	icon: Option<String>,
 
 	exec: String,
+	launch_options: LaunchOptions,
 	actions: Option<Vec<Action>>,
-
+	wm_class: Option<String>,
+	popularity: usize,
+	monitor: Option<usize>,
+	window_state: Option<WindowState>,
+	doc_url: Option<String>,
+	mime_types: Vec<String>,
+	file: Option<std::path::PathBuf>,
+
+	#[cfg(feature = "gui")]
 	widget: gtk::Box,
+	#[cfg(feature = "gui")]
 	top_button: gtk::Button,
+	#[cfg(feature = "gui")]
+	actions_box: Option<gtk::Box>,
+
+	preferences: Shared<Preferences>,
+	history: Shared<History>,
+	context: Shared<SearchContext>,
 
 	score: usize
 }
@@ -60,157 +472,541 @@ pub struct ApplicationResult {
 impl ApplicationResult {
 
 	/**
-	 * Removes template parameters from a shell command.
+	 * Removes template parameters from a shell command, expanding `%c` to the localized name and `%k` to the desktop file's path.
+	 * The deprecated `%d`, `%D`, `%n`, `%N`, `%v`, and `%m` codes are removed as the specification requires.
+	 */
+
+	pub fn format_exec(exec: &str, name: &str, source_path: &std::path::Path) -> String {
+		ApplicationResult::expand_field_codes(exec, name, source_path, None)
+	}
+
+
+	/**
+	 * Substitutes a file into the file and URL parameters of a shell command, then formats the rest.
+	 */
+
+	pub fn format_exec_with_file(exec: &str, name: &str, source_path: &std::path::Path, file: &std::path::Path) -> String {
+		ApplicationResult::expand_field_codes(exec, name, source_path, Some(file))
+	}
+
+
+	/**
+	 * Expands every field code of a shell command in a single pass, so that codes in the substituted values are left alone.
+	 * File and URL codes are replaced with the file if there is one, `%%` becomes `%`, and other codes are kept as they are.
+	 */
+
+	fn expand_field_codes(exec: &str, name: &str, source_path: &std::path::Path, file: Option<&std::path::Path>) -> String {
+		let mut formatted = String::new();
+		let mut chars = exec.chars();
+
+		while let Some(c) = chars.next() {
+			if c != '%' { formatted.push(c); continue; }
+			match chars.next() {
+				Some('%') => formatted.push('%'),
+				Some('f') | Some('F') | Some('u') | Some('U') =>
+					if let Some(file) = file { formatted.push_str(&shell_words::quote(&file.to_string_lossy())); },
+				Some('c') => formatted.push_str(&shell_words::quote(name)),
+				Some('k') => formatted.push_str(&shell_words::quote(&source_path.to_string_lossy())),
+				Some('d') | Some('D') | Some('n') | Some('N') | Some('v') | Some('m') => (),
+				Some(c) => { formatted.push('%'); formatted.push(c); },
+				None => formatted.push('%')
+			}
+		}
+
+		formatted
+	}
+
+
+	/**
+	 * Returns true if any of the categories in the `Categories` list is allowed, ignoring case.
+	 * Every entry is allowed if `allowed` is empty.
+	 */
+
+	pub fn is_category_allowed(list: Option<&str>, allowed: &[String]) -> bool {
+		allowed.is_empty() || list::parse_list(list).iter()
+			.any(|category| allowed.iter().any(|allowed| allowed.eq_ignore_ascii_case(category)))
+	}
+
+
+	/**
+	 * Chooses the best category to display in the result,
+	 * using `fallback` as it is written if none of the categories are suitable.
+	 * Vendor-specific `X-` categories are never displayed.
+	 */
+
+	pub fn choose_category(list: Option<&str>, fallback: &str) -> String {
+		ApplicationResult::get_display_categories(list).into_iter().next()
+			.unwrap_or_else(|| fallback.to_owned())
+	}
+
+
+	/**
+	 * Returns every category in the `Categories` list that can be displayed, as they are written and in order,
+	 * leaving out the excluded and vendor-specific `X-` categories.
+	 */
+
+	pub fn get_spec_categories(list: Option<&str>) -> Vec<String> {
+		list::parse_list(list).into_iter()
+			.filter(|s| !s.to_uppercase().starts_with("X-"))
+			.filter(|s| !EXCLUDED_CATEGORIES.contains(&s.to_uppercase().as_str()))
+			.collect()
+	}
+
+
+	/**
+	 * Returns every category in the `Categories` list that can be displayed, in display form and in order,
+	 * leaving out the excluded and vendor-specific `X-` categories, and any duplicates.
+	 */
+
+	pub fn get_display_categories(list: Option<&str>) -> Vec<String> {
+		let mut categories: Vec<String> = vec![];
+		for category in ApplicationResult::get_spec_categories(list).into_iter().map(|s| s.to_case(Case::Title).to_uppercase()) {
+			if !categories.contains(&category) { categories.push(category); }
+		}
+		categories
+	}
+
+
+	/**
+	 * Chooses the main category as it is written in the desktop entry, from a valid `X-Scout-Category` override
+	 * or the first displayable category, matching the category chosen for display.
 	 */
 
-	pub fn format_exec(exec: &str) -> String {
-		exec.replace("%f", "").replace("%F", "").replace("%D", "~").replace("%u", "").replace("%U", "")
+	pub fn choose_category_key(list: Option<&str>, override_value: Option<&str>) -> Option<String> {
+		override_value.map(|value| value.trim()).filter(|value| ApplicationResult::override_category(Some(value)).is_some())
+			.map(|value| value.to_owned())
+			.or_else(|| ApplicationResult::get_spec_categories(list).into_iter().next())
 	}
 
 
 	/**
-	 * Chooses the best category to display in the result.
+	 * Validates a category override from the `X-Scout-Category` key,
+	 * returning it in display form if it names a main category.
 	 */
 
-	pub fn choose_category(list: Option<&str>) -> String {
-		let list: Vec<_> = list.unwrap_or("").split(";")
-			.filter(|s| !EXCLUDED_CATEGORIES.contains(&s.to_uppercase().as_str())).collect();
-		list.get(0).map_or("Application", |s| &s).to_case(Case::Title).to_uppercase()
+	pub fn override_category(value: Option<&str>) -> Option<String> {
+		let value = value?.trim();
+		if !MAIN_CATEGORIES.contains(&value.to_uppercase().as_str()) { return None; }
+		Some(value.to_case(Case::Title).to_uppercase())
 	}
 
 
 	/**
-	 * Finds an icon from an icon name specified in the desktop file.
+	 * Chooses the subtitle displayed above the name, which is the generic name if there is one,
+	 * so that applications with the same name can be told apart, or otherwise the category.
 	 */
 
-	pub fn get_icon(icon: Option<&str>, scale: i32) -> gtk::Image {
-		let flags = gtk::IconLookupFlags::USE_BUILTIN | gtk::IconLookupFlags::GENERIC_FALLBACK | gtk::IconLookupFlags::FORCE_SIZE;
-		let theme = gtk::IconTheme::get_default().unwrap();
+	pub fn choose_subtitle(generic_name: Option<&str>, category: &str) -> String {
+		generic_name.map_or_else(|| category.to_owned(), |generic_name| generic_name.to_uppercase())
+	}
+
 
-		let find_in_theme  = || theme.load_icon(&icon.unwrap_or(""), scale, flags).map_err(|_| ()).unwrap_or(None).ok_or(());
-		let find_from_file = || gdk_pixbuf::Pixbuf::from_file_at_scale(&icon.unwrap_or(""), scale, scale, true).map_err(|_| ());
-		let find_default   = || theme.load_icon("application-x-executable", scale, flags).unwrap().unwrap();
+	/**
+	 * Decides which labels the preview shows, from the preferences and what the entry has.
+	 */
 
-		gtk::Image::from_pixbuf(Some(&find_from_file().unwrap_or_else(|_| find_in_theme().unwrap_or_else(|_| find_default()))))
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn get_preview_labels(&self) -> PreviewLabels {
+		let preferences = self.preferences.borrow();
+		let badges = preferences.show_category && preferences.show_all_categories && !self.categories.is_empty();
+		PreviewLabels {
+			generic_name: badges && self.generic_name.is_some(),
+			badges,
+			category: preferences.show_category && !badges,
+			version: preferences.show_version && self.version.is_some()
+		}
 	}
 
 
 	/**
-	 * Creates a new Program result, with a corresponding result widget.
+	 * Decides the layout of a result widget from the preferences and the number of actions the entry has.
 	 */
 
-	pub fn new(name: &str, description: &str, category: &str,
-		exec: &str, icon: Option<&str>, actions: Option<Vec<Action>>) -> Self {
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn get_result_layout(preferences: &Preferences, actions: usize) -> ResultLayout {
+		ResultLayout {
+			compact: preferences.compact,
+			subtitle: preferences.show_category,
+			inline_actions: if preferences.compact { 0 } else { std::cmp::min(actions, preferences.max_inline_actions.unwrap_or(usize::MAX)) }
+		}
+	}
+
 
-		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
-		widget.get_style_context().add_class("Application");
-		widget.set_widget_name("SearchResult");
-		let top_button = gtk::Button::new();
+	/**
+	 * Returns the layout of the result's widget, with `get_result_layout`.
+	 */
 
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn get_layout(&self) -> ResultLayout {
+		ApplicationResult::get_result_layout(&self.preferences.borrow(), self.actions.as_ref().map_or(0, Vec::len))
+	}
+
+
+	/**
+	 * Composes the tooltip of a result, which shows the parts of it that may be ellipsized and the command it runs.
+	 * The description is omitted if it is empty, and shortened if it is very long.
+	 */
+
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn get_tooltip_text(name: &str, description: &str, exec: &str) -> String {
+		let description = scout_core::truncate_graphemes(description, MAX_TOOLTIP_DESCRIPTION);
+		[ Some(name), Some(description.as_str()).filter(|description| !description.is_empty()), Some(exec) ]
+			.iter().flatten().cloned().collect::<Vec<_>>().join("\n")
+	}
+
+
+	/**
+	 * Derives the style class added to a category label, such as `category-audio-video` for `AUDIO VIDEO`,
+	 * so that themes can color results by category. Runs of non-alphanumeric characters become single dashes.
+	 */
+
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn category_class(category: &str) -> String {
+		let name = category.to_lowercase().chars()
+			.map(|c| if c.is_alphanumeric() { c } else { '-' })
+			.collect::<String>();
+		let name = name.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-");
+		[ "category-", &name ].join("")
+	}
+
+
+	/**
+	 * Creates a new Program result, with a corresponding result widget if built with the `gui` feature.
+	 * Use `ApplicationResultBuilder` to construct results.
+	 */
+
+	fn new(builder: ApplicationResultBuilder) -> Self {
+		let ApplicationResultBuilder { id, source_path, name, unlocalized_name, keywords, generic_name, category, category_key, categories, description, version, icon,
+			exec, mut launch_options, actions, wm_class, popularity, monitor, window_state, doc_url, mime_types, file, preferences, history } = builder;
+		if preferences.borrow().notify_on_launch {
+			launch_options.notify_summary = Some(preferences.borrow().strings.launching.replace("{}", &name));
+		}
+
+		#[cfg(feature = "gui")]
+		let layout = ApplicationResult::get_result_layout(&preferences.borrow(), actions.as_ref().map_or(0, Vec::len));
+		#[cfg(feature = "gui")]
+		let (widget, top_button, actions_box) = ApplicationResult::build_result_widget(&name,
+			&ApplicationResult::choose_subtitle(generic_name.as_deref(), &category), &category, icon.as_deref(), layout);
+
+		#[cfg(feature = "gui")]
+		top_button.set_tooltip_text(Some(&ApplicationResult::get_tooltip_text(&name, &description, &match file.as_ref() {
+			Some(file) => ApplicationResult::format_exec_with_file(&exec, &name, &source_path, file),
+			None => ApplicationResult::format_exec(&exec, &name, &source_path)
+		})));
+
+		let result = ApplicationResult {
+			id, source_path, name, unlocalized_name, keywords, generic_name, category, category_key, categories, description, version, icon,
+			exec, launch_options, actions, wm_class, popularity, monitor, window_state, doc_url, mime_types, file,
+			#[cfg(feature = "gui")]
+			widget,
+			#[cfg(feature = "gui")]
+			top_button,
+			#[cfg(feature = "gui")]
+			actions_box,
+			preferences,
+			history,
+			context: Shared::new(SearchContext::default()),
+			score: 0
+		};
+
+		#[cfg(feature = "gui")]
 		{
-			top_button.get_style_context().add_class("flat");
-			widget.pack_start(&top_button, true, true, 0);
-
-			let exec = ApplicationResult::format_exec(exec);
-			top_button.connect_clicked(move |_| {
-				println!("Executing '{}'", &exec);
-				let args = shell_words::split(&exec).unwrap();
-				std::process::Command::new(&args[0]).args(&args[1..])
-					.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn().unwrap();
+			result.add_inline_actions();
+
+			let result_clone = result.clone();
+			result.top_button.connect_clicked(move |_| result_clone.activate());
+
+			let result_clone = result.clone();
+			result.top_button.connect_button_release_event(move |_, event| {
+				if event.get_button() != 2 { return gtk::Inhibit(false); }
+				result_clone.activate_secondary();
+				gtk::Inhibit(true)
 			});
+		}
+
+		result
+	}
 
-			let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
-			top_button.add(&widget_top);
-
-			let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
-			icon_box.get_style_context().add_class("IconBox");
-			widget_top.pack_start(&icon_box, false, false, 4);
-
-			let icon = ApplicationResult::get_icon(icon, 32);
-			icon.set_size_request(32, 32);
-			icon_box.pack_start(&icon, false, false, 0);
-
-			let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
-			widget_top.pack_start(&description_box, true, true, 0);
-
-			let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", category, "</span>" ].join("")));
-			category_label.get_style_context().add_class("Category");
-			category_label.set_ellipsize(pango::EllipsizeMode::End);
-			category_label.set_use_markup(true);
-			category_label.set_xalign(0.0);
-			description_box.pack_start(&category_label, false, false, 1);
-
-			let label = gtk::Label::new(Some(name));
-			label.set_ellipsize(pango::EllipsizeMode::End);
-			label.set_xalign(0.0);
-			description_box.pack_start(&label, false, false, 1);
-
-			if let Some(actions) = actions.as_ref() {
-				let widget_actions = gtk::Box::new(gtk::Orientation::Vertical, 0);
-				widget.pack_start(&widget_actions, true, true, 0);
-
-				for action in actions {
-					let widget_action_button = gtk::Button::new();
-					widget_action_button.get_style_context().add_class("flat");
-					widget_action_button.get_style_context().add_class("ActionButton");
-					widget_actions.pack_start(&widget_action_button, true, true, 0);
-
-					let exec = ApplicationResult::format_exec(&action.exec);
-					widget_action_button.connect_clicked(move |_| {
-						println!("Executing '{}'", &exec);
-						let args = shell_words::split(&exec).unwrap();
-						std::process::Command::new(&args[0]).args(&args[1..])
-							.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn().unwrap();
-					});
-
-					let widget_action = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-					widget_action_button.add(&widget_action);
-
-					let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
-					icon_box.get_style_context().add_class("IconBox");
-					widget_action.pack_start(&icon_box, false, false, 4);
-
-					let icon = gtk::Image::from_icon_name(Some("start-here-symbolic"), gtk::IconSize::Button);
-					icon.set_size_request(16, 16);
-					icon.set_pixel_size(16);
-					icon_box.pack_start(&icon, false, false, 0);
-
-					let action_label = gtk::Label::new(Some(&action.name));
-					action_label.set_ellipsize(pango::EllipsizeMode::End);
-					action_label.set_xalign(0.0);
-					widget_action.pack_start(&action_label, false, false, 4);
+
+	/**
+	 * Returns true if the program can open files of the MIME type specified.
+	 */
+
+	pub fn handles_mime(&self, mime: &str) -> bool {
+		mime::handles(&self.mime_types, mime)
+	}
+
+
+	/**
+	 * Creates a result that opens a file with this program, with its own widget.
+	 */
+
+	pub fn open_with(&self, file: &std::path::Path, score: usize) -> ApplicationResult {
+		let mut result = ApplicationResultBuilder {
+			id: self.id.clone(),
+			source_path: self.source_path.clone(),
+			name: self.name.clone(),
+			unlocalized_name: self.unlocalized_name.clone(),
+			keywords: self.keywords.clone(),
+			generic_name: self.generic_name.clone(),
+			category: self.category.clone(),
+			category_key: self.category_key.clone(),
+			categories: self.categories.clone(),
+			description: self.description.clone(),
+			version: self.version.clone(),
+			icon: self.icon.clone(),
+			exec: self.exec.clone(),
+			launch_options: self.launch_options.clone(),
+			actions: self.actions.clone(),
+			wm_class: self.wm_class.clone(),
+			popularity: self.popularity,
+			monitor: self.monitor,
+			window_state: self.window_state,
+			doc_url: self.doc_url.clone(),
+			mime_types: self.mime_types.clone(),
+			file: None,
+			preferences: self.preferences.clone(),
+			history: self.history.clone()
+		}.file(file).build();
+
+		result.score = score;
+		result
+	}
+
+
+	/**
+	 * Reads the contents of the desktop file the result was created from.
+	 */
+
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn read_source(&self) -> std::io::Result<String> {
+		std::fs::read_to_string(&self.source_path)
+	}
+
+
+	/**
+	 * Emits an activation event for this result, if enabled in the preferences.
+	 */
+
+	fn emit_event(&self) {
+		let preferences = self.preferences.borrow();
+		if !preferences.emit_events { return }
+
+		let context = self.context.borrow();
+		let event = ActivationEvent::new(&self.id, "application", &context.query, context.rank);
+		if let Err(err) = event.emit(&preferences.events_path) {
+			println!("[WARN] Failed to emit activation event: {}", err);
+		}
+	}
+
+	/**
+	 * Records an activation of the result in the launch history.
+	 */
+
+	fn record_history(&self) {
+		if let Err(err) = self.history.borrow_mut().record(&self.id) {
+			println!("[WARN] Failed to record launch history: {}", err);
+		}
+	}
+
+	/**
+	 * Launches a command of the result, then records the launch in the history, places and focuses the window,
+	 * and emits an activation event. Every way of launching the result goes through here, so that they're all counted.
+	 */
+
+	fn launch_exec(&self, exec: &str) {
+		let placement = self.wm_class.as_deref().and_then(|wm_class| Placement::new(wm_class, self.monitor, self.window_state));
+
+		match launch::launch(exec, &self.launch_options) {
+			Ok(()) => {
+				self.record_history();
+				if let Some(placement) = placement { placement.apply(); }
+				if let Some(focus_command) = self.preferences.borrow().focus_command.as_deref().filter(|command| !command.trim().is_empty()) {
+					placement::focus_window(focus_command, self.wm_class.as_deref().unwrap_or(&self.id));
 				}
-			}
+				self.emit_event();
+			},
+			Err(err) => println!("[WARN] Failed to launch '{}': {}", exec, err)
 		}
+	}
 
-		ApplicationResult {
-			name: name.to_owned(),
-			category: category.to_owned(),
-			description: description.to_owned(),
-			icon: icon.and_then(|s| Some(s.to_owned())),
-			exec: exec.to_owned(),
-			top_button,
-			actions,
-			widget,
-			score: 0
+
+	/**
+	 * Launches one of the result's desktop actions.
+	 */
+
+	fn launch_action(&self, action: &Action) {
+		self.launch_exec(&ApplicationResult::format_exec(&action.exec, &self.name, &self.source_path));
+	}
+
+	/**
+	 * Scores the query against one of the result's names.
+	 */
+
+	fn get_name_score(&self, query: &str, name: &str) -> RankingBreakdown {
+		let lowercase_name = ranking::normalize(name);
+		let mut score = scout_core::get_name_score(query, &lowercase_name);
+
+		if score == 0 && self.preferences.borrow().typo_tolerance {
+			score = ranking::typo_score(query, &lowercase_name);
+		}
+
+		let mut breakdown = RankingBreakdown { name: name.to_owned(), ..RankingBreakdown::default() };
+		if score > 0 {
+			let preferences = self.preferences.borrow();
+			breakdown.length_penalty = ranking::length_penalty(query, &lowercase_name, preferences.length_penalty_weight);
+			breakdown.fuzzy = std::cmp::max(score.saturating_sub(breakdown.length_penalty), 1);
+			breakdown.bonus = Some(ranking::match_bonus(query, &lowercase_name)).filter(|bonus| *bonus > 0)
+				.unwrap_or_else(|| std::cmp::max(ranking::acronym_bonus(query, name, &preferences.word_separators, preferences.split_camel_case),
+					ranking::capital_bonus(query, name)));
 		}
+
+		breakdown
 	}
 
-	pub fn set_score_from_query(&mut self, query: &str) {
-		let mut score = 0;
-		let mut last_letter_ind: usize = 0;
-		let mut lowercase_name = self.name.to_lowercase();
-		lowercase_name.retain(|c| !c.is_whitespace());
-
-		for letter in query.chars() {
-			let mut name_chars = lowercase_name.chars().skip(last_letter_ind);
-			let pos = name_chars.position(|c| c == letter).map_or(-1, |c| c as isize);
-			if pos >= 0 {
-				last_letter_ind += pos as usize + 1;
-				score += std::cmp::max(10 - pos, 0) as usize
-			}
+	/**
+	 * Returns true if the result's desktop actions should be shown for a query,
+	 * so that short queries aren't flooded with actions.
+	 */
+
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	fn shows_actions(&self, query: &str) -> bool {
+		query.chars().count() > self.preferences.borrow().actions_min_query_length
+	}
+
+	/**
+	 * Returns the program the result runs, used to find its running processes.
+	 */
+
+	pub fn get_program(&self) -> Option<String> {
+		launch::get_args(&ApplicationResult::format_exec(&self.exec, &self.name, &self.source_path)).ok()?.into_iter().next()
+	}
+
+	pub fn get_wm_class(&self) -> Option<&str> {
+		self.wm_class.as_deref()
+	}
+
+	#[cfg(test)]
+	pub fn get_substitute_user(&self) -> Option<&str> {
+		self.launch_options.substitute_user.as_deref()
+	}
+
+
+	/**
+	 * Returns the program's documentation, which the preview has a help button for if there is one.
+	 */
+
+	#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+	pub fn get_doc_url(&self) -> Option<&str> {
+		self.doc_url.as_deref()
+	}
+
+
+	/**
+	 * Returns the names, keywords, desktop file ID components, and program name that queries are scored against.
+	 */
+
+	pub fn get_search_names(&self) -> Vec<&str> {
+		std::iter::once(&self.name).chain(self.unlocalized_name.iter()).chain(self.keywords.iter()).chain(self.generic_name.iter())
+			.map(|name| name.as_str()).chain(ranking::get_id_components(&self.id)).chain(ranking::get_exec_name(&self.exec)).collect()
+	}
+
+	/**
+	 * Scores the query against the result's names and keywords, without its launch history.
+	 */
+
+	pub fn get_query_score(&self, query: &str) -> usize {
+		self.get_query_breakdown(query).get_score()
+	}
+
+
+	/**
+	 * Returns the parts of the score of the best matching names or keywords, without the launch history.
+	 * Keywords and the generic name count for half as much as names, and names with a penalized term
+	 * count for half as much unless the query has it too. Queries with several terms score the sum
+	 * of each term's score if it is higher, but score nothing if any term doesn't match.
+	 */
+
+	fn get_query_breakdown(&self, query: &str) -> RankingBreakdown {
+		let whole = self.get_best_breakdown(&ranking::normalize(query));
+
+		// Queries with several terms also match if every term matches on its own, in any order.
+		let terms: Vec<_> = query.split_whitespace().collect();
+		let mut breakdown = if terms.len() < 2 { whole } else {
+			let term_breakdowns: Vec<_> = terms.iter().map(|term| self.get_best_breakdown(&ranking::normalize(term))).collect();
+			if term_breakdowns.iter().any(|breakdown| breakdown.get_score() == 0) { RankingBreakdown::default() }
+			else { std::cmp::max_by_key(whole, RankingBreakdown::sum(&term_breakdowns), |breakdown| breakdown.get_score()) }
+		};
+
+		if ranking::is_penalized(&ranking::normalize(query), &self.name, &self.preferences.borrow().penalized_terms) {
+			let score = breakdown.get_score();
+			breakdown.term_penalty = score - score / 2;
 		}
 
+		breakdown
+	}
+
+
+	/**
+	 * Returns the breakdown of the best matching field for a normalized query or term, after applying each field's weight
+	 * from `field_weights`. The fields are the names, including without their leading stop words if enabled, the generic name,
+	 * the keywords, the desktop file ID and its components, and the name of the program run. Ties go to the earlier field.
+	 */
+
+	fn get_best_breakdown(&self, query: &str) -> RankingBreakdown {
+		let preferences = self.preferences.borrow();
+		let weights = preferences.field_weights.clamped();
+		let stripped_names: Vec<String> = if !preferences.strip_stop_words { vec![] } else {
+			std::iter::once(&self.name).chain(self.unlocalized_name.iter())
+				.filter_map(|name| ranking::strip_stop_words(name, &preferences.stop_words)).collect()
+		};
+		drop(preferences);
+
+		let names = std::iter::once(self.name.as_str()).chain(self.unlocalized_name.as_deref()).chain(stripped_names.iter().map(|name| name.as_str()))
+			.map(|name| (name, weights.name));
+		let generic_name = self.generic_name.as_deref().map(|generic_name| (generic_name, weights.generic_name));
+		let keywords = self.keywords.iter().map(|keyword| (keyword.as_str(), weights.keywords));
+		let id = ranking::get_id_components(&self.id).into_iter().map(|component| (component, weights.id));
+		let exec = ranking::get_exec_name(&self.exec).map(|exec| (exec, weights.exec));
+
+		// Reversed, so that `max_by_key`, which keeps the last of equal scores, keeps the earliest field.
+		names.chain(generic_name).chain(keywords).chain(id).chain(exec).rev()
+			.map(|(field, weight)| ranking::apply_weight(self.get_name_score(query, field), weight))
+			.max_by_key(|breakdown| breakdown.get_score()).unwrap_or_default()
+	}
+
+
+	/**
+	 * Explains the result's score for a query, including its launch history and popularity.
+	 */
+
+	pub fn explain_ranking(&self, query: &str) -> RankingBreakdown {
+		let mut breakdown = self.get_query_breakdown(query);
+		if breakdown.get_score() > 0 {
+			breakdown.frequency = ranking::frequency_score(self.get_frequency());
+			breakdown.popularity = self.popularity;
+		}
+		breakdown
+	}
+
+	pub fn set_score_from_query(&mut self, query: &str) {
+		let score = self.get_query_score(query);
+		self.set_query_score(query, score);
+	}
+
+
+	/**
+	 * Sets the result's score for a query from a score returned by `get_query_score`, adding its launch history and popularity.
+	 */
+
+	pub fn set_query_score(&mut self, query: &str, mut score: usize) {
+		self.context.borrow_mut().query = query.to_owned();
+
+		#[cfg(feature = "gui")]
+		if let Some(actions_box) = self.actions_box.as_ref() { actions_box.set_visible(self.shows_actions(&ranking::normalize(query))); }
+
+		if score > 0 { score += ranking::frequency_score(self.get_frequency()) + self.popularity; }
+
 		self.score = score
 	}
 
@@ -221,74 +1017,86 @@ impl SearchResult for ApplicationResult {
 		self.score
 	}
 
+	fn get_id(&self) -> String {
+		self.id.clone()
+	}
+
+	fn get_title(&self) -> String {
+		self.name.clone()
+	}
+
+	fn get_subtitle(&self) -> String {
+		ApplicationResult::choose_subtitle(self.generic_name.as_deref(), &self.category)
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		self.icon.clone()
+	}
+
+	fn get_category(&self) -> Option<String> {
+		Some(self.category.clone())
+	}
+
+	fn get_category_key(&self) -> Option<String> {
+		self.category_key.clone().or_else(|| self.get_category())
+	}
+
+	#[cfg(feature = "gui")]
 	fn set_first(&self, first: bool) -> () {
 		self.top_button.set_can_focus(!first);
 	}
 
+	fn set_rank(&self, rank: usize) {
+		self.context.borrow_mut().rank = rank;
+	}
+
+	fn get_frequency(&self) -> f64 {
+		let half_life = self.preferences.borrow().history_half_life_days * 24.0 * 60.0 * 60.0;
+		self.history.borrow().get_frequency(&self.id, History::now(), half_life)
+	}
+
+	fn get_last_used(&self) -> Option<u64> {
+		self.history.borrow().get_last_launch(&self.id)
+	}
+
+	fn explain_score(&self) -> Option<String> {
+		Some(format!("{:?}", self.explain_ranking(&self.context.borrow().query)))
+	}
+
 	fn activate(&self) {
-		let exec = ApplicationResult::format_exec(&self.exec);
-		println!("Executing '{}'", &exec);
-		let args = shell_words::split(&exec).unwrap();
-		std::process::Command::new(&args[0]).args(&args[1..])
-			.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn().unwrap();
+		if let Some(wm_class) = self.wm_class.as_ref().filter(|_| self.file.is_none()) {
+			// Raising the running window still counts as activating the result.
+			if self.preferences.borrow().raise_running && launch::raise(wm_class) {
+				self.record_history();
+				self.emit_event();
+				return;
+			}
+		}
+
+		let exec = match self.file.as_ref() {
+			Some(file) => ApplicationResult::format_exec_with_file(&self.exec, &self.name, &self.source_path, file),
+			None => ApplicationResult::format_exec(&self.exec, &self.name, &self.source_path)
+		};
+		self.launch_exec(&exec);
+	}
+
+	fn activate_secondary(&self) {
+		let action = match self.actions.as_ref().and_then(|actions| actions.first()) {
+			Some(action) => action,
+			None => return self.activate()
+		};
+
+		self.launch_action(action);
 	}
 
+	#[cfg(feature = "gui")]
 	fn get_result_widget(&self) -> gtk::Widget {
 		self.widget.clone().upcast()
 	}
 
+	#[cfg(feature = "gui")]
 	fn get_preview_widget(&self) -> gtk::Widget {
-		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
-		widget.get_style_context().add_class("Application");
-		widget.set_widget_name("SearchPreview");
-		widget.set_border_width(36);
-
-		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
-		icon_box.get_style_context().add_class("IconBox");
-		icon_box.set_halign(gtk::Align::Center);
-		widget.pack_start(&icon_box, false, false, 0);
-
-		let icon = ApplicationResult::get_icon(self.icon.as_ref().and_then(|s| Some(s.as_str())), 96);
-		icon_box.pack_start(&icon, false, false, 0);
-
-		let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &self.category, "</span>" ].join("")));
-		category_label.get_style_context().add_class("Category");
-		category_label.set_ellipsize(pango::EllipsizeMode::End);
-		category_label.set_use_markup(true);
-		widget.pack_start(&category_label, false, false, 0);
-
-		let label = gtk::Label::new(Some(&self.name));
-		label.set_ellipsize(pango::EllipsizeMode::End);
-		widget.pack_start(&label, false, false, 4);
-
-		let description = gtk::Label::new(Some(&[ &self.description, "." ].join("")));
-		description.get_style_context().add_class("Description");
-
-		description.set_line_wrap_mode(pango::WrapMode::WordChar);
-		description.set_ellipsize(pango::EllipsizeMode::End);
-		description.set_justify(gtk::Justification::Center);
-		description.set_halign(gtk::Align::Center);
-		description.set_max_width_chars(36);
-		description.set_line_wrap(true);
-		description.set_lines(5);
-
-		widget.pack_start(&description, false, false, 0);
-
-		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-		button_box.get_style_context().add_class("ButtonBox");
-		button_box.get_style_context().add_class("linked");
-		button_box.set_halign(gtk::Align::Center);
-		button_box.set_valign(gtk::Align::End);
-		widget.pack_end(&button_box, false, false, 0);
-
-		let launch_button = gtk::Button::from_icon_name(Some("media-playback-start-symbolic"), gtk::IconSize::Button);
-		button_box.pack_start(&launch_button, false, false, 0);
-		let favorite_button = gtk::Button::from_icon_name(Some("emblem-favorite-symbolic"), gtk::IconSize::Button);
-		button_box.pack_start(&favorite_button, false, false, 0);
-		let edit_button = gtk::Button::from_icon_name(Some("document-edit-symbolic"), gtk::IconSize::Button);
-		button_box.pack_start(&edit_button, false, false, 0);
-
-		return widget.upcast();
+		self.build_preview_widget().upcast()
 	}
 }
 
@@ -312,3 +1120,376 @@ impl PartialEq for ApplicationResult {
 	}
 }
 
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_preferences(json: &str) -> Shared<Preferences> {
+		Shared::new(serde_json::from_str(json).unwrap())
+	}
+
+	fn get_builder(name: &str, exec: &str, preferences: &str) -> ApplicationResultBuilder {
+		ApplicationResultBuilder::new(&name.to_lowercase().replace(' ', "-"), name, exec, get_preferences(preferences), Shared::new(History::default()))
+	}
+
+	fn get_action(id: &str) -> Action {
+		Action {
+			id: id.to_owned(),
+			#[cfg(feature = "gui")]
+			name: id.to_owned(),
+			exec: [ "firefox --", id ].join("")
+		}
+	}
+
+	fn get_action_ids(actions: &[Action]) -> Vec<&str> {
+		actions.iter().map(|action| action.id.as_str()).collect()
+	}
+
+	#[test]
+	fn listed_actions_come_first_in_order() {
+		let mut actions = [ "new-window", "new-private-window", "profile-manager", "safe-mode" ].iter().map(|id| get_action(id)).collect::<Vec<_>>();
+		Action::sort_by_order(&mut actions, &[ "safe-mode".to_owned(), "missing".to_owned(), "new-private-window".to_owned() ]);
+		assert_eq!(get_action_ids(&actions), vec![ "safe-mode", "new-private-window", "new-window", "profile-manager" ]);
+
+		Action::sort_by_order(&mut actions, &[]);
+		assert_eq!(get_action_ids(&actions), vec![ "safe-mode", "new-private-window", "new-window", "profile-manager" ]);
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn program_subtitles_are_their_category() {
+		let result = get_builder("Calculator", "gnome-calculator", "{}").category("UTILITY").build();
+		assert_eq!(result.get_subtitle(), "UTILITY");
+		assert_eq!(get_builder("Calculator", "gnome-calculator", "{}").build().get_subtitle(), "APPLICATION");
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn results_build_from_only_the_required_fields() {
+		let result = ApplicationResultBuilder::new("org.gnome.Nautilus", "Files", "nautilus --new-window",
+			get_preferences("{}"), Shared::new(History::default())).build();
+
+		assert_eq!(result.get_id(), "org.gnome.Nautilus");
+		assert_eq!(result.get_title(), "Files");
+		assert_eq!(result.get_category(), Some("APPLICATION".to_owned()));
+		assert_eq!(result.get_icon_name(), None);
+		assert_eq!(result.get_program(), Some("nautilus".to_owned()));
+		assert_eq!(result.get_score(), 0);
+	}
+
+	fn get_score(name: &str, query: &str) -> usize {
+		get_builder(name, &name.to_lowercase().replace(' ', "-"), "{}").build().get_query_score(query)
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn exact_names_beat_partial_matches() {
+		let exact = get_score("Code", "code");
+		assert!(exact > get_score("CodeBlocks", "code"));
+		assert!(exact > get_score("Visual Studio Code", "code"));
+		assert!(exact > get_score("Code Editor Pro", "code"));
+		assert!(get_score("CodeBlocks", "code") > get_score("Visual Studio Code", "code"));
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn spaced_queries_match_spaced_names() {
+		let mut result = get_builder("File Roller", "file-roller", "{}").build();
+		result.set_score_from_query("file roller");
+		let spaced = result.get_score();
+		result.set_score_from_query("fileroller");
+
+		assert!(spaced > 0);
+		assert_eq!(spaced, result.get_score());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn longer_names_with_the_same_prefix_score_slightly_less() {
+		let score = |name: &str, preferences: &str| get_builder(name, "code", preferences).build().get_query_score("cod");
+		let (short, long) = (score("Code", "{}"), score("Code Editor Professional Suite", "{}"));
+
+		assert!(short > long);
+		assert!(short - long <= ranking::MAX_LENGTH_PENALTY);
+		assert_eq!(score("Code", r#"{ "length_penalty_weight": 0 }"#), score("Code Editor Professional Suite", r#"{ "length_penalty_weight": 0 }"#));
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn distinct_generic_names_replace_the_category() {
+		let build = |generic_name| get_builder("Files", "nautilus", "{}").generic_name(generic_name).category("SYSTEM").build().get_subtitle();
+		assert_eq!(build(Some("File Manager")), "FILE MANAGER");
+		assert_eq!(build(Some(" files ")), "SYSTEM");
+		assert_eq!(build(Some("")), "SYSTEM");
+		assert_eq!(build(None), "SYSTEM");
+	}
+
+	#[test]
+	fn exec_field_codes_are_formatted() {
+		let source = std::path::Path::new("/usr/share/applications/viewer.desktop");
+		assert_eq!(launch::get_args(&ApplicationResult::format_exec("viewer %F", "Viewer", source)).unwrap(), vec![ "viewer" ]);
+		assert_eq!(ApplicationResult::format_exec("viewer --zoom=100%%", "Viewer", source), "viewer --zoom=100%");
+		assert_eq!(ApplicationResult::format_exec_with_file("viewer %u", "Viewer", source, std::path::Path::new("/tmp/my photo.png")),
+			"viewer '/tmp/my photo.png'");
+	}
+
+	#[test]
+	fn deprecated_field_codes_are_removed() {
+		let source = std::path::Path::new("/usr/share/applications/viewer.desktop");
+		for code in &[ "%D", "%d", "%n", "%N", "%v", "%m" ] {
+			let exec = ApplicationResult::format_exec(&[ "viewer ", code, " --new" ].join(""), "Viewer", source);
+			assert!(!exec.contains('~') && !exec.contains('%'), "{} was formatted as {:?}", code, exec);
+			assert_eq!(launch::get_args(&exec).unwrap(), vec![ "viewer", "--new" ]);
+		}
+	}
+
+	#[test]
+	fn desktop_file_codes_expand_to_the_source_path() {
+		let source = std::path::Path::new("/usr/share/applications/my viewer.desktop");
+		assert_eq!(launch::get_args(&ApplicationResult::format_exec("viewer --desktop-file %k", "Viewer", source)).unwrap(),
+			vec![ "viewer", "--desktop-file", "/usr/share/applications/my viewer.desktop" ]);
+	}
+
+	#[test]
+	fn categories_are_chosen_in_order() {
+		assert_eq!(ApplicationResult::choose_category(Some("Utility;TextEditor;"), "APPLICATION"), "UTILITY");
+		assert_eq!(ApplicationResult::choose_category(Some("GTK;AudioVideo;Player;"), "APPLICATION"), "AUDIO VIDEO");
+		assert_eq!(ApplicationResult::choose_category(None, "APPLICATION"), "APPLICATION");
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn actions_are_hidden_for_short_queries() {
+		let result = get_builder("Firefox", "firefox", r#"{ "actions_min_query_length": 1 }"#).build();
+		assert!(!result.shows_actions("f"));
+		assert!(result.shows_actions("fi"));
+		assert!(get_builder("Firefox", "firefox", "{}").build().shows_actions("f"));
+	}
+
+	#[test]
+	fn category_classes_are_sanitized() {
+		assert_eq!(ApplicationResult::category_class("GAME"), "category-game");
+		assert_eq!(ApplicationResult::category_class("AUDIO VIDEO"), "category-audio-video");
+		assert_eq!(ApplicationResult::category_class(" Office & Work! "), "category-office-work");
+		assert_eq!(ApplicationResult::category_class("3D GRAPHICS"), "category-3d-graphics");
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn disabled_labels_are_left_out() {
+		let build = |preferences| get_builder("Blender", "blender", preferences)
+			.category("GRAPHICS").version(Some("3.0")).build().get_preview_labels();
+
+		assert_eq!(build("{}"), PreviewLabels { category: true, version: true, ..Default::default() });
+		assert_eq!(build(r#"{ "show_category": false }"#), PreviewLabels { version: true, ..Default::default() });
+		assert_eq!(build(r#"{ "show_version": false }"#), PreviewLabels { category: true, ..Default::default() });
+		assert_eq!(build(r#"{ "show_category": false, "show_version": false }"#), PreviewLabels::default());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn missing_versions_are_left_out() {
+		let labels = get_builder("Blender", "blender", "{}").build().get_preview_labels();
+		assert!(!labels.version);
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn sources_are_read_from_the_desktop_file() {
+		let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/applications/editor.desktop");
+		let result = get_builder("Text Editor", "editor %F", "{}").source_path(&path).build();
+		assert_eq!(result.read_source().unwrap(), std::fs::read_to_string(&path).unwrap());
+		assert!(result.read_source().unwrap().starts_with("[Desktop Entry]\nType=Application\nName=Text Editor\n"));
+
+		let missing = get_builder("Text Editor", "editor %F", "{}").source_path(&path.with_file_name("missing.desktop")).build();
+		assert!(missing.read_source().is_err());
+	}
+
+	#[test]
+	fn tooltips_show_the_name_description_and_command() {
+		assert_eq!(ApplicationResult::get_tooltip_text("Firefox", "Browse the World Wide Web", "firefox %u"),
+			"Firefox\nBrowse the World Wide Web\nfirefox %u");
+		assert_eq!(ApplicationResult::get_tooltip_text("Firefox", "", "firefox"), "Firefox\nfirefox");
+
+		let tooltip = ApplicationResult::get_tooltip_text("Firefox", &"a".repeat(MAX_TOOLTIP_DESCRIPTION + 10), "firefox");
+		assert_eq!(tooltip, [ "Firefox\n", &"a".repeat(MAX_TOOLTIP_DESCRIPTION - 1), "…\nfirefox" ].join(""));
+	}
+
+	#[test]
+	fn vendor_categories_are_never_displayed() {
+		assert_eq!(ApplicationResult::choose_category(Some("X-Foo;Utility;"), "APPLICATION"), "UTILITY");
+		assert_eq!(ApplicationResult::choose_category(Some("X-GNOME-Utilities;x-lowercase;"), "APPLICATION"), "APPLICATION");
+		assert_eq!(ApplicationResult::get_display_categories(Some("X-Foo;Game;X-Bar;ArcadeGame;")), vec![ "GAME", "ARCADE GAME" ]);
+	}
+
+	#[test]
+	fn category_override_replaces_the_category() {
+		assert_eq!(ApplicationResult::override_category(Some("AudioVideo")), Some("AUDIO VIDEO".to_owned()));
+		assert_eq!(ApplicationResult::override_category(Some(" game ")), Some("GAME".to_owned()));
+		assert_eq!(ApplicationResult::override_category(Some("X-Custom")), None);
+		assert_eq!(ApplicationResult::choose_category_key(Some("Utility;"), Some("Game")), Some("Game".to_owned()));
+		assert_eq!(ApplicationResult::choose_category_key(Some("Utility;"), Some("X-Custom")), Some("Utility".to_owned()));
+	}
+
+
+	#[test]
+	fn compact_results_have_no_inline_actions() {
+		let layout = ApplicationResult::get_result_layout(&get_preferences(r#"{ "compact": true, "show_category": false }"#).borrow(), 3);
+		assert_eq!(layout, ResultLayout { compact: true, subtitle: false, inline_actions: 0 });
+
+		let layout = ApplicationResult::get_result_layout(&get_preferences("{}").borrow(), 3);
+		assert!(!layout.compact);
+		assert_eq!(layout.inline_actions, 3);
+	}
+
+
+	#[test]
+	fn class_codes_expand_to_the_quoted_name() {
+		let source = std::path::Path::new("/usr/share/applications/viewer.desktop");
+		assert_eq!(ApplicationResult::format_exec("viewer --class %c", "Visionneuse", source), "viewer --class Visionneuse");
+		assert_eq!(ApplicationResult::format_exec("viewer --class %c", "Image Viewer", source), "viewer --class 'Image Viewer'");
+		assert_eq!(launch::get_args(&ApplicationResult::format_exec("viewer --class %c", "Tom's Viewer", source)).unwrap(),
+			vec![ "viewer", "--class", "Tom's Viewer" ]);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn inline_actions_are_limited_by_the_preferences() {
+		let actions = || Some([ "new-window", "new-private-window", "profile-manager" ].iter().map(|id| get_action(id)).collect());
+		let inline_actions = |preferences| get_builder("Firefox", "firefox %u", preferences).actions(actions()).build().get_layout().inline_actions;
+
+		assert_eq!(inline_actions("{}"), 3);
+		assert_eq!(inline_actions(r#"{ "max_inline_actions": 2 }"#), 2);
+		assert_eq!(inline_actions(r#"{ "max_inline_actions": 0 }"#), 0);
+		assert_eq!(inline_actions(r#"{ "max_inline_actions": 5 }"#), 3);
+		assert_eq!(get_builder("Firefox", "firefox %u", "{}").build().get_layout().inline_actions, 0);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn ranking_breakdowns_sum_to_the_score() {
+		let mut result = get_builder("Visual Studio Code", "code %F", "{}").keywords(vec![ "editor".to_owned() ]).popularity(7).build();
+
+		for query in [ "code", "vsc", "editor", "studio visual", "missing" ].iter() {
+			result.set_score_from_query(query);
+			let breakdown = result.explain_ranking(query);
+			assert_eq!(breakdown.get_score(), result.get_score(), "{}", query);
+		}
+
+		let breakdown = result.explain_ranking("editor");
+		assert_eq!(breakdown.name, "editor");
+		assert!(breakdown.field_penalty > 0);
+		assert_eq!(breakdown.popularity, 7);
+		assert_eq!(result.explain_ranking("missing").get_score(), 0);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn queries_match_past_leading_stop_words() {
+		let build = |preferences| get_builder("The GIMP", "gimp %U", preferences).build();
+		let (stripped, unstripped) = (build(r#"{ "strip_stop_words": true }"#), build("{}"));
+
+		assert!(stripped.get_query_score("gimp") > unstripped.get_query_score("gimp"));
+		assert_eq!(stripped.explain_ranking("gimp").name, "GIMP");
+		assert_eq!(stripped.get_title(), "The GIMP");
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn uninstallers_only_outrank_the_program_when_asked_for() {
+		let (writer, uninstaller) = (get_score("LibreOffice Writer", "libre"), get_score("Uninstall LibreOffice", "libre"));
+		assert!(writer > uninstaller);
+		assert!(get_score("Uninstall LibreOffice", "uninstall libre") > get_score("LibreOffice Writer", "uninstall libre"));
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn every_term_of_a_query_must_match() {
+		let editor = get_builder("Text Editor", "gedit %U", "{}").generic_name(Some("Notepad")).build();
+		assert!(editor.get_query_score("text editor") > 0);
+		assert!(editor.get_query_score("editor text") > 0);
+		assert!(editor.get_query_score("editor notepad") > 0);
+		assert_eq!(editor.get_query_score("text xyz"), 0);
+		assert!(editor.get_query_score("editor text") > editor.get_query_score("editor"));
+	}
+
+
+	#[test]
+	fn profiles_become_actions_with_their_arguments() {
+		let actions = Action::from_profiles(Some("Work:-P work;Personal: -P 'my personal' ;:-P none;Empty:;"), "firefox %u", "{} Profile");
+		assert_eq!(get_action_ids(&actions), vec![ "profile-Work", "profile-Personal" ]);
+
+		let source = std::path::Path::new("/usr/share/applications/firefox.desktop");
+		let args = |action: &Action| launch::get_args(&ApplicationResult::format_exec(&action.exec, "Firefox", source)).unwrap();
+		assert_eq!(args(&actions[0]), vec![ "firefox", "-P", "work" ]);
+		assert_eq!(args(&actions[1]), vec![ "firefox", "-P", "my personal" ]);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn launch_notifications_show_the_command_spawned() {
+		let result = get_builder("Image Viewer", "sh --class %c %f", r#"{ "notify_on_launch": true }"#).nice(5).build();
+		let exec = ApplicationResult::format_exec(&result.exec, &result.name, &result.source_path);
+		let args = launch::get_command(&exec, &result.launch_options).unwrap();
+		let program = launch::resolve_executable("sh").unwrap().to_string_lossy().into_owned();
+		assert_eq!(launch::get_notification(&result.launch_options, &args),
+			Some(("Launching Image Viewer", [ "nice -n 5 ", &program, " --class 'Image Viewer'" ].join(""))));
+
+		let result = get_builder("Image Viewer", "sh %f", "{}").build();
+		assert_eq!(launch::get_notification(&result.launch_options, &[ "sh".to_owned() ]), None);
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn id_components_match_below_names() {
+		let build = |id: &str, name: &str| ApplicationResultBuilder::new(id, name, "files-app", get_preferences("{}"), Shared::new(History::default())).build();
+		let files = build("org.gnome.Nautilus", "Files");
+
+		assert!(files.get_query_score("nautilus") > 0);
+		assert!(files.get_query_score("gnome") > 0);
+		assert!(build("nautilus", "Nautilus").get_query_score("nautilus") > files.get_query_score("nautilus"));
+	}
+
+
+	#[test]
+	fn every_displayable_category_becomes_a_badge() {
+		assert_eq!(ApplicationResult::get_display_categories(Some("GTK;GNOME;AudioVideo;Audio;Player;audio;Network;")),
+			vec![ "AUDIO VIDEO", "AUDIO", "PLAYER" ]);
+		assert_eq!(ApplicationResult::get_display_categories(Some("Application;X-GNOME-Utilities;")), Vec::<String>::new());
+		assert_eq!(ApplicationResult::get_display_categories(None), Vec::<String>::new());
+	}
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn badges_replace_the_category_when_enabled() {
+		let build = |categories: Vec<&str>, preferences| get_builder("Rhythmbox", "rhythmbox", preferences).category("AUDIO VIDEO")
+			.generic_name(Some("Music Player")).categories(categories.into_iter().map(str::to_owned).collect()).build().get_preview_labels();
+		let enabled = r#"{ "show_all_categories": true }"#;
+
+		assert_eq!(build(vec![ "AUDIO VIDEO", "AUDIO" ], enabled), PreviewLabels { generic_name: true, badges: true, ..Default::default() });
+		assert_eq!(build(vec![ "AUDIO VIDEO", "AUDIO" ], "{}"), PreviewLabels { category: true, ..Default::default() });
+		assert_eq!(build(vec![], enabled), PreviewLabels { category: true, ..Default::default() });
+	}
+
+
+	#[test]
+	#[cfg_attr(feature = "gui", ignore = "building a result creates widgets, which needs a display")]
+	fn name_matches_beat_equal_keyword_matches() {
+		let keyword_score = |preferences| get_builder("Gedit", "gedit", preferences).keywords(vec![ "editor".to_owned() ]).build().get_query_score("editor");
+		let name_score = get_builder("Editor", "gedit", "{}").build().get_query_score("editor");
+
+		assert!(name_score > keyword_score("{}"));
+		assert_eq!(keyword_score(r#"{ "field_weights": { "keywords": 1.0 } }"#), name_score);
+		assert_eq!(keyword_score(r#"{ "field_weights": { "keywords": 5.0 } }"#), name_score);
+		assert_eq!(keyword_score(r#"{ "field_weights": { "keywords": 0.0 } }"#), 0);
+	}
+}
@@ -0,0 +1,343 @@
+/*!
+ * Builds the widgets for application results. Only compiled with the `gui` feature.
+ */
+
+use gtk::prelude::*;
+
+use scout_core::SearchResult;
+
+use crate::launch;
+use super::{ Action, ApplicationResult, ResultLayout };
+
+impl ApplicationResult {
+
+	/**
+	 * Finds an icon from an icon name specified in the desktop file.
+	 */
+
+	pub fn get_icon(icon: Option<&str>, scale: i32) -> gtk::Image {
+		let flags = gtk::IconLookupFlags::USE_BUILTIN | gtk::IconLookupFlags::GENERIC_FALLBACK | gtk::IconLookupFlags::FORCE_SIZE;
+		let theme = gtk::IconTheme::get_default().unwrap();
+
+		let find_in_theme  = || theme.load_icon(&icon.unwrap_or(""), scale, flags).map_err(|_| ()).unwrap_or(None).ok_or(());
+		let find_from_file = || gdk_pixbuf::Pixbuf::from_file_at_scale(&icon.unwrap_or(""), scale, scale, true).map_err(|_| ());
+		let find_default   = || theme.load_icon("application-x-executable", scale, flags).map_err(|_| ()).unwrap_or(None).ok_or(());
+
+		match find_from_file().or_else(|_| find_in_theme()).or_else(|_| find_default()) {
+			Ok(pixbuf) => gtk::Image::from_pixbuf(Some(&pixbuf)),
+			// Leave a blank space instead of the broken image icon if the theme has no generic icon either.
+			Err(_) => {
+				let image = gtk::Image::new();
+				image.set_pixel_size(scale);
+				image
+			}
+		}
+	}
+
+
+	/**
+	 * Creates a button that launches one of the result's desktop actions.
+	 */
+
+	fn build_action_button(&self, action: &Action) -> gtk::Button {
+		let widget_action_button = gtk::Button::new();
+		widget_action_button.get_style_context().add_class("flat");
+		widget_action_button.get_style_context().add_class("ActionButton");
+
+		let (result, action_clone) = (self.clone(), action.clone());
+		widget_action_button.connect_clicked(move |_| result.launch_action(&action_clone));
+
+		let widget_action = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		widget_action_button.add(&widget_action);
+
+		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		icon_box.get_style_context().add_class("IconBox");
+		widget_action.pack_start(&icon_box, false, false, 4);
+
+		let icon = gtk::Image::from_icon_name(Some("start-here-symbolic"), gtk::IconSize::Button);
+		icon.set_size_request(16, 16);
+		icon.set_pixel_size(16);
+		icon_box.pack_start(&icon, false, false, 0);
+
+		let action_label = gtk::Label::new(Some(&action.name));
+		action_label.set_ellipsize(pango::EllipsizeMode::End);
+		action_label.set_xalign(0.0);
+		widget_action.pack_start(&action_label, false, false, 4);
+
+		widget_action_button
+	}
+
+
+	/**
+	 * Creates a compact result widget, with the icon, name, and subtitle on a single line and no actions.
+	 */
+
+	fn build_compact_result_widget(name: &str, subtitle: Option<&str>, category: &str, icon: Option<&str>) -> (gtk::Box, gtk::Button) {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Application");
+		widget.get_style_context().add_class("Compact");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		top_button.add(&widget_top);
+
+		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		icon_box.get_style_context().add_class("IconBox");
+		widget_top.pack_start(&icon_box, false, false, 4);
+
+		let icon = ApplicationResult::get_icon(icon, 16);
+		icon.set_size_request(16, 16);
+		icon_box.pack_start(&icon, false, false, 0);
+
+		let label = gtk::Label::new(Some(name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		label.set_xalign(0.0);
+		widget_top.pack_start(&label, true, true, 4);
+
+		if let Some(subtitle) = subtitle {
+			let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(subtitle), "</span>" ].join("")));
+			category_label.get_style_context().add_class("Category");
+			category_label.get_style_context().add_class(&ApplicationResult::category_class(category));
+			category_label.set_ellipsize(pango::EllipsizeMode::End);
+			category_label.set_use_markup(true);
+			category_label.set_xalign(1.0);
+			widget_top.pack_end(&category_label, false, false, 4);
+		}
+
+		(widget, top_button)
+	}
+
+
+	/**
+	 * Creates the result widget with the layout specified, returning it, its top button,
+	 * and the box its actions are added to with `add_inline_actions`, if the layout has inline actions.
+	 * The category label shows `subtitle`, and is omitted if the layout has no subtitle.
+	 */
+
+	pub(super) fn build_result_widget(name: &str, subtitle: &str, category: &str, icon: Option<&str>, layout: ResultLayout)
+		-> (gtk::Box, gtk::Button, Option<gtk::Box>) {
+
+		let subtitle = Some(subtitle).filter(|_| layout.subtitle);
+		if layout.compact {
+			let (widget, top_button) = ApplicationResult::build_compact_result_widget(name, subtitle, category, icon);
+			return (widget, top_button, None);
+		}
+
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Application");
+		widget.set_widget_name("SearchResult");
+		let top_button = gtk::Button::new();
+		let mut actions_box = None;
+
+		{
+			top_button.get_style_context().add_class("flat");
+			widget.pack_start(&top_button, true, true, 0);
+
+			let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+			top_button.add(&widget_top);
+
+			let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+			icon_box.get_style_context().add_class("IconBox");
+			widget_top.pack_start(&icon_box, false, false, 4);
+
+			let icon = ApplicationResult::get_icon(icon, 32);
+			icon.set_size_request(32, 32);
+			icon_box.pack_start(&icon, false, false, 0);
+
+			let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+			widget_top.pack_start(&description_box, true, true, 0);
+
+			if let Some(subtitle) = subtitle {
+				let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(subtitle), "</span>" ].join("")));
+				category_label.get_style_context().add_class("Category");
+				category_label.get_style_context().add_class(&ApplicationResult::category_class(category));
+				category_label.set_ellipsize(pango::EllipsizeMode::End);
+				category_label.set_use_markup(true);
+				category_label.set_xalign(0.0);
+				description_box.pack_start(&category_label, false, false, 1);
+			}
+
+			let label = gtk::Label::new(Some(name));
+			label.set_ellipsize(pango::EllipsizeMode::End);
+			label.set_xalign(0.0);
+			description_box.pack_start(&label, false, false, 1);
+
+			if layout.inline_actions > 0 {
+				let widget_actions = gtk::Box::new(gtk::Orientation::Vertical, 0);
+				widget.pack_start(&widget_actions, true, true, 0);
+
+				// Visibility is toggled by the query, so don't let `show_all` on the results override it.
+				widget_actions.show_all();
+				widget_actions.set_no_show_all(true);
+				actions_box = Some(widget_actions);
+			}
+		}
+
+		(widget, top_button, actions_box)
+	}
+
+
+	/**
+	 * Adds a button for each of the result's inline actions to its actions box, if it has one.
+	 * The buttons launch through the result, so they need it to exist before they can be created.
+	 */
+
+	pub(super) fn add_inline_actions(&self) {
+		if let (Some(actions_box), Some(actions)) = (self.actions_box.as_ref(), self.actions.as_ref()) {
+			for action in actions.iter().take(self.get_layout().inline_actions) {
+				let widget_action_button = self.build_action_button(action);
+				// The box doesn't show its children once it's built, so each button is shown itself.
+				widget_action_button.show_all();
+				actions_box.pack_start(&widget_action_button, true, true, 0);
+			}
+		}
+	}
+
+
+	/**
+	 * Builds a centered row of small badges, one for each category, styled like category labels.
+	 */
+
+	fn build_category_badges(categories: &[String]) -> gtk::Box {
+		let badges = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		badges.get_style_context().add_class("CategoryBadges");
+		badges.set_halign(gtk::Align::Center);
+
+		for category in categories {
+			let badge = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(category), "</span>" ].join("")));
+			badge.get_style_context().add_class("Category");
+			badge.get_style_context().add_class(&ApplicationResult::category_class(category));
+			badge.set_use_markup(true);
+			badges.pack_start(&badge, false, false, 0);
+		}
+
+		badges
+	}
+
+
+	/**
+	 * Creates the preview widget.
+	 */
+
+	pub(super) fn build_preview_widget(&self) -> gtk::Box {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Application");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		icon_box.get_style_context().add_class("IconBox");
+		icon_box.set_halign(gtk::Align::Center);
+		widget.pack_start(&icon_box, false, false, 0);
+
+		let icon = ApplicationResult::get_icon(self.icon.as_deref(), 96);
+		icon_box.pack_start(&icon, false, false, 0);
+
+		let labels = self.get_preview_labels();
+		if labels.badges {
+			if let Some(generic_name) = self.generic_name.as_ref().filter(|_| labels.generic_name) {
+				let generic_name_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(&generic_name.to_uppercase()), "</span>" ].join("")));
+				generic_name_label.get_style_context().add_class("Category");
+				generic_name_label.set_ellipsize(pango::EllipsizeMode::End);
+				generic_name_label.set_use_markup(true);
+				widget.pack_start(&generic_name_label, false, false, 0);
+			}
+			widget.pack_start(&ApplicationResult::build_category_badges(&self.categories), false, false, 0);
+		}
+		else if labels.category {
+			let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(&self.get_subtitle()), "</span>" ].join("")));
+			category_label.get_style_context().add_class("Category");
+			category_label.get_style_context().add_class(&ApplicationResult::category_class(&self.category));
+			category_label.set_ellipsize(pango::EllipsizeMode::End);
+			category_label.set_use_markup(true);
+			widget.pack_start(&category_label, false, false, 0);
+		}
+
+		let label = gtk::Label::new(Some(&self.name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let description = gtk::Label::new(Some(&[ &self.description, "." ].join("")));
+		description.get_style_context().add_class("Description");
+
+		description.set_line_wrap_mode(pango::WrapMode::WordChar);
+		description.set_ellipsize(pango::EllipsizeMode::End);
+		description.set_justify(gtk::Justification::Center);
+		description.set_halign(gtk::Align::Center);
+		description.set_max_width_chars(36);
+		description.set_line_wrap(true);
+		description.set_lines(5);
+
+		widget.pack_start(&description, false, false, 0);
+
+		if let Some(version) = self.version.as_ref().filter(|_| labels.version) {
+			let version_label = gtk::Label::new(Some(&[ "VERSION ", version ].join("")));
+			version_label.get_style_context().add_class("Version");
+			version_label.set_ellipsize(pango::EllipsizeMode::End);
+			widget.pack_start(&version_label, false, false, 4);
+		}
+
+		let inline_actions = self.get_layout().inline_actions;
+		if let Some(actions) = self.actions.as_ref().filter(|actions| actions.len() > inline_actions) {
+			let widget_actions = gtk::Box::new(gtk::Orientation::Vertical, 0);
+			widget_actions.get_style_context().add_class("PreviewActions");
+			widget.pack_start(&widget_actions, false, false, 8);
+
+			for action in actions.iter().skip(inline_actions) {
+				let widget_action_button = self.build_action_button(action);
+				widget_actions.pack_start(&widget_action_button, false, false, 0);
+			}
+		}
+
+		if self.preferences.borrow().developer_mode {
+			let source = self.read_source().unwrap_or_else(|err| format!("Failed to read '{}': {}", self.source_path.display(), err));
+
+			let source_expander = gtk::Expander::new(Some(&self.source_path.to_string_lossy()));
+			source_expander.get_style_context().add_class("Source");
+			widget.pack_start(&source_expander, true, true, 8);
+
+			let source_scroller = gtk::ScrolledWindow::new::<gtk::Adjustment, gtk::Adjustment>(None, None);
+			source_scroller.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
+			source_scroller.set_size_request(-1, 160);
+			source_expander.add(&source_scroller);
+
+			let source_label = gtk::Label::new(Some(&source));
+			source_label.get_style_context().add_class("SourceText");
+			source_label.set_selectable(true);
+			source_label.set_xalign(0.0);
+			source_label.set_yalign(0.0);
+			source_scroller.add(&source_label);
+		}
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.get_style_context().add_class("linked");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		let launch_button = gtk::Button::from_icon_name(Some("media-playback-start-symbolic"), gtk::IconSize::Button);
+		button_box.pack_start(&launch_button, false, false, 0);
+		if self.is_pinnable() {
+			let favorite_button = gtk::Button::from_icon_name(Some("emblem-favorite-symbolic"), gtk::IconSize::Button);
+			button_box.pack_start(&favorite_button, false, false, 0);
+		}
+		let edit_button = gtk::Button::from_icon_name(Some("document-edit-symbolic"), gtk::IconSize::Button);
+		button_box.pack_start(&edit_button, false, false, 0);
+
+		if let Some(doc_url) = self.get_doc_url().map(str::to_owned) {
+			let help_button = gtk::Button::from_icon_name(Some("help-browser-symbolic"), gtk::IconSize::Button);
+			help_button.set_tooltip_text(Some("Help"));
+			help_button.connect_clicked(move |_| {
+				if let Err(err) = launch::open(&doc_url) { println!("[WARN] Failed to open '{}': {}", &doc_url, err); }
+			});
+			button_box.pack_start(&help_button, false, false, 0);
+		}
+
+		widget
+	}
+}
@@ -0,0 +1,94 @@
+/*!
+ * User defined commands from `$XDG_CONFIG_HOME/scout/snippets.toml`, which are searched like applications.
+ *
+ * ```toml
+ * [[snippet]]
+ * name = "Work Server"
+ * keywords = [ "ssh", "remote" ]
+ * exec = "gnome-terminal -- ssh work"
+ * ```
+ */
+
+use serde::Deserialize;
+
+use crate::error::ScoutError;
+
+/**
+ * A named command, with optional keywords, description, and icon.
+ */
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct Snippet {
+	pub name: String,
+	pub exec: String,
+
+	#[serde(default)]
+	pub keywords: Vec<String>,
+
+	#[serde(default)]
+	pub description: String,
+
+	#[serde(default)]
+	pub icon: Option<String>
+}
+
+#[derive(Debug, Deserialize)]
+struct SnippetsFile {
+	#[serde(default)]
+	snippet: Vec<Snippet>
+}
+
+
+/**
+ * Returns the path of the snippets file.
+ */
+
+pub fn get_path() -> std::path::PathBuf {
+	dirs::config_dir().unwrap_or_else(|| std::path::PathBuf::from([ "/home/", &whoami::username(), "/.config" ].join("")))
+		.join("scout/snippets.toml")
+}
+
+
+/**
+ * Parses the contents of a snippets file. Snippets without a name or command are skipped.
+ */
+
+pub fn parse(contents: &str) -> Result<Vec<Snippet>, ScoutError> {
+	let file: SnippetsFile = toml::from_str(contents).map_err(|err| ScoutError::Parse(err.to_string()))?;
+	Ok(file.snippet.into_iter().filter(|snippet| !snippet.name.trim().is_empty() && !snippet.exec.trim().is_empty()).collect())
+}
+
+
+/**
+ * Reads the snippets from a file. Returns no snippets if the file doesn't exist.
+ */
+
+pub fn load(path: &std::path::Path) -> Result<Vec<Snippet>, ScoutError> {
+	match std::fs::read_to_string(path) {
+		Ok(contents) => parse(&contents),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+		Err(err) => Err(err.into())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snippets_without_a_name_or_command_are_skipped() {
+		let snippets = parse("[[snippet]]\nname = \"Work Server\"\nkeywords = [ \"ssh\" ]\nexec = \"gnome-terminal -- ssh work\"\n\n\
+			[[snippet]]\nname = \" \"\nexec = \"true\"\n\n[[snippet]]\nname = \"Nothing\"\nexec = \"\"\n").unwrap();
+
+		assert_eq!(snippets, vec![ Snippet { name: "Work Server".to_owned(), exec: "gnome-terminal -- ssh work".to_owned(),
+			keywords: vec![ "ssh".to_owned() ], description: String::new(), icon: None } ]);
+		assert_eq!(parse("").unwrap(), vec![]);
+	}
+
+	#[test]
+	fn invalid_and_missing_files_are_handled() {
+		assert!(matches!(parse("[[snippet]]\nname = \"Missing Exec\"\n"), Err(ScoutError::Parse(_))));
+		assert_eq!(load(std::path::Path::new("/scout/missing/snippets.toml")).unwrap(), vec![]);
+	}
+}
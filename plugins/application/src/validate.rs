@@ -0,0 +1,179 @@
+/*!
+ * Linting of desktop files, reporting the problems that would make the application plugin skip or misread them.
+ */
+
+use freedesktop_entry_parser::parse_entry;
+
+use crate::discovery;
+use crate::launch;
+use crate::list;
+
+/** The keys of a `Desktop Entry` section defined by the desktop entry specification. */
+const ENTRY_KEYS: &[&str] = &[ "Type", "Version", "Name", "GenericName", "NoDisplay", "Comment", "Icon", "Hidden",
+	"OnlyShowIn", "NotShowIn", "DBusActivatable", "TryExec", "Exec", "Path", "Terminal", "Actions", "MimeType",
+	"Categories", "Implements", "Keywords", "StartupNotify", "StartupWMClass", "URL", "PrefersNonDefaultGPU", "SingleMainWindow" ];
+
+/** The keys of a `Desktop Action` section defined by the desktop entry specification. */
+const ACTION_KEYS: &[&str] = &[ "Name", "Icon", "Exec" ];
+
+/** The field codes that can follow a `%` in an `Exec` value, including the deprecated ones. */
+const FIELD_CODES: &[char] = &[ 'f', 'F', 'u', 'U', 'i', 'c', 'k', 'd', 'D', 'n', 'N', 'v', 'm', '%' ];
+
+/**
+ * A problem found in a desktop file.
+ */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+
+	/** Used when the file can't be read or parsed. */
+	Unreadable(String),
+
+	/** Used when the file has no `Desktop Entry` section. */
+	MissingEntry,
+
+	/** Used when an application has no `Exec` key, or it is empty. */
+	MissingExec,
+
+	/** Used when an `Exec` value can't be split into arguments, with the section it is in. */
+	InvalidExec(String, String),
+
+	/** Used when an `Exec` value contains an unknown field code, with the section it is in. */
+	InvalidFieldCode(String, char),
+
+	/** Used when a section contains a key that isn't in the specification and doesn't start with `X-`. */
+	UnknownKey(String, String),
+
+	/** Used when an action listed in `Actions` has no `Desktop Action` section. */
+	MissingAction(String),
+
+	/** Used when an action has no `Exec` key, or it is empty. */
+	MissingActionExec(String),
+
+	/** Used when `X-Scout-Nice` isn't a number between -20 and 19. */
+	InvalidNice(String)
+}
+
+impl std::fmt::Display for ValidationIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ValidationIssue::Unreadable(err) => write!(f, "The file can't be read: {}", err),
+			ValidationIssue::MissingEntry => write!(f, "There is no [Desktop Entry] section."),
+			ValidationIssue::MissingExec => write!(f, "The application has no Exec key, so it will be skipped."),
+			ValidationIssue::InvalidExec(section, err) => write!(f, "[{}] Exec can't be split into arguments: {}", section, err),
+			ValidationIssue::InvalidFieldCode(section, code) => write!(f, "[{}] Exec contains the unknown field code '%{}'.", section, code),
+			ValidationIssue::UnknownKey(section, key) => write!(f, "[{}] The key '{}' is unknown, custom keys should start with 'X-'.", section, key),
+			ValidationIssue::MissingAction(action) => write!(f, "The action '{}' has no [Desktop Action {}] section.", action, action),
+			ValidationIssue::MissingActionExec(action) => write!(f, "The action '{}' has no Exec key, so it will be skipped.", action),
+			ValidationIssue::InvalidNice(value) => write!(f, "X-Scout-Nice '{}' must be a number between -20 and 19.", value)
+		}
+	}
+}
+
+/**
+ * Finds the problems in the `Exec` value of a section.
+ */
+
+fn validate_exec(section: &str, exec: &str) -> Vec<ValidationIssue> {
+	let mut issues = vec![];
+
+	let mut chars = exec.chars();
+	while let Some(c) = chars.next() {
+		if c != '%' { continue; }
+		match chars.next() {
+			Some(code) if FIELD_CODES.contains(&code) => (),
+			code => issues.push(ValidationIssue::InvalidFieldCode(section.to_owned(), code.unwrap_or(' ')))
+		}
+	}
+
+	if let Err(err) = launch::get_args(exec) { issues.push(ValidationIssue::InvalidExec(section.to_owned(), err.to_string())); }
+	issues
+}
+
+
+/**
+ * Checks a desktop file with the same parsing applications are discovered with, returning every problem found.
+ * Files without any problems return an empty list.
+ */
+
+pub fn validate_entry(path: &std::path::Path) -> Vec<ValidationIssue> {
+	let parsed = match parse_entry(path) {
+		Ok(parsed) => parsed,
+		Err(err) => return vec![ ValidationIssue::Unreadable(err.to_string()) ]
+	};
+
+	if !parsed.has_section("Desktop Entry") { return vec![ ValidationIssue::MissingEntry ]; }
+	let entry = parsed.section("Desktop Entry");
+	let mut issues = vec![];
+
+	for attr in entry.attrs() {
+		if !ENTRY_KEYS.contains(&attr.name) && !attr.name.starts_with("X-") {
+			issues.push(ValidationIssue::UnknownKey("Desktop Entry".to_owned(), attr.name.to_owned()));
+		}
+	}
+
+	let is_application = entry.attr("Type").is_none_or(|kind| kind == "Application");
+	match discovery::get_exec(&entry) {
+		Some(exec) => issues.extend(validate_exec("Desktop Entry", exec)),
+		None => if is_application { issues.push(ValidationIssue::MissingExec); }
+	}
+
+	if let Some(nice) = entry.attr("X-Scout-Nice") {
+		if launch::parse_nice(Some(nice)).is_none() { issues.push(ValidationIssue::InvalidNice(nice.to_owned())); }
+	}
+
+	for action in list::parse_list(entry.attr("Actions")) {
+		let section_name = [ "Desktop Action", action.as_str() ].join(" ");
+		if !parsed.has_section(&section_name) {
+			issues.push(ValidationIssue::MissingAction(action.to_owned()));
+			continue;
+		}
+
+		let section = discovery::get_action_section(&parsed, &action);
+		for attr in section.attrs() {
+			if !ACTION_KEYS.contains(&attr.name) && !attr.name.starts_with("X-") {
+				issues.push(ValidationIssue::UnknownKey(section_name.clone(), attr.name.to_owned()));
+			}
+		}
+
+		match discovery::get_exec(&section) {
+			Some(exec) => issues.extend(validate_exec(&section_name, exec)),
+			None => issues.push(ValidationIssue::MissingActionExec(action.to_owned()))
+		}
+	}
+
+	issues
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_fixture(name: &str) -> std::path::PathBuf {
+		std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+	}
+
+	#[test]
+	fn valid_entries_have_no_issues() {
+		assert_eq!(validate_entry(&get_fixture("applications/editor.desktop")), vec![]);
+	}
+
+	#[test]
+	fn issues_are_reported_in_order() {
+		assert_eq!(validate_entry(&get_fixture("invalid/broken.desktop")), vec![
+			ValidationIssue::UnknownKey("Desktop Entry".to_owned(), "Colour".to_owned()),
+			ValidationIssue::InvalidFieldCode("Desktop Entry".to_owned(), 'z'),
+			ValidationIssue::InvalidNice("30".to_owned()),
+			ValidationIssue::UnknownKey("Desktop Action new-window".to_owned(), "Shortcut".to_owned()),
+			ValidationIssue::MissingActionExec("new-window".to_owned()),
+			ValidationIssue::MissingAction("missing".to_owned())
+		]);
+	}
+
+	#[test]
+	fn applications_without_exec_are_reported() {
+		assert_eq!(validate_entry(&get_fixture("invalid/no-exec.desktop")), vec![ ValidationIssue::MissingExec ]);
+		assert!(matches!(validate_entry(&get_fixture("invalid/missing.desktop"))[..], [ ValidationIssue::Unreadable(_) ]));
+	}
+}
@@ -0,0 +1,125 @@
+/*!
+ * Discovery and parsing of browser bookmarks.
+ * Only Chromium based browsers are supported, as Firefox stores its bookmarks in an SQLite database.
+ */
+
+use std::io::Read;
+
+/** The config directories of Chromium based browsers, relative to `$XDG_CONFIG_HOME`. */
+const CHROMIUM_DIRS: [&str; 4] = [ "chromium", "google-chrome", "BraveSoftware/Brave-Browser", "vivaldi" ];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+	pub title: String,
+	pub url: String
+}
+
+
+/**
+ * Parses the contents of a Chromium `Bookmarks` file, returning every bookmark in all of its folders.
+ * Returns an empty list if the contents are malformed.
+ */
+
+pub fn parse_chromium(contents: &str) -> Vec<Bookmark> {
+	fn collect(node: &serde_json::Value, bookmarks: &mut Vec<Bookmark>) {
+		match node["type"].as_str() {
+			Some("url") => if let (Some(title), Some(url)) = (node["name"].as_str(), node["url"].as_str()) {
+				bookmarks.push(Bookmark { title: title.to_owned(), url: url.to_owned() });
+			},
+			Some("folder") => if let Some(children) = node["children"].as_array() {
+				children.iter().for_each(|child| collect(child, bookmarks));
+			},
+			_ => ()
+		}
+	}
+
+	let json = match serde_json::from_str::<serde_json::Value>(contents) {
+		Ok(json) => json,
+		Err(_) => return vec![]
+	};
+
+	let mut bookmarks = vec![];
+	if let Some(roots) = json["roots"].as_object() { roots.values().for_each(|root| collect(root, &mut bookmarks)); }
+	bookmarks
+}
+
+
+/**
+ * Finds the `Bookmarks` file of every profile of every installed Chromium based browser.
+ */
+
+pub fn find_chromium_files() -> Vec<std::path::PathBuf> {
+	let config_dir = match dirs::config_dir() {
+		Some(dir) => dir,
+		None => return vec![]
+	};
+
+	CHROMIUM_DIRS.iter()
+		.filter_map(|dir| std::fs::read_dir(config_dir.join(dir)).ok())
+		.flat_map(|profiles| profiles.filter_map(|profile| profile.ok()))
+		.map(|profile| profile.path().join("Bookmarks"))
+		.filter(|path| path.is_file())
+		.collect()
+}
+
+
+/**
+ * Reads the bookmarks of every browser profile, removing duplicate URLs.
+ */
+
+pub fn find_bookmarks() -> Vec<Bookmark> {
+	let mut bookmarks = Vec::<Bookmark>::new();
+
+	for path in find_chromium_files() {
+		let mut contents = String::new();
+		let mut file = scout_core::or_continue!(std::fs::File::open(&path));
+		scout_core::or_continue!(file.read_to_string(&mut contents));
+
+		for bookmark in parse_chromium(&contents) {
+			if !bookmarks.iter().any(|existing| existing.url == bookmark.url) { bookmarks.push(bookmark); }
+		}
+	}
+
+	bookmarks
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const BOOKMARKS: &str = r#"{
+		"checksum": "0123456789abcdef",
+		"roots": {
+			"bookmark_bar": {
+				"type": "folder",
+				"name": "Bookmarks bar",
+				"children": [
+					{ "type": "url", "name": "Rust", "url": "https://www.rust-lang.org/" },
+					{ "type": "folder", "name": "Docs", "children": [
+						{ "type": "url", "name": "GTK", "url": "https://www.gtk.org/" }
+					] }
+				]
+			},
+			"other": { "type": "folder", "name": "Other bookmarks", "children": [] },
+			"synced": { "type": "folder", "name": "Mobile bookmarks", "children": [
+				{ "type": "url", "name": "Untitled" }
+			] }
+		},
+		"version": 1
+	}"#;
+
+	#[test]
+	fn bookmarks_are_read_from_every_folder() {
+		assert_eq!(parse_chromium(BOOKMARKS), vec![
+			Bookmark { title: "Rust".to_owned(), url: "https://www.rust-lang.org/".to_owned() },
+			Bookmark { title: "GTK".to_owned(), url: "https://www.gtk.org/".to_owned() }
+		]);
+	}
+
+	#[test]
+	fn malformed_bookmarks_are_ignored() {
+		assert!(parse_chromium("{ \"roots\": ").is_empty());
+		assert!(parse_chromium("[]").is_empty());
+	}
+}
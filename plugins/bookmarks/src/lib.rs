@@ -0,0 +1,56 @@
+use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
+
+mod bookmarks;
+
+mod result;
+use result::BookmarkResult;
+
+pub struct BookmarksPlugin {
+	bindings: Shared<Box<dyn PluginBindings>>,
+	results: Vec<BookmarkResult>
+}
+
+impl BookmarksPlugin {
+	fn find_results() -> Vec<BookmarkResult> {
+		bookmarks::find_bookmarks().iter().map(|bookmark| BookmarkResult::new(bookmark)).collect()
+	}
+
+	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		gtk::init().unwrap();
+
+		Box::new(BookmarksPlugin {
+			bindings,
+			results: BookmarksPlugin::find_results()
+		})
+	}
+}
+
+impl Plugin for BookmarksPlugin {
+	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+		let query = query.to_lowercase().replace(' ', "");
+		Ok(self.results.iter()
+			.map(|res| {
+				let mut result = res.clone();
+				result.set_score_from_query(&query);
+				Box::new(result) as Box<dyn SearchResult>
+			})
+			.filter(|result| result.get_score() > 0)
+			.collect::<Vec<Box<dyn SearchResult>>>()
+		)
+	}
+
+	fn refresh(&mut self) -> scout_core::Result<()> {
+		self.results = BookmarksPlugin::find_results();
+		Ok(())
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
+	let mut bindings = bindings_shr.borrow_mut();
+	let plugin = BookmarksPlugin::new(bindings_shr.clone());
+	bindings.add_stylesheet(include_str!("../style/.build.css"));
+	bindings.register("bookmarks", plugin);
+}
+
+scout_core::export_plugin!(register);
@@ -0,0 +1,185 @@
+use gtk::prelude::*;
+
+use scout_core::SearchResult;
+
+use crate::bookmarks::Bookmark;
+
+
+/**
+ * A bookmark search result, which opens its URL in the default browser when activated.
+ */
+
+#[derive(Debug, Clone)]
+pub struct BookmarkResult {
+	title: String,
+	url: String,
+
+	widget: gtk::Box,
+	top_button: gtk::Button,
+
+	score: usize
+}
+
+impl BookmarkResult {
+
+	/**
+	 * Returns the browser icon, or a generic web page icon if the icon theme doesn't have one.
+	 */
+
+	pub fn get_icon() -> &'static str {
+		let has_icon = gtk::IconTheme::get_default().is_some_and(|theme| theme.has_icon("web-browser"));
+		if has_icon { "web-browser" } else { "text-html" }
+	}
+
+
+	/**
+	 * Opens a URL with `xdg-open`.
+	 */
+
+	pub fn open(url: &str) {
+		println!("Opening '{}'", url);
+		if let Err(err) = std::process::Command::new("xdg-open").arg(url)
+			.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn() {
+			println!("[WARN] Failed to open '{}': {}", url, err);
+		}
+	}
+
+
+	/**
+	 * Creates a new Bookmark result, with a corresponding result widget.
+	 */
+
+	pub fn new(bookmark: &Bookmark) -> Self {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Bookmark");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let url = bookmark.url.clone();
+		top_button.connect_clicked(move |_| BookmarkResult::open(&url));
+
+		{
+			let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+			top_button.add(&widget_top);
+
+			let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+			icon_box.get_style_context().add_class("IconBox");
+			widget_top.pack_start(&icon_box, false, false, 4);
+
+			let icon = gtk::Image::from_icon_name(Some(BookmarkResult::get_icon()), gtk::IconSize::Dnd);
+			icon.set_size_request(32, 32);
+			icon.set_pixel_size(32);
+			icon_box.pack_start(&icon, false, false, 0);
+
+			let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+			widget_top.pack_start(&description_box, true, true, 0);
+
+			let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>BOOKMARK</span>"));
+			category_label.get_style_context().add_class("Category");
+			category_label.set_ellipsize(pango::EllipsizeMode::End);
+			category_label.set_use_markup(true);
+			category_label.set_xalign(0.0);
+			description_box.pack_start(&category_label, false, false, 1);
+
+			let label = gtk::Label::new(Some(&bookmark.title));
+			label.set_ellipsize(pango::EllipsizeMode::End);
+			label.set_xalign(0.0);
+			description_box.pack_start(&label, false, false, 1);
+		}
+
+		BookmarkResult {
+			title: bookmark.title.clone(),
+			url: bookmark.url.clone(),
+			top_button, widget,
+			score: 0
+		}
+	}
+
+	pub fn set_score_from_query(&mut self, query: &str) {
+		if query.is_empty() { self.score = 0; return; }
+
+		self.score = std::cmp::max(
+			scout_core::get_name_score(query, &self.title),
+			scout_core::get_name_score(query, &self.url) / 2);
+	}
+}
+
+impl SearchResult for BookmarkResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		self.url.clone()
+	}
+
+	fn get_title(&self) -> String {
+		self.title.clone()
+	}
+
+	fn get_subtitle(&self) -> String {
+		self.url.clone()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some(BookmarkResult::get_icon().to_owned())
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		BookmarkResult::open(&self.url);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Bookmark");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = gtk::Image::from_icon_name(Some(BookmarkResult::get_icon()), gtk::IconSize::Dialog);
+		icon.set_pixel_size(96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>BOOKMARK</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.title));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let description = gtk::Label::new(Some(&self.url));
+		description.get_style_context().add_class("Description");
+		description.set_line_wrap_mode(pango::WrapMode::Char);
+		description.set_justify(gtk::Justification::Center);
+		description.set_max_width_chars(36);
+		description.set_line_wrap(true);
+		description.set_lines(3);
+		description.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&description, false, false, 0);
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		let open_button = gtk::Button::from_icon_name(Some("web-browser-symbolic"), gtk::IconSize::Button);
+		let url = self.url.clone();
+		open_button.connect_clicked(move |_| BookmarkResult::open(&url));
+		button_box.pack_start(&open_button, false, false, 0);
+
+		return widget.upcast();
+	}
+}
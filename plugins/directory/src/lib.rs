@@ -2,18 +2,22 @@ use core::{ Plugin, SearchResult, PluginBindings, Shared };
 
 use std::path::PathBuf;
 
+mod preferences;
+use preferences::Preferences;
+
 mod result;
 use result::{ DirectoryResult };
 
 pub struct DirectoryPlugin {
 	bindings: Shared<Box<dyn PluginBindings>>,
+	preferences: Shared<Preferences>,
 	results: Vec<DirectoryResult>
 }
 
 impl DirectoryPlugin {
 	fn add_directory(&mut self, description: Option<&str>, path: Option<PathBuf>) {
 		if let Some(path) = path {
-			let result = DirectoryResult::new(description, &path);
+			let result = DirectoryResult::new(description, &path, self.preferences.borrow().file_display);
 			self.results.push(result);
 			// println!("{:?}", path);
 		}
@@ -24,6 +28,7 @@ impl DirectoryPlugin {
 
 		let mut plugin = Box::new(DirectoryPlugin {
 			bindings,
+			preferences: Preferences::new(None),
 			results: vec![]
 		});
 
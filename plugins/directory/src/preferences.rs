@@ -0,0 +1,72 @@
+use serde::{ Serialize, Deserialize };
+use std::io::{ Read, Write, Seek, SeekFrom };
+
+use core::Shared;
+
+/** How the suggested files of a directory are labelled. */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDisplay {
+
+	/** Only the file's name, below the directory it is in. */
+	Name,
+
+	/** The file's full path, with the home directory abbreviated to `~`. */
+	Path
+}
+
+impl Default for FileDisplay {
+	fn default() -> Self { FileDisplay::Name }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Preferences {
+
+	#[serde(skip_serializing, default)]
+	pub path: std::path::PathBuf,
+
+	/** Whether suggested files show their name or their full path. */
+	#[serde(default)]
+	pub file_display: FileDisplay
+}
+
+impl Preferences {
+	pub fn new(path: Option<&std::path::Path>) -> Shared<Self> {
+		let path = path
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| dirs::config_dir()
+				.map(|dir| dir.join("scout_directory.conf"))
+				.unwrap_or_else(|| std::path::Path::new(&[ "/home/", &whoami::username(), "/.config/scout_directory.conf" ].join(""))
+				.to_owned()));
+
+		let mut contents = String::new();
+		match std::fs::OpenOptions::new().read(true).write(true).create(true).open(&path) {
+			Ok(mut file) => drop(file.read_to_string(&mut contents)),
+			Err(err) => println!("[WARN] Failed to open '{}', using the default preferences: {}", path.display(), err)
+		}
+
+		let prefs = Shared::new(match serde_json::from_str::<Preferences>(&contents) {
+			Ok(json) => json,
+			Err(err) => {
+				println!("Error reading directory plugin config file, resetting to default. {:?}", err);
+				serde_json::from_str("{}").unwrap()
+			}
+		});
+
+		let mut prefs_mut = prefs.borrow_mut();
+		prefs_mut.path = path;
+		if let Err(err) = prefs_mut.save() { println!("[WARN] Failed to save '{}': {}", prefs_mut.path.display(), err); }
+		drop(prefs_mut);
+
+		prefs
+	}
+
+	pub fn save(&self) -> std::io::Result<()> {
+		let mut file = std::fs::OpenOptions::new().read(true).write(true).create(true).open(&self.path)?;
+
+		file.set_len(0)?;
+		file.seek(SeekFrom::Start(0))?;
+		file.write_all(serde_json::to_string(&self)?.as_bytes())?;
+
+		Ok(())
+	}
+}
@@ -2,9 +2,10 @@ use gtk::prelude::*;
 
 use core::SearchResult;
 
+use crate::preferences::FileDisplay;
+
 #[derive(Debug)]
 pub struct FileResult {
-	name: String,
 	icon: String,
 	path: std::path::PathBuf,
 }
@@ -25,20 +26,39 @@ pub struct DirectoryResult {
 impl DirectoryResult {
 
 	/**
-	 *
+	 * Returns the icon name if the icon theme has it, or otherwise the fallback,
+	 * so that missing icons don't show the broken image icon.
 	 */
 
-	pub fn get_file_icon(path: &std::path::Path) -> String {
-		let mime_type = mime_guess::from_path(&path).first()
-			.and_then(|mime| Some(mime.to_string())).get_or_insert(String::new()).clone();
+	pub fn icon_or_fallback(icon: &str, fallback: &str) -> String {
+		let has_icon = gtk::IconTheme::get_default().is_some_and(|theme| theme.has_icon(icon));
+		if has_icon { icon.to_owned() } else { fallback.to_owned() }
+	}
+
 
-		if mime_type.is_empty() { return "folder".to_owned(); }
+	/**
+	 * Finds the themed icon name for a file from its content type, falling back to
+	 * a folder icon for directories and a generic document icon for unknown types.
+	 */
 
-		let content_type = gio::content_type_from_mime_type(&mime_type.to_string()).unwrap().to_string();
-		let icon = gio::content_type_get_icon(&content_type).unwrap().downcast::<gio::ThemedIcon>().unwrap()
-			.get_names().first().and_then(|s| Some(s.to_string()));
+	pub fn icon_name_for_path(path: &std::path::Path) -> String {
+		if path.is_dir() { return "folder".to_owned(); }
 
-		if icon.is_some() && !icon.as_ref().unwrap().is_empty() { icon.unwrap() } else { "text-x-script".to_owned() }
+		DirectoryResult::get_content_type_icon(path)
+			.map_or_else(|| "text-x-generic".to_owned(), |icon| DirectoryResult::icon_or_fallback(&icon, "text-x-generic"))
+	}
+
+
+	/**
+	 * Returns the themed icon name of the content type guessed from a file's name, like `image-png`, if it has one.
+	 */
+
+	pub fn get_content_type_icon(path: &std::path::Path) -> Option<String> {
+		let (content_type, _) = gio::content_type_guess(path.to_str(), &[]);
+		gio::content_type_get_icon(&content_type)
+			.and_then(|icon| icon.downcast::<gio::ThemedIcon>().ok())
+			.and_then(|icon| icon.get_names().first().map(|s| s.to_string()))
+			.filter(|icon| !icon.is_empty())
 	}
 
 
@@ -81,8 +101,7 @@ impl DirectoryResult {
 			.map(|(file, _)| file)
 			.take(5)
 			.map(|file| FileResult {
-				name: file.file_name().to_str().unwrap().to_owned(),
-				icon: DirectoryResult::get_file_icon(file.file_name().as_ref()),
+				icon: DirectoryResult::icon_name_for_path(&file.path()),
 				path: file.path()
 			})
 			.collect::<Vec<_>>();
@@ -91,13 +110,28 @@ impl DirectoryResult {
 	}
 
 
+	/**
+	 * Formats the label of a suggested file, which is its name or its full path with the home directory as `~`.
+	 */
+
+	pub fn get_file_label(path: &std::path::Path, file_display: FileDisplay) -> String {
+		match file_display {
+			FileDisplay::Name => path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+			FileDisplay::Path => match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(|path| path.to_owned())) {
+				Some(relative) => format!("~/{}", relative.display()),
+				None => path.display().to_string()
+			}
+		}
+	}
+
+
 	/**
 	 * Creates a new Directory result, with a corresponding result widget.
 	 */
 
-	pub fn new(description: Option<&str>, path: &std::path::Path) -> Self {
+	pub fn new(description: Option<&str>, path: &std::path::Path, file_display: FileDisplay) -> Self {
 		let description = description.unwrap_or_else(|| path.file_name().unwrap().to_str().unwrap()).to_owned();
-		let icon = DirectoryResult::get_dir_icon(&description).to_owned();
+		let icon = DirectoryResult::icon_or_fallback(DirectoryResult::get_dir_icon(&description), "folder");
 
 		let home_str = dirs::home_dir().and_then(|dir| Some(dir.to_str().unwrap().to_owned())).unwrap();
 		let mut path_str = format!("{}/", path.to_str().unwrap().to_owned());
@@ -169,8 +203,9 @@ impl DirectoryResult {
 						icon.set_pixel_size(16);
 						icon_box.pack_start(&icon, false, false, 0);
 
-						let action_label = gtk::Label::new(Some(&file.name));
-						action_label.set_ellipsize(pango::EllipsizeMode::End);
+						let action_label = gtk::Label::new(Some(&DirectoryResult::get_file_label(&file.path, file_display)));
+						// Paths are ellipsized from the start, so the file name stays visible.
+						action_label.set_ellipsize(if file_display == FileDisplay::Path { pango::EllipsizeMode::Start } else { pango::EllipsizeMode::End });
 						action_label.set_xalign(0.0);
 						widget_action.pack_start(&action_label, false, false, 4);
 					}
@@ -188,21 +223,7 @@ impl DirectoryResult {
 	}
 
 	pub fn set_score_from_query(&mut self, query: &str) {
-		let mut score = 0;
-		let mut last_letter_ind: usize = 0;
-		let mut lowercase_name = self.description.to_lowercase();
-		lowercase_name.retain(|c| !c.is_whitespace());
-
-		for letter in query.chars() {
-			let mut name_chars = lowercase_name.chars().skip(last_letter_ind);
-			let pos = name_chars.position(|c| c == letter).map_or(-1, |c| c as isize);
-			if pos >= 0 {
-				last_letter_ind += pos as usize + 1;
-				score += std::cmp::max(10 - pos, 0) as usize
-			}
-		}
-
-		self.score = score;
+		self.score = core::get_name_score(query, &self.description);
 	}
 }
 
@@ -211,6 +232,22 @@ impl SearchResult for DirectoryResult {
 		self.score
 	}
 
+	fn get_id(&self) -> String {
+		self.path_str.clone()
+	}
+
+	fn get_title(&self) -> String {
+		self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| self.path_str.clone())
+	}
+
+	fn get_subtitle(&self) -> String {
+		self.path_str.clone()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some(self.icon.clone())
+	}
+
 	fn set_first(&self, first: bool) -> () {
 		self.top_button.set_can_focus(!first);
 	}
@@ -277,3 +314,36 @@ impl SearchResult for DirectoryResult {
 		return widget.upcast();
 	}
 }
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn icons_follow_the_content_type() {
+		assert_eq!(DirectoryResult::icon_name_for_path(&std::env::temp_dir()), "folder");
+		assert!(DirectoryResult::get_content_type_icon(std::path::Path::new("/tmp/photo.png")).unwrap().starts_with("image-"));
+
+		let extensionless = DirectoryResult::get_content_type_icon(std::path::Path::new("/tmp/notes"));
+		assert!(extensionless.map_or(true, |icon| !icon.starts_with("image-")));
+	}
+
+	#[test]
+	fn file_labels_are_the_name_or_the_abbreviated_path() {
+		let home = dirs::home_dir().unwrap();
+		let file = home.join("Documents/report.pdf");
+		assert_eq!(DirectoryResult::get_file_label(&file, FileDisplay::Name), "report.pdf");
+		assert_eq!(DirectoryResult::get_file_label(&file, FileDisplay::Path), "~/Documents/report.pdf");
+
+		let outside = std::path::Path::new("/scout-outside-home/report.pdf");
+		assert_eq!(DirectoryResult::get_file_label(outside, FileDisplay::Name), "report.pdf");
+		assert_eq!(DirectoryResult::get_file_label(outside, FileDisplay::Path), "/scout-outside-home/report.pdf");
+	}
+
+	#[test]
+	fn missing_icons_fall_back() {
+		assert_eq!(DirectoryResult::icon_or_fallback("scout-definitely-missing-icon", "text-x-generic"), "text-x-generic");
+		assert_eq!(DirectoryResult::icon_or_fallback("", "folder"), "folder");
+	}
+}
@@ -0,0 +1,169 @@
+/*!
+ * Parses code point queries like `U+1F600` or `codepoint 9731`, and looks up the names of Unicode characters.
+ */
+
+use std::collections::HashMap;
+
+/** The places distributions install the Unicode Character Database's `UnicodeData.txt`. */
+const UNICODE_DATA_PATHS: &[&str] = &[
+	"/usr/share/unicode/UnicodeData.txt",
+	"/usr/share/unicode-data/UnicodeData.txt",
+	"/usr/share/unicode/ucd/UnicodeData.txt"
+];
+
+
+/**
+ * Parses a `U+XXXX` hexadecimal or `codepoint NNNN` decimal query into the character it refers to.
+ * Returns None if the query isn't a code point query, or if the code point isn't a valid character.
+ */
+
+pub fn parse_query(query: &str) -> Option<char> {
+	let query = query.trim();
+	let lowercase = query.to_lowercase();
+
+	let value = if let Some(hex) = lowercase.strip_prefix("u+") {
+		// `from_str_radix` accepts a leading sign, so the digits are checked first.
+		if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) { return None; }
+		u32::from_str_radix(hex, 16).ok()?
+	}
+	else if let Some(decimal) = lowercase.strip_prefix("codepoint") {
+		let decimal = decimal.trim();
+		if decimal.is_empty() || !decimal.chars().all(|c| c.is_ascii_digit()) { return None; }
+		decimal.parse::<u32>().ok()?
+	}
+	else { return None; };
+
+	std::char::from_u32(value)
+}
+
+
+/**
+ * A range of code points that UnicodeData lists by its first and last character, like CJK ideographs.
+ */
+
+#[derive(Debug, Clone)]
+struct NameRange {
+	first: u32,
+	last: u32,
+	prefix: String
+}
+
+
+/**
+ * The names of assigned Unicode characters, read from the Unicode Character Database.
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct UnicodeNames {
+	names: HashMap<u32, String>,
+	ranges: Vec<NameRange>
+}
+
+impl UnicodeNames {
+
+	/**
+	 * Parses the contents of a `UnicodeData.txt` file.
+	 * Surrogates and private use ranges are left out, as they aren't assigned characters.
+	 */
+
+	pub fn parse(contents: &str) -> Self {
+		let mut names = HashMap::new();
+		let mut ranges = Vec::new();
+		let mut range_start: Option<(u32, String)> = None;
+
+		for line in contents.lines() {
+			let mut fields = line.split(';');
+			let code = match fields.next().and_then(|code| u32::from_str_radix(code, 16).ok()) { Some(code) => code, None => continue };
+			let name = fields.next().unwrap_or("");
+			let old_name = fields.nth(8).unwrap_or("");
+
+			if let Some(range) = name.strip_prefix('<').and_then(|name| name.strip_suffix(", First>")) {
+				range_start = Some((code, range.to_owned()));
+			}
+			else if name.ends_with(", Last>") {
+				if let Some((first, range)) = range_start.take() {
+					if range.contains("Surrogate") || range.contains("Private Use") { continue; }
+					let prefix = if range.starts_with("CJK Ideograph") { "CJK UNIFIED IDEOGRAPH".to_owned() } else { range.to_uppercase() };
+					ranges.push(NameRange { first, last: code, prefix });
+				}
+			}
+			else if name == "<control>" {
+				names.insert(code, if old_name.is_empty() { "CONTROL".to_owned() } else { old_name.to_owned() });
+			}
+			else if !name.is_empty() {
+				names.insert(code, name.to_owned());
+			}
+		}
+
+		UnicodeNames { names, ranges }
+	}
+
+
+	/**
+	 * Reads the first `UnicodeData.txt` file that exists, or returns None if none can be read.
+	 */
+
+	pub fn load() -> Option<Self> {
+		UNICODE_DATA_PATHS.iter()
+			.find_map(|path| std::fs::read_to_string(path).ok())
+			.map(|contents| UnicodeNames::parse(&contents))
+	}
+
+
+	/**
+	 * Gets the name of a character, or None if it isn't assigned.
+	 */
+
+	pub fn get_name(&self, character: char) -> Option<String> {
+		let code = character as u32;
+		if let Some(name) = self.names.get(&code) { return Some(name.clone()); }
+
+		self.ranges.iter()
+			.find(|range| code >= range.first && code <= range.last)
+			.map(|range| format!("{}-{:04X}", range.prefix, code))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const UNICODE_DATA: &str = "0000;<control>;Cc;0;BN;;;;;N;NULL;;;;\n\
+		0041;LATIN CAPITAL LETTER A;Lu;0;L;;;;;N;;;;0061;\n\
+		2603;SNOWMAN;So;0;ON;;;;;N;;;;;\n\
+		4E00;<CJK Ideograph, First>;Lo;0;L;;;;;N;;;;;\n\
+		9FFF;<CJK Ideograph, Last>;Lo;0;L;;;;;N;;;;;\n\
+		D800;<Non Private Use High Surrogate, First>;Cs;0;L;;;;;N;;;;;\n\
+		DB7F;<Non Private Use High Surrogate, Last>;Cs;0;L;;;;;N;;;;;\n\
+		1F600;GRINNING FACE;So;0;ON;;;;;N;;;;;\n";
+
+	#[test]
+	fn hexadecimal_and_decimal_queries_are_parsed() {
+		assert_eq!(parse_query("U+1F600"), Some('😀'));
+		assert_eq!(parse_query(" u+2603 "), Some('☃'));
+		assert_eq!(parse_query("codepoint 9731"), Some('☃'));
+		assert_eq!(parse_query("Codepoint65"), Some('A'));
+	}
+
+	#[test]
+	fn other_queries_are_not_code_points() {
+		assert_eq!(parse_query("U+"), None);
+		assert_eq!(parse_query("U+-41"), None);
+		assert_eq!(parse_query("U+XYZ"), None);
+		assert_eq!(parse_query("U+D800"), None);
+		assert_eq!(parse_query("U+110000"), None);
+		assert_eq!(parse_query("codepoint +65"), None);
+		assert_eq!(parse_query("snowman"), None);
+	}
+
+	#[test]
+	fn only_assigned_characters_have_names() {
+		let names = UnicodeNames::parse(UNICODE_DATA);
+		assert_eq!(names.get_name('☃'), Some("SNOWMAN".to_owned()));
+		assert_eq!(names.get_name('\0'), Some("NULL".to_owned()));
+		assert_eq!(names.get_name('一'), Some("CJK UNIFIED IDEOGRAPH-4E00".to_owned()));
+		assert_eq!(names.get_name(parse_query("U+0378").unwrap()), None);
+		assert_eq!(names.get_name(parse_query("U+E000").unwrap()), None);
+	}
+}
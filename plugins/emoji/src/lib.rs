@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+
+use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
+
+mod table;
+use table::EMOJI;
+
+mod result;
+use result::EmojiResult;
+
+mod codepoint;
+use codepoint::UnicodeNames;
+
+pub struct EmojiPlugin {
+	bindings: Shared<Box<dyn PluginBindings>>,
+	results: Vec<EmojiResult>,
+
+	/** The Unicode character names, read the first time a code point is searched for. */
+	names: RefCell<Option<Option<UnicodeNames>>>
+}
+
+impl EmojiPlugin {
+	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		gtk::init().unwrap();
+
+		Box::new(EmojiPlugin {
+			bindings,
+			results: EMOJI.iter().map(|emoji| EmojiResult::new(emoji)).collect(),
+			names: RefCell::new(None)
+		})
+	}
+
+
+	/**
+	 * Gets the name of a character from the Unicode Character Database, or None if it isn't assigned.
+	 * If the database isn't installed, the emoji table is used instead, and other characters return None.
+	 */
+
+	fn get_character_name(&self, character: char) -> Option<String> {
+		let mut names = self.names.borrow_mut();
+		if names.is_none() {
+			let loaded = UnicodeNames::load();
+			if loaded.is_none() { println!("[WARN] Failed to find UnicodeData.txt, only emoji will be named."); }
+			*names = Some(loaded);
+		}
+
+		match names.as_ref().unwrap() {
+			Some(names) => names.get_name(character),
+			None => EMOJI.iter()
+				.find(|emoji| emoji.glyph.trim_end_matches('\u{fe0f}') == character.to_string())
+				.map(|emoji| emoji.name.to_uppercase())
+		}
+	}
+}
+
+impl Plugin for EmojiPlugin {
+	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+		if let Some(character) = codepoint::parse_query(query) {
+			return Ok(self.get_character_name(character)
+				.map(|name| Box::new(EmojiResult::from_code_point(character, name)) as Box<dyn SearchResult>)
+				.into_iter().collect());
+		}
+
+		let query = query.to_lowercase().replace(' ', "");
+		Ok(self.results.iter()
+			.map(|res| {
+				let mut result = res.clone();
+				result.set_score_from_query(&query);
+				Box::new(result) as Box<dyn SearchResult>
+			})
+			.filter(|result| result.get_score() > 0)
+			.collect::<Vec<Box<dyn SearchResult>>>()
+		)
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
+	let mut bindings = bindings_shr.borrow_mut();
+	let plugin = EmojiPlugin::new(bindings_shr.clone());
+	bindings.add_stylesheet(include_str!("../style/.build.css"));
+	bindings.register("emoji", plugin);
+}
+
+scout_core::export_plugin!(register);
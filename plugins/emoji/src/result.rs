@@ -0,0 +1,191 @@
+use gtk::prelude::*;
+
+use scout_core::SearchResult;
+
+use crate::table;
+use crate::table::Emoji;
+
+/** The score of a Unicode character result, above any emoji name match since its query names the character exactly. */
+const CODE_POINT_SCORE: usize = 1000;
+
+
+/**
+ * An emoji or Unicode character search result, which copies its glyph to the clipboard when activated.
+ */
+
+#[derive(Debug, Clone)]
+pub struct EmojiResult {
+	glyph: String,
+	name: String,
+	label: String,
+	category: &'static str,
+	aliases: &'static [&'static str],
+
+	widget: gtk::Box,
+	top_button: gtk::Button,
+
+	score: usize
+}
+
+impl EmojiResult {
+
+	/**
+	 * Copies a glyph to the clipboard.
+	 */
+
+	pub fn copy_to_clipboard(glyph: &str) {
+		let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+		clipboard.set_text(glyph);
+		clipboard.store();
+	}
+
+
+	/**
+	 * Creates a new Emoji result, with a corresponding result widget.
+	 */
+
+	pub fn new(emoji: &Emoji) -> Self {
+		EmojiResult::build(emoji.glyph.to_owned(), emoji.name.to_owned(),
+			[ ":", &emoji.name.replace(' ', "_"), ":" ].join(""), "EMOJI", emoji.aliases)
+	}
+
+
+	/**
+	 * Creates a result for a single Unicode character, labelled with its code point.
+	 */
+
+	pub fn from_code_point(character: char, name: String) -> Self {
+		let mut result = EmojiResult::build(character.to_string(), name,
+			format!("U+{:04X}", character as u32), "UNICODE", &[]);
+		result.score = CODE_POINT_SCORE;
+		result
+	}
+
+
+	/**
+	 * Builds the result widget shared by emoji and Unicode character results.
+	 */
+
+	fn build(glyph: String, name: String, label: String, category: &'static str, aliases: &'static [&'static str]) -> Self {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Emoji");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let glyph_clone = glyph.clone();
+		top_button.connect_clicked(move |_| EmojiResult::copy_to_clipboard(&glyph_clone));
+
+		{
+			let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+			top_button.add(&widget_top);
+
+			let glyph_label = gtk::Label::new(Some(&[ "<span size='xx-large'>", &scout_core::escape_markup(&glyph), "</span>" ].join("")));
+			glyph_label.get_style_context().add_class("Glyph");
+			glyph_label.set_use_markup(true);
+			glyph_label.set_size_request(40, 40);
+			widget_top.pack_start(&glyph_label, false, false, 4);
+
+			let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+			widget_top.pack_start(&description_box, true, true, 0);
+
+			let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", category, "</span>" ].join("")));
+			category_label.get_style_context().add_class("Category");
+			category_label.set_ellipsize(pango::EllipsizeMode::End);
+			category_label.set_use_markup(true);
+			category_label.set_xalign(0.0);
+			description_box.pack_start(&category_label, false, false, 1);
+
+			let label = gtk::Label::new(Some(&label));
+			label.set_ellipsize(pango::EllipsizeMode::End);
+			label.set_xalign(0.0);
+			description_box.pack_start(&label, false, false, 1);
+		}
+
+		EmojiResult {
+			glyph, name, label, category, aliases,
+			top_button, widget,
+			score: 0
+		}
+	}
+
+	pub fn set_score_from_query(&mut self, query: &str) {
+		self.score = table::get_score(query, &self.name, self.aliases);
+	}
+}
+
+impl SearchResult for EmojiResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		self.glyph.clone()
+	}
+
+	fn get_title(&self) -> String {
+		self.name.clone()
+	}
+
+	fn get_subtitle(&self) -> String {
+		self.category.to_owned()
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		EmojiResult::copy_to_clipboard(&self.glyph);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Emoji");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let glyph_label = gtk::Label::new(Some(&[ "<span size='72000'>", &scout_core::escape_markup(&self.glyph), "</span>" ].join("")));
+		glyph_label.get_style_context().add_class("Glyph");
+		glyph_label.set_use_markup(true);
+		widget.pack_start(&glyph_label, false, false, 0);
+
+		let category_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(&self.get_subtitle()), "</span>" ].join("")));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.label));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		if !self.aliases.is_empty() {
+			let description = gtk::Label::new(Some(&[ "Also known as ", &self.aliases.join(", "), "." ].join("")));
+			description.get_style_context().add_class("Description");
+			description.set_line_wrap_mode(pango::WrapMode::WordChar);
+			description.set_justify(gtk::Justification::Center);
+			description.set_max_width_chars(36);
+			description.set_line_wrap(true);
+			widget.pack_start(&description, false, false, 0);
+		}
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		let copy_button = gtk::Button::from_icon_name(Some("edit-copy-symbolic"), gtk::IconSize::Button);
+		let glyph = self.glyph.clone();
+		copy_button.connect_clicked(move |_| EmojiResult::copy_to_clipboard(&glyph));
+		button_box.pack_start(&copy_button, false, false, 0);
+
+		return widget.upcast();
+	}
+}
@@ -0,0 +1,172 @@
+/*!
+ * A table of commonly used emoji, with their names and search aliases.
+ */
+
+/**
+ * Represents an emoji glyph and the names it can be found by.
+ */
+
+pub struct Emoji {
+	pub glyph: &'static str,
+	pub name: &'static str,
+	pub aliases: &'static [&'static str]
+}
+
+/**
+ * Scores a query against a name and its aliases, ignoring surrounding colons and underscores like `:heart_eyes:`.
+ */
+
+pub fn get_score(query: &str, name: &str, aliases: &[&str]) -> usize {
+	let query = query.trim_matches(':').replace('_', "");
+	if query.is_empty() { return 0; }
+
+	std::iter::once(name).chain(aliases.iter().copied())
+		.map(|name| scout_core::get_name_score(&query, name))
+		.max().unwrap_or(0)
+}
+
+
+macro_rules! emoji {
+	($glyph: expr, $name: expr, [ $($alias: expr),* ]) => {
+		Emoji { glyph: $glyph, name: $name, aliases: &[ $($alias),* ] }
+	};
+}
+
+pub const EMOJI: &[Emoji] = &[
+	emoji!("😀", "grinning", [ "grin", "happy" ]),
+	emoji!("😃", "smiley", [ "happy", "joy" ]),
+	emoji!("😄", "smile", [ "happy", "laugh" ]),
+	emoji!("😁", "beaming", [ "grin" ]),
+	emoji!("😆", "laughing", [ "satisfied", "lol" ]),
+	emoji!("😅", "sweat smile", [ "relief", "nervous" ]),
+	emoji!("🤣", "rofl", [ "rolling on the floor laughing", "lmao" ]),
+	emoji!("😂", "joy", [ "tears of joy", "crying laughing" ]),
+	emoji!("🙂", "slight smile", [ "slightly smiling face" ]),
+	emoji!("🙃", "upside down", [ "sarcasm" ]),
+	emoji!("😉", "wink", [ "flirt" ]),
+	emoji!("😊", "blush", [ "proud" ]),
+	emoji!("😇", "innocent", [ "halo", "angel" ]),
+	emoji!("🥰", "smiling face with hearts", [ "love", "adore" ]),
+	emoji!("😍", "heart eyes", [ "love", "crush" ]),
+	emoji!("😘", "kissing heart", [ "kiss" ]),
+	emoji!("😋", "yum", [ "tasty", "delicious" ]),
+	emoji!("😛", "tongue", [ "stuck out tongue" ]),
+	emoji!("😜", "winking tongue", [ "crazy", "prank" ]),
+	emoji!("🤪", "zany", [ "goofy", "wacky" ]),
+	emoji!("🤔", "thinking", [ "hmm", "think" ]),
+	emoji!("🤐", "zipper mouth", [ "silence", "secret" ]),
+	emoji!("😐", "neutral", [ "meh" ]),
+	emoji!("😑", "expressionless", [ "blank" ]),
+	emoji!("😶", "no mouth", [ "speechless" ]),
+	emoji!("😏", "smirk", [ "smug" ]),
+	emoji!("😒", "unamused", [ "meh" ]),
+	emoji!("🙄", "eye roll", [ "rolling eyes" ]),
+	emoji!("😬", "grimace", [ "awkward" ]),
+	emoji!("😌", "relieved", [ "calm" ]),
+	emoji!("😔", "pensive", [ "sad" ]),
+	emoji!("😪", "sleepy", [ "tired" ]),
+	emoji!("😴", "sleeping", [ "zzz" ]),
+	emoji!("😷", "mask", [ "sick", "ill" ]),
+	emoji!("🤒", "thermometer face", [ "sick", "fever" ]),
+	emoji!("🤢", "nauseated", [ "sick", "gross" ]),
+	emoji!("🤮", "vomiting", [ "puke", "sick" ]),
+	emoji!("🥵", "hot", [ "heat", "sweating" ]),
+	emoji!("🥶", "cold", [ "freezing" ]),
+	emoji!("😵", "dizzy face", [ "dizzy" ]),
+	emoji!("🤯", "exploding head", [ "mind blown" ]),
+	emoji!("🥳", "partying", [ "party", "celebrate" ]),
+	emoji!("😎", "sunglasses", [ "cool" ]),
+	emoji!("🤓", "nerd", [ "geek" ]),
+	emoji!("😕", "confused", [ "puzzled" ]),
+	emoji!("😟", "worried", [ "nervous" ]),
+	emoji!("😮", "open mouth", [ "surprise", "wow" ]),
+	emoji!("😲", "astonished", [ "shocked", "amazed" ]),
+	emoji!("😳", "flushed", [ "embarrassed" ]),
+	emoji!("🥺", "pleading", [ "puppy eyes", "please" ]),
+	emoji!("😢", "cry", [ "sad", "tear" ]),
+	emoji!("😭", "sob", [ "sad", "crying" ]),
+	emoji!("😱", "scream", [ "horror", "fear" ]),
+	emoji!("😤", "triumph", [ "huff" ]),
+	emoji!("😡", "rage", [ "angry", "mad" ]),
+	emoji!("😠", "angry", [ "mad", "annoyed" ]),
+	emoji!("🤬", "cursing", [ "swearing" ]),
+	emoji!("😈", "smiling imp", [ "devil", "evil" ]),
+	emoji!("💀", "skull", [ "dead", "death" ]),
+	emoji!("💩", "poop", [ "poo", "hankey" ]),
+	emoji!("🤡", "clown", [ "joker" ]),
+	emoji!("👻", "ghost", [ "boo", "halloween" ]),
+	emoji!("👽", "alien", [ "ufo" ]),
+	emoji!("🤖", "robot", [ "bot" ]),
+	emoji!("🤷", "shrug", [ "dunno", "whatever", "person shrugging" ]),
+	emoji!("🤦", "facepalm", [ "person facepalming", "ugh" ]),
+	emoji!("🙏", "pray", [ "please", "thanks", "folded hands" ]),
+	emoji!("👍", "thumbs up", [ "+1", "like", "yes" ]),
+	emoji!("👎", "thumbs down", [ "-1", "dislike", "no" ]),
+	emoji!("👌", "ok hand", [ "okay", "perfect" ]),
+	emoji!("✌️", "victory", [ "peace" ]),
+	emoji!("🤞", "crossed fingers", [ "luck", "hopeful" ]),
+	emoji!("👋", "wave", [ "hello", "goodbye", "hi" ]),
+	emoji!("👏", "clap", [ "applause", "bravo" ]),
+	emoji!("🙌", "raised hands", [ "hooray", "praise" ]),
+	emoji!("💪", "muscle", [ "strong", "flex" ]),
+	emoji!("👀", "eyes", [ "look", "see" ]),
+	emoji!("🧠", "brain", [ "smart" ]),
+	emoji!("❤️", "heart", [ "love", "red heart" ]),
+	emoji!("💔", "broken heart", [ "heartbreak" ]),
+	emoji!("💯", "hundred", [ "100", "perfect", "score" ]),
+	emoji!("💥", "boom", [ "collision", "explode" ]),
+	emoji!("✨", "sparkles", [ "shiny", "magic" ]),
+	emoji!("🔥", "fire", [ "flame", "lit", "hot" ]),
+	emoji!("⭐", "star", [ "favorite" ]),
+	emoji!("🌈", "rainbow", [ "pride" ]),
+	emoji!("☀️", "sun", [ "sunny", "weather" ]),
+	emoji!("🌙", "moon", [ "night", "crescent" ]),
+	emoji!("⚡", "zap", [ "lightning", "thunder", "electric" ]),
+	emoji!("❄️", "snowflake", [ "winter", "snow" ]),
+	emoji!("☕", "coffee", [ "cafe", "espresso" ]),
+	emoji!("🍺", "beer", [ "drink" ]),
+	emoji!("🍕", "pizza", [ "food" ]),
+	emoji!("🎂", "birthday", [ "cake" ]),
+	emoji!("🎉", "tada", [ "party", "celebration", "hooray" ]),
+	emoji!("🎁", "gift", [ "present" ]),
+	emoji!("🏆", "trophy", [ "award", "win" ]),
+	emoji!("🚀", "rocket", [ "launch", "ship" ]),
+	emoji!("💻", "computer", [ "laptop" ]),
+	emoji!("📱", "phone", [ "mobile", "smartphone" ]),
+	emoji!("📧", "email", [ "mail", "e-mail" ]),
+	emoji!("📌", "pushpin", [ "pin" ]),
+	emoji!("🔒", "lock", [ "locked", "security" ]),
+	emoji!("🔑", "key", [ "password" ]),
+	emoji!("🐛", "bug", [ "insect" ]),
+	emoji!("🐶", "dog", [ "puppy" ]),
+	emoji!("🐱", "cat", [ "kitten" ]),
+	emoji!("🦊", "fox", [ "firefox" ]),
+	emoji!("✅", "check", [ "done", "yes", "white check mark" ]),
+	emoji!("❌", "cross", [ "x", "no", "wrong" ]),
+	emoji!("⚠️", "warning", [ "caution", "alert" ]),
+	emoji!("❓", "question", [ "what", "confused" ]),
+	emoji!("❗", "exclamation", [ "bang", "important" ]),
+];
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn find_best(query: &str) -> &'static Emoji {
+		EMOJI.iter().max_by_key(|emoji| get_score(query, emoji.name, emoji.aliases)).unwrap()
+	}
+
+	#[test]
+	fn names_and_aliases_find_the_glyph() {
+		assert_eq!(find_best("grinning").glyph, "😀");
+		assert_eq!(find_best(":heart_eyes:").glyph, "😍");
+		assert_eq!(find_best("lmao").glyph, "🤣");
+	}
+
+	#[test]
+	fn unmatched_queries_score_nothing() {
+		assert!(EMOJI.iter().all(|emoji| get_score("::", emoji.name, emoji.aliases) == 0));
+		assert!(EMOJI.iter().all(|emoji| get_score("qqqqqq", emoji.name, emoji.aliases) == 0));
+	}
+}
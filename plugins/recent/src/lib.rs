@@ -0,0 +1,58 @@
+use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
+
+mod recent;
+
+mod result;
+use result::RecentDocumentResult;
+
+pub struct RecentPlugin {
+	bindings: Shared<Box<dyn PluginBindings>>,
+	results: Vec<RecentDocumentResult>
+}
+
+impl RecentPlugin {
+	fn find_results() -> Vec<RecentDocumentResult> {
+		recent::find_recent_documents().iter().enumerate()
+			.map(|(recency, document)| RecentDocumentResult::new(document, recency)).collect()
+	}
+
+	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		gtk::init().unwrap();
+
+		Box::new(RecentPlugin {
+			bindings,
+			results: RecentPlugin::find_results()
+		})
+	}
+}
+
+impl Plugin for RecentPlugin {
+	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+		let query = query.to_lowercase().replace(' ', "");
+		let count = self.results.len();
+		Ok(self.results.iter()
+			.map(|res| {
+				let mut result = res.clone();
+				result.set_score_from_query(&query, count);
+				Box::new(result) as Box<dyn SearchResult>
+			})
+			.filter(|result| result.get_score() > 0)
+			.collect::<Vec<Box<dyn SearchResult>>>()
+		)
+	}
+
+	fn refresh(&mut self) -> scout_core::Result<()> {
+		self.results = RecentPlugin::find_results();
+		Ok(())
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
+	let mut bindings = bindings_shr.borrow_mut();
+	let plugin = RecentPlugin::new(bindings_shr.clone());
+	bindings.add_stylesheet(include_str!("../style/.build.css"));
+	bindings.register("recent", plugin);
+}
+
+scout_core::export_plugin!(register);
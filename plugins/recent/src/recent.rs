@@ -0,0 +1,83 @@
+/*!
+ * Reading of the recently used files tracked by GTK in `recently-used.xbel`.
+ */
+
+use gtk::prelude::*;
+
+/**
+ * A recently used file, with the name it is displayed with and the time it was last modified in the list.
+ */
+
+#[derive(Debug, Clone)]
+pub struct RecentDocument {
+	pub name: String,
+	pub uri: String,
+	pub mime_type: String,
+	pub modified: i64
+}
+
+/**
+ * Reads the recently used files from the default recent manager, most recent first.
+ * Local files that no longer exist are skipped.
+ */
+
+pub fn find_recent_documents() -> Vec<RecentDocument> {
+	let manager = match gtk::RecentManager::get_default() {
+		Some(manager) => manager,
+		None => return vec![]
+	};
+
+	let mut documents: Vec<_> = manager.get_items().iter()
+		.filter(|info| !info.is_local() || info.exists())
+		.filter_map(|info| Some(RecentDocument {
+			name: info.get_display_name()?.to_string(),
+			uri: info.get_uri()?.to_string(),
+			mime_type: info.get_mime_type().map(|mime| mime.to_string()).unwrap_or_default(),
+			modified: info.get_modified() as i64
+		}))
+		.collect();
+
+	documents.sort_by(|a, b| b.modified.cmp(&a.modified));
+	documents
+}
+
+
+/**
+ * Scores a query against the name of the document at position `recency` of the `count` recent documents.
+ * Empty queries score documents by recency instead, from `count` for the most recent down to 1,
+ * so that they are all listed, most recent first.
+ */
+
+pub fn get_score(query: &str, name: &str, recency: usize, count: usize) -> usize {
+	if query.is_empty() { count - recency }
+	else { scout_core::get_name_score(query, name) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DOCUMENTS: [&str; 4] = [ "budget.ods", "holiday photo.png", "report draft.odt", "Final Report.pdf" ];
+
+	fn rank(query: &str) -> Vec<&'static str> {
+		let mut ranked: Vec<_> = DOCUMENTS.iter().enumerate()
+			.map(|(recency, name)| (*name, get_score(query, name, recency, DOCUMENTS.len())))
+			.filter(|(_, score)| *score > 0)
+			.collect();
+		ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+		ranked.into_iter().map(|(name, _)| name).collect()
+	}
+
+	#[test]
+	fn empty_queries_list_every_document_by_recency() {
+		assert_eq!(rank(""), DOCUMENTS.to_vec());
+	}
+
+	#[test]
+	fn queries_rank_documents_by_name() {
+		assert_eq!(rank("report"), vec![ "report draft.odt", "Final Report.pdf" ]);
+		assert_eq!(rank("photo"), vec![ "holiday photo.png" ]);
+		assert!(rank("spreadsheet").is_empty());
+	}
+}
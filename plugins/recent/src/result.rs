@@ -0,0 +1,198 @@
+use gtk::prelude::*;
+
+use scout_core::SearchResult;
+
+use crate::recent::{ self, RecentDocument };
+
+
+/**
+ * A recently used file, which opens in its default application when activated.
+ */
+
+#[derive(Debug, Clone)]
+pub struct RecentDocumentResult {
+	name: String,
+	uri: String,
+	icon: String,
+
+	/** The position of the document in the recent list, with zero being the most recent. */
+	recency: usize,
+
+	widget: gtk::Box,
+	top_button: gtk::Button,
+
+	score: usize
+}
+
+impl RecentDocumentResult {
+
+	/**
+	 * Finds the themed icon name for a MIME type, falling back to a generic document icon.
+	 */
+
+	pub fn icon_name_for_mime(mime_type: &str) -> String {
+		let icon = gio::content_type_from_mime_type(mime_type)
+			.and_then(|content_type| gio::content_type_get_icon(&content_type))
+			.and_then(|icon| icon.downcast::<gio::ThemedIcon>().ok())
+			.and_then(|icon| icon.get_names().first().map(|s| s.to_string()))
+			.filter(|icon| gtk::IconTheme::get_default().is_some_and(|theme| theme.has_icon(icon)));
+
+		icon.unwrap_or_else(|| "text-x-generic".to_owned())
+	}
+
+
+	/**
+	 * Opens a URI with `xdg-open`.
+	 */
+
+	pub fn open(uri: &str) {
+		println!("Opening '{}'", uri);
+		if let Err(err) = std::process::Command::new("xdg-open").arg(uri)
+			.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn() {
+			println!("[WARN] Failed to open '{}': {}", uri, err);
+		}
+	}
+
+
+	/**
+	 * Creates a new recent document result, with a corresponding result widget.
+	 */
+
+	pub fn new(document: &RecentDocument, recency: usize) -> Self {
+		let icon = RecentDocumentResult::icon_name_for_mime(&document.mime_type);
+
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("RecentDocument");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let uri = document.uri.clone();
+		top_button.connect_clicked(move |_| RecentDocumentResult::open(&uri));
+
+		{
+			let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+			top_button.add(&widget_top);
+
+			let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+			icon_box.get_style_context().add_class("IconBox");
+			widget_top.pack_start(&icon_box, false, false, 4);
+
+			let icon = gtk::Image::from_icon_name(Some(&icon), gtk::IconSize::Dnd);
+			icon.set_size_request(32, 32);
+			icon.set_pixel_size(32);
+			icon_box.pack_start(&icon, false, false, 0);
+
+			let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+			widget_top.pack_start(&description_box, true, true, 0);
+
+			let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>RECENT</span>"));
+			category_label.get_style_context().add_class("Category");
+			category_label.set_ellipsize(pango::EllipsizeMode::End);
+			category_label.set_use_markup(true);
+			category_label.set_xalign(0.0);
+			description_box.pack_start(&category_label, false, false, 1);
+
+			let label = gtk::Label::new(Some(&document.name));
+			label.set_ellipsize(pango::EllipsizeMode::End);
+			label.set_xalign(0.0);
+			description_box.pack_start(&label, false, false, 1);
+		}
+
+		RecentDocumentResult {
+			name: document.name.clone(),
+			uri: document.uri.clone(),
+			icon, recency,
+			top_button, widget,
+			score: 0
+		}
+	}
+
+
+	/**
+	 * Scores the query against the document's name, or by its recency for empty queries, with `recent::get_score`.
+	 */
+
+	pub fn set_score_from_query(&mut self, query: &str, count: usize) {
+		self.score = recent::get_score(query, &self.name, self.recency, count);
+	}
+}
+
+impl SearchResult for RecentDocumentResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		self.uri.clone()
+	}
+
+	fn get_title(&self) -> String {
+		self.name.clone()
+	}
+
+	fn get_subtitle(&self) -> String {
+		self.uri.clone()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some(self.icon.clone())
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		RecentDocumentResult::open(&self.uri);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("RecentDocument");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = gtk::Image::from_icon_name(Some(&self.icon), gtk::IconSize::Dialog);
+		icon.set_pixel_size(96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>RECENT</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let description = gtk::Label::new(Some(&self.uri));
+		description.get_style_context().add_class("Description");
+		description.set_line_wrap_mode(pango::WrapMode::Char);
+		description.set_justify(gtk::Justification::Center);
+		description.set_max_width_chars(36);
+		description.set_line_wrap(true);
+		description.set_lines(3);
+		description.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&description, false, false, 0);
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		let open_button = gtk::Button::from_icon_name(Some("document-open-symbolic"), gtk::IconSize::Button);
+		let uri = self.uri.clone();
+		open_button.connect_clicked(move |_| RecentDocumentResult::open(&uri));
+		button_box.pack_start(&open_button, false, false, 0);
+
+		return widget.upcast();
+	}
+}
@@ -0,0 +1,56 @@
+use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
+
+mod systemd;
+
+mod result;
+use result::ServiceResult;
+
+pub struct ServicesPlugin {
+	bindings: Shared<Box<dyn PluginBindings>>,
+	results: Vec<ServiceResult>
+}
+
+impl ServicesPlugin {
+	fn find_results() -> Vec<ServiceResult> {
+		systemd::find_units().into_iter().map(ServiceResult::new).collect()
+	}
+
+	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		gtk::init().unwrap();
+
+		Box::new(ServicesPlugin {
+			bindings,
+			results: ServicesPlugin::find_results()
+		})
+	}
+}
+
+impl Plugin for ServicesPlugin {
+	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+		let query = query.to_lowercase().replace(' ', "");
+		Ok(self.results.iter()
+			.map(|res| {
+				let mut result = res.clone();
+				result.set_score_from_query(&query);
+				Box::new(result) as Box<dyn SearchResult>
+			})
+			.filter(|result| result.get_score() > 0)
+			.collect::<Vec<Box<dyn SearchResult>>>()
+		)
+	}
+
+	fn refresh(&mut self) -> scout_core::Result<()> {
+		self.results = ServicesPlugin::find_results();
+		Ok(())
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
+	let mut bindings = bindings_shr.borrow_mut();
+	let plugin = ServicesPlugin::new(bindings_shr.clone());
+	bindings.add_stylesheet(include_str!("../style/.build.css"));
+	bindings.register("services", plugin);
+}
+
+scout_core::export_plugin!(register);
@@ -0,0 +1,221 @@
+use gtk::prelude::*;
+
+use scout_core::{ SearchResult, Shared };
+
+use crate::systemd;
+use crate::systemd::Unit;
+
+
+/**
+ * A systemd user service, which is started when activated and stopped when activated secondarily.
+ * The subtitle shows the unit's active state, and is updated after starting or stopping it.
+ */
+
+#[derive(Debug, Clone)]
+pub struct ServiceResult {
+	unit: Unit,
+
+	/** The unit's active state, shared by the plugin's result and its copies for each query so that it stays up to date. */
+	active: Shared<String>,
+
+	widget: gtk::Box,
+	top_button: gtk::Button,
+	state_label: gtk::Label,
+
+	score: usize
+}
+
+impl ServiceResult {
+
+	/**
+	 * Returns the name of a unit without its `.service` suffix, which is what queries are matched against.
+	 */
+
+	pub fn get_display_name(name: &str) -> &str {
+		name.strip_suffix(".service").unwrap_or(name)
+	}
+
+
+	/**
+	 * Formats the subtitle of a unit from its active and enablement states, like `ACTIVE · ENABLED`.
+	 */
+
+	pub fn get_state_text(active: &str, state: &str) -> String {
+		[ active.to_uppercase(), state.to_uppercase() ].join(" · ")
+	}
+
+
+	/**
+	 * Starts or stops the unit without waiting for it, then shows the state it is changing to.
+	 * If the job can't be queued, the unit's actual state is shown instead.
+	 */
+
+	fn set_running(&self, running: bool) {
+		let active = match systemd::set_running(&self.unit.name, running) {
+			Ok(()) => (if running { "active" } else { "inactive" }).to_owned(),
+			Err(err) => {
+				println!("[WARN] Failed to {} '{}': {}", if running { "start" } else { "stop" }, self.unit.name, err);
+				systemd::get_active_state(&self.unit.name)
+			}
+		};
+
+		self.state_label.set_markup(&[ "<span size='small' weight='bold'>", &scout_core::escape_markup(&ServiceResult::get_state_text(&active, &self.unit.state)), "</span>" ].join(""));
+		*self.active.borrow_mut() = active;
+	}
+
+
+	/**
+	 * Creates a new service result, with a corresponding result widget.
+	 */
+
+	pub fn new(unit: Unit) -> Self {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Service");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		top_button.add(&widget_top);
+
+		let icon_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		icon_box.get_style_context().add_class("IconBox");
+		widget_top.pack_start(&icon_box, false, false, 4);
+
+		let icon = gtk::Image::from_icon_name(Some("system-run"), gtk::IconSize::Dnd);
+		icon.set_size_request(32, 32);
+		icon.set_pixel_size(32);
+		icon_box.pack_start(&icon, false, false, 0);
+
+		let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget_top.pack_start(&description_box, true, true, 0);
+
+		let state_label = gtk::Label::new(Some(&[ "<span size='small' weight='bold'>",
+			&scout_core::escape_markup(&ServiceResult::get_state_text(&unit.active, &unit.state)), "</span>" ].join("")));
+		state_label.get_style_context().add_class("Category");
+		state_label.set_ellipsize(pango::EllipsizeMode::End);
+		state_label.set_use_markup(true);
+		state_label.set_xalign(0.0);
+		description_box.pack_start(&state_label, false, false, 1);
+
+		let label = gtk::Label::new(Some(ServiceResult::get_display_name(&unit.name)));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		label.set_xalign(0.0);
+		description_box.pack_start(&label, false, false, 1);
+
+		if !unit.description.is_empty() { top_button.set_tooltip_text(Some(&unit.description)); }
+
+		let result = ServiceResult {
+			active: Shared::new(unit.active.clone()), unit,
+			widget, top_button, state_label,
+			score: 0
+		};
+
+		let result_clone = result.clone();
+		result.top_button.connect_clicked(move |_| result_clone.set_running(true));
+
+		result
+	}
+
+
+	/**
+	 * Scores the query against the unit's name. Empty queries don't list any services.
+	 */
+
+	pub fn set_score_from_query(&mut self, query: &str) {
+		self.score = if query.is_empty() { 0 }
+			else { scout_core::get_name_score(query, ServiceResult::get_display_name(&self.unit.name)) };
+	}
+}
+
+impl SearchResult for ServiceResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		self.unit.name.clone()
+	}
+
+	fn get_title(&self) -> String {
+		ServiceResult::get_display_name(&self.unit.name).to_owned()
+	}
+
+	fn get_subtitle(&self) -> String {
+		ServiceResult::get_state_text(&self.active.borrow(), &self.unit.state)
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some("system-run".to_owned())
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		self.set_running(true);
+	}
+
+	fn activate_secondary(&self) {
+		self.set_running(false);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Service");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = gtk::Image::from_icon_name(Some("system-run"), gtk::IconSize::Dialog);
+		icon.set_pixel_size(96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>USER SERVICE</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.unit.name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		if !self.unit.description.is_empty() {
+			let description = gtk::Label::new(Some(&self.unit.description));
+			description.get_style_context().add_class("Description");
+			description.set_line_wrap_mode(pango::WrapMode::WordChar);
+			description.set_justify(gtk::Justification::Center);
+			description.set_max_width_chars(36);
+			description.set_line_wrap(true);
+			description.set_lines(3);
+			description.set_ellipsize(pango::EllipsizeMode::End);
+			widget.pack_start(&description, false, false, 0);
+		}
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		let start_button = gtk::Button::from_icon_name(Some("media-playback-start-symbolic"), gtk::IconSize::Button);
+		start_button.set_tooltip_text(Some("Start"));
+		let result = self.clone();
+		start_button.connect_clicked(move |_| result.set_running(true));
+		button_box.pack_start(&start_button, false, false, 0);
+
+		let stop_button = gtk::Button::from_icon_name(Some("media-playback-stop-symbolic"), gtk::IconSize::Button);
+		stop_button.set_tooltip_text(Some("Stop"));
+		let result = self.clone();
+		stop_button.connect_clicked(move |_| result.set_running(false));
+		button_box.pack_start(&stop_button, false, false, 0);
+
+		return widget.upcast();
+	}
+}
@@ -0,0 +1,160 @@
+/*!
+ * Listing and controlling systemd user services through `systemctl --user`.
+ */
+
+/**
+ * A user service unit, with its enablement state from `list-unit-files` and its active state from `list-units`.
+ */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unit {
+	pub name: String,
+	pub state: String,
+	pub active: String,
+	pub description: String
+}
+
+/**
+ * Parses the output of `systemctl --user list-unit-files --no-legend`, one unit and enablement state per line.
+ * Template units are skipped, since they can't be started without an instance name.
+ */
+
+pub fn parse_unit_files(output: &str) -> Vec<(String, String)> {
+	output.lines().filter_map(|line| {
+		let mut columns = line.split_whitespace();
+		let name = columns.next()?;
+		let state = columns.next()?;
+		if name.contains("@.") { return None; }
+		Some((name.to_owned(), state.to_owned()))
+	}).collect()
+}
+
+
+/**
+ * Parses the output of `systemctl --user list-units --all --no-legend --plain` into its units'
+ * active states and descriptions. The description is the rest of the line after the first four columns.
+ */
+
+pub fn parse_units(output: &str) -> std::collections::HashMap<String, (String, String)> {
+	output.lines().filter_map(|line| {
+		// Failed units are marked with a bullet unless `--plain` is used.
+		let mut rest = line.trim_start().trim_start_matches('●');
+		let mut columns = vec![];
+		for _ in 0..4 {
+			rest = rest.trim_start();
+			let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+			if end == 0 { return None; }
+			columns.push(&rest[..end]);
+			rest = &rest[end..];
+		}
+
+		Some((columns[0].to_owned(), (columns[2].to_owned(), rest.trim().to_owned())))
+	}).collect()
+}
+
+
+/**
+ * Combines the unit files and loaded units into a list of units, sorted by name.
+ * Units that aren't loaded are inactive and have no description.
+ */
+
+pub fn merge_units(unit_files: Vec<(String, String)>, units: &std::collections::HashMap<String, (String, String)>) -> Vec<Unit> {
+	let mut merged: Vec<_> = unit_files.into_iter().map(|(name, state)| {
+		let (active, description) = units.get(&name).cloned().unwrap_or_else(|| ("inactive".to_owned(), String::new()));
+		Unit { name, state, active, description }
+	}).collect();
+
+	merged.sort_by(|a, b| a.name.cmp(&b.name));
+	merged
+}
+
+
+/**
+ * Runs `systemctl --user` with the arguments specified, returning its output.
+ */
+
+fn systemctl(args: &[&str]) -> std::io::Result<String> {
+	let output = std::process::Command::new("systemctl").arg("--user").args(args)
+		.stderr(std::process::Stdio::null()).output()?;
+	Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+
+/**
+ * Lists the user's service units. Returns an empty list if `systemctl` isn't available.
+ */
+
+pub fn find_units() -> Vec<Unit> {
+	let list = || -> std::io::Result<Vec<Unit>> {
+		let unit_files = parse_unit_files(&systemctl(&[ "list-unit-files", "--type=service", "--no-legend", "--no-pager" ])?);
+		let units = parse_units(&systemctl(&[ "list-units", "--type=service", "--all", "--no-legend", "--no-pager", "--plain" ])?);
+		Ok(merge_units(unit_files, &units))
+	};
+
+	list().unwrap_or_else(|err| {
+		println!("[WARN] Failed to list user services: {}", err);
+		vec![]
+	})
+}
+
+
+/**
+ * Gets the current active state of a unit, such as `active` or `failed`.
+ */
+
+pub fn get_active_state(name: &str) -> String {
+	systemctl(&[ "is-active", name ]).map(|output| output.trim().to_owned())
+		.ok().filter(|state| !state.is_empty()).unwrap_or_else(|| "unknown".to_owned())
+}
+
+
+/**
+ * Queues a job to start or stop a unit, without waiting for the unit to finish starting or stopping.
+ */
+
+pub fn set_running(name: &str, running: bool) -> std::io::Result<()> {
+	let status = std::process::Command::new("systemctl").args(&[ "--user", "--no-block" ]).arg(if running { "start" } else { "stop" }).arg(name)
+		.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status()?;
+	if status.success() { Ok(()) }
+	else { Err(std::io::Error::new(std::io::ErrorKind::Other, format!("systemctl exited with {}", status))) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const UNIT_FILES: &str = "pipewire.service                  enabled  enabled\n\
+		syncthing.service                 disabled enabled\n\
+		gpg-agent@.service                static   -\n\
+		\n";
+
+	const UNITS: &str = "pipewire.service  loaded active   running PipeWire Multimedia Service\n\
+		● mpd.service     loaded failed   failed  Music Player Daemon\n\
+		broken\n";
+
+	#[test]
+	fn unit_files_are_parsed_without_templates() {
+		assert_eq!(parse_unit_files(UNIT_FILES), vec![
+			("pipewire.service".to_owned(), "enabled".to_owned()),
+			("syncthing.service".to_owned(), "disabled".to_owned())
+		]);
+	}
+
+	#[test]
+	fn units_are_parsed_with_their_states_and_descriptions() {
+		let units = parse_units(UNITS);
+		assert_eq!(units.len(), 2);
+		assert_eq!(units["pipewire.service"], ("active".to_owned(), "PipeWire Multimedia Service".to_owned()));
+		assert_eq!(units["mpd.service"], ("failed".to_owned(), "Music Player Daemon".to_owned()));
+	}
+
+	#[test]
+	fn unloaded_units_are_inactive() {
+		assert_eq!(merge_units(parse_unit_files(UNIT_FILES), &parse_units(UNITS)), vec![
+			Unit { name: "pipewire.service".to_owned(), state: "enabled".to_owned(), active: "active".to_owned(),
+				description: "PipeWire Multimedia Service".to_owned() },
+			Unit { name: "syncthing.service".to_owned(), state: "disabled".to_owned(), active: "inactive".to_owned(), description: String::new() }
+		]);
+	}
+}
@@ -0,0 +1,76 @@
+use scout_core::{ Plugin, SearchResult, PluginBindings, Shared };
+
+mod toggle;
+use toggle::ToggleCache;
+
+mod preferences;
+use preferences::Preferences;
+
+mod result;
+use result::ToggleResult;
+
+pub struct TogglesPlugin {
+	bindings: Shared<Box<dyn PluginBindings>>,
+	preferences: Shared<Preferences>,
+
+	/** The toggles that have a backend that works on this system, with the first such backend and its last known state. */
+	available: ToggleCache
+}
+
+impl TogglesPlugin {
+
+	/**
+	 * Finds the available toggles and their states on another thread, since it runs a command for each backend,
+	 * and replaces the cached toggles when it's done. Until then, the previous toggles are shown.
+	 */
+
+	fn refresh_available(&self) {
+		let toggles = self.preferences.borrow().get_toggles();
+		let cache = self.available.clone();
+		std::thread::spawn(move || {
+			let available = toggle::find_available(&toggles);
+			if let Ok(mut cached) = cache.lock() { *cached = available; }
+		});
+	}
+
+	fn new(bindings: Shared<Box<dyn PluginBindings>>) -> Box<dyn Plugin> {
+		gtk::init().unwrap();
+
+		let plugin = TogglesPlugin {
+			bindings,
+			preferences: Preferences::new(None),
+			available: ToggleCache::default()
+		};
+		plugin.refresh_available();
+
+		Box::new(plugin)
+	}
+}
+
+impl Plugin for TogglesPlugin {
+	fn get_results(&self, query: &str) -> scout_core::Result<Vec<Box<dyn SearchResult>>> {
+		let (setting, requested) = toggle::parse_query(query);
+		let available = self.available.lock().map(|available| available.clone()).unwrap_or_default();
+		Ok(available.into_iter()
+			.filter(|available| toggle::matches(&setting, &available.toggle))
+			.map(|available| Box::new(ToggleResult::new(available, self.available.clone(), &setting, requested)) as Box<dyn SearchResult>)
+			.collect::<Vec<Box<dyn SearchResult>>>()
+		)
+	}
+
+	fn refresh(&mut self) -> scout_core::Result<()> {
+		self.preferences = Preferences::new(None);
+		self.refresh_available();
+		Ok(())
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn register(bindings_shr: Shared<Box<dyn PluginBindings>>) {
+	let mut bindings = bindings_shr.borrow_mut();
+	let plugin = TogglesPlugin::new(bindings_shr.clone());
+	bindings.add_stylesheet(include_str!("../style/.build.css"));
+	bindings.register("toggles", plugin);
+}
+
+scout_core::export_plugin!(register);
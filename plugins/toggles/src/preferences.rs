@@ -0,0 +1,77 @@
+use serde::{ Serialize, Deserialize };
+use std::io::{ Read, Write, Seek, SeekFrom };
+
+use scout_core::Shared;
+
+use crate::toggle::{ self, Toggle };
+
+fn default_builtin_toggles() -> bool { true }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Preferences {
+
+	#[serde(skip_serializing, default)]
+	pub path: std::path::PathBuf,
+
+	/** Whether the toggles built into the plugin, like Wi-Fi and Dark Mode, are offered. */
+	#[serde(default = "default_builtin_toggles")]
+	pub builtin_toggles: bool,
+
+	/** More toggles, which replace a built in toggle with the same name. */
+	#[serde(default)]
+	pub toggles: Vec<Toggle>
+}
+
+impl Preferences {
+	pub fn new(path: Option<&std::path::Path>) -> Shared<Self> {
+		let path = path
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| dirs::config_dir()
+				.map(|dir| dir.join("scout_toggles.conf"))
+				.unwrap_or_else(|| std::path::Path::new(&[ "/home/", &whoami::username(), "/.config/scout_toggles.conf" ].join(""))
+				.to_owned()));
+
+		let mut contents = String::new();
+		match std::fs::OpenOptions::new().read(true).write(true).create(true).open(&path) {
+			Ok(mut file) => drop(file.read_to_string(&mut contents)),
+			Err(err) => println!("[WARN] Failed to open '{}', using the default preferences: {}", path.display(), err)
+		}
+
+		let prefs = Shared::new(match serde_json::from_str::<Preferences>(&contents) {
+			Ok(json) => json,
+			Err(err) => {
+				println!("Error reading toggles plugin config file, resetting to default. {:?}", err);
+				serde_json::from_str("{}").unwrap()
+			}
+		});
+
+		let mut prefs_mut = prefs.borrow_mut();
+		prefs_mut.path = path;
+		if let Err(err) = prefs_mut.save() { println!("[WARN] Failed to save '{}': {}", prefs_mut.path.display(), err); }
+		drop(prefs_mut);
+
+		prefs
+	}
+
+
+	/**
+	 * Returns the configured toggles, followed by the built in ones that they don't replace if they're enabled.
+	 */
+
+	pub fn get_toggles(&self) -> Vec<Toggle> {
+		let builtin = if self.builtin_toggles { toggle::get_builtin() } else { vec![] };
+		self.toggles.iter().cloned()
+			.chain(builtin.into_iter().filter(|builtin| !self.toggles.iter().any(|toggle| toggle.name == builtin.name)))
+			.collect()
+	}
+
+	pub fn save(&self) -> std::io::Result<()> {
+		let mut file = std::fs::OpenOptions::new().read(true).write(true).create(true).open(&self.path)?;
+
+		file.set_len(0)?;
+		file.seek(SeekFrom::Start(0))?;
+		file.write_all(serde_json::to_string(&self)?.as_bytes())?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,210 @@
+use gtk::prelude::*;
+
+use scout_core::{ SearchResult, Shared };
+
+use crate::toggle::{ self, Toggle, Backend, AvailableToggle, ToggleCache };
+
+/** The score of each character of a query that names a toggle. */
+const LETTER_SCORE: usize = 10;
+
+
+/**
+ * A system setting, which is switched to the state the query asked for when activated, or the opposite of its current state.
+ * The subtitle shows the plugin's cached state, and is updated as soon as the setting is switched.
+ * The switch itself runs on another thread, which stores the setting's actual state in the cache when it's done.
+ */
+
+#[derive(Debug, Clone)]
+pub struct ToggleResult {
+	toggle: Toggle,
+	backend: Backend,
+	requested: Option<bool>,
+	state: Shared<Option<bool>>,
+	cache: ToggleCache,
+
+	widget: gtk::Box,
+	top_button: gtk::Button,
+	state_label: gtk::Label,
+
+	score: usize
+}
+
+impl ToggleResult {
+
+	/**
+	 * Formats the subtitle of a setting from its current state.
+	 */
+
+	pub fn get_state_text(state: Option<bool>) -> &'static str {
+		match state {
+			Some(true) => "ON",
+			Some(false) => "OFF",
+			None => "UNKNOWN"
+		}
+	}
+
+
+	/**
+	 * Returns the title of a toggle, which names the state asked for if there is one, like `Turn Wi-Fi Off`.
+	 */
+
+	pub fn get_title_text(toggle: &Toggle, requested: Option<bool>) -> String {
+		match requested {
+			Some(on) => [ "Turn ", &toggle.name, if on { " On" } else { " Off" } ].join(""),
+			None => [ "Toggle ", &toggle.name ].join("")
+		}
+	}
+
+
+	/**
+	 * Switches the setting to the state specified, or the opposite of its current one, and shows the new state.
+	 * The command is run on another thread so that a slow backend doesn't block the window.
+	 */
+
+	fn switch(&self, requested: Option<bool>) {
+		let on = requested.unwrap_or_else(|| !self.state.borrow().unwrap_or(false));
+		*self.state.borrow_mut() = Some(on);
+		self.update_state();
+
+		let (name, backend, cache) = (self.toggle.name.clone(), self.backend.clone(), self.cache.clone());
+		std::thread::spawn(move || {
+			if let Err(err) = backend.set_state(on) {
+				println!("[WARN] Failed to turn {} {}: {}", name, if on { "on" } else { "off" }, err);
+			}
+			toggle::set_cached_state(&cache, &name, backend.get_state());
+		});
+	}
+
+
+	/**
+	 * Updates the state label and the tooltip of the command that activating the result will run.
+	 */
+
+	fn update_state(&self) {
+		let state = *self.state.borrow();
+		self.state_label.set_markup(&[ "<span size='small' weight='bold'>", ToggleResult::get_state_text(state), "</span>" ].join(""));
+		self.top_button.set_tooltip_text(Some(&self.backend.get_command(self.requested.unwrap_or(!state.unwrap_or(false))).join(" ")));
+	}
+
+
+	/**
+	 * Creates a new toggle result for a query, with a corresponding result widget showing the setting's cached state.
+	 */
+
+	pub fn new(available: AvailableToggle, cache: ToggleCache, setting: &str, requested: Option<bool>) -> Self {
+		let AvailableToggle { toggle, backend, state } = available;
+
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget.get_style_context().add_class("Toggle");
+		widget.set_widget_name("SearchResult");
+
+		let top_button = gtk::Button::new();
+		top_button.get_style_context().add_class("flat");
+		widget.pack_start(&top_button, true, true, 0);
+
+		let widget_top = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+		top_button.add(&widget_top);
+
+		let icon = gtk::Image::from_icon_name(Some(&toggle.icon), gtk::IconSize::Dnd);
+		icon.set_size_request(32, 32);
+		icon.set_pixel_size(32);
+		widget_top.pack_start(&icon, false, false, 4);
+
+		let description_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+		widget_top.pack_start(&description_box, true, true, 0);
+
+		let state_label = gtk::Label::new(None);
+		state_label.get_style_context().add_class("Category");
+		state_label.set_ellipsize(pango::EllipsizeMode::End);
+		state_label.set_use_markup(true);
+		state_label.set_xalign(0.0);
+		description_box.pack_start(&state_label, false, false, 1);
+
+		let label = gtk::Label::new(Some(&ToggleResult::get_title_text(&toggle, requested)));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		label.set_xalign(0.0);
+		description_box.pack_start(&label, false, false, 1);
+
+		let result = ToggleResult {
+			toggle, backend, requested,
+			state: Shared::new(state), cache,
+			widget, top_button, state_label,
+			score: setting.chars().count() * LETTER_SCORE
+		};
+		result.update_state();
+
+		let result_clone = result.clone();
+		result.top_button.connect_clicked(move |_| result_clone.switch(result_clone.requested));
+
+		result
+	}
+}
+
+impl SearchResult for ToggleResult {
+	fn get_score(&self) -> usize {
+		self.score
+	}
+
+	fn get_id(&self) -> String {
+		[ "toggle:", &self.toggle.name ].join("")
+	}
+
+	fn get_title(&self) -> String {
+		ToggleResult::get_title_text(&self.toggle, self.requested)
+	}
+
+	fn get_subtitle(&self) -> String {
+		ToggleResult::get_state_text(*self.state.borrow()).to_owned()
+	}
+
+	fn get_icon_name(&self) -> Option<String> {
+		Some(self.toggle.icon.clone())
+	}
+
+	fn set_first(&self, first: bool) -> () {
+		self.top_button.set_can_focus(!first);
+	}
+
+	fn activate(&self) {
+		self.switch(self.requested);
+	}
+
+	fn get_result_widget(&self) -> gtk::Widget {
+		self.widget.clone().upcast()
+	}
+
+	fn get_preview_widget(&self) -> gtk::Widget {
+		let widget = gtk::Box::new(gtk::Orientation::Vertical, 4);
+		widget.get_style_context().add_class("Toggle");
+		widget.set_widget_name("SearchPreview");
+		widget.set_border_width(36);
+
+		let icon = gtk::Image::from_icon_name(Some(&self.toggle.icon), gtk::IconSize::Dialog);
+		icon.set_pixel_size(96);
+		widget.pack_start(&icon, false, false, 0);
+
+		let category_label = gtk::Label::new(Some("<span size='small' weight='bold'>SYSTEM SETTING</span>"));
+		category_label.get_style_context().add_class("Category");
+		category_label.set_use_markup(true);
+		widget.pack_start(&category_label, false, false, 0);
+
+		let label = gtk::Label::new(Some(&self.toggle.name));
+		label.set_ellipsize(pango::EllipsizeMode::End);
+		widget.pack_start(&label, false, false, 4);
+
+		let button_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+		button_box.get_style_context().add_class("ButtonBox");
+		button_box.set_halign(gtk::Align::Center);
+		button_box.set_valign(gtk::Align::End);
+		widget.pack_end(&button_box, false, false, 0);
+
+		for (on, text) in [ (true, "Turn On"), (false, "Turn Off") ].iter().copied() {
+			let button = gtk::Button::with_label(text);
+			let result = self.clone();
+			button.connect_clicked(move |_| result.switch(Some(on)));
+			button_box.pack_start(&button, false, false, 0);
+		}
+
+		return widget.upcast();
+	}
+}
@@ -0,0 +1,279 @@
+/*!
+ * Common system settings that can be switched on and off, like dark mode or Wi-Fi, and the commands that switch them.
+ * Each toggle has a list of backends, and the first one that works on this system is used.
+ * Toggles and their backends are plain data, so more can be added in the plugin's preferences.
+ */
+
+use serde::{ Serialize, Deserialize };
+
+/**
+ * A way of reading and changing a setting, through a command line tool.
+ */
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Backend {
+	/** A GSettings key, with the values it has when the setting is on and off. */
+	GSettings { schema: String, key: String, on: String, off: String },
+	/** A NetworkManager radio, like `wifi`, switched with `nmcli radio`. */
+	NmCli { radio: String },
+	/** A type of radio device, like `bluetooth`, blocked and unblocked with `rfkill`. */
+	Rfkill { device: String },
+	/** Any other commands, with the command that reads the setting and the output it prints when the setting is on and off. */
+	Command { get: Vec<String>, on: Vec<String>, off: Vec<String>, on_output: String, off_output: String }
+}
+
+impl Backend {
+
+	/**
+	 * Returns the program and arguments that read the setting's current value.
+	 */
+
+	fn get_state_command(&self) -> Vec<String> {
+		match self {
+			Backend::GSettings { schema, key, .. } => vec![ "gsettings".to_owned(), "get".to_owned(), schema.clone(), key.clone() ],
+			Backend::NmCli { radio } => vec![ "nmcli".to_owned(), "radio".to_owned(), radio.clone() ],
+			Backend::Rfkill { device } => vec![ "rfkill".to_owned(), "list".to_owned(), device.clone() ],
+			Backend::Command { get, .. } => get.clone()
+		}
+	}
+
+
+	/**
+	 * Returns the program and arguments that switch the setting on or off.
+	 */
+
+	pub fn get_command(&self, on: bool) -> Vec<String> {
+		let args = match self {
+			Backend::GSettings { schema, key, on: on_value, off } => vec![ "gsettings", "set", schema.as_str(), key.as_str(), if on { on_value.as_str() } else { off.as_str() } ],
+			Backend::NmCli { radio } => vec![ "nmcli", "radio", radio.as_str(), if on { "on" } else { "off" } ],
+			Backend::Rfkill { device } => vec![ "rfkill", if on { "unblock" } else { "block" }, device.as_str() ],
+			Backend::Command { on: on_command, off, .. } => return if on { on_command.clone() } else { off.clone() }
+		};
+		args.iter().map(|arg| arg.to_string()).collect()
+	}
+
+
+	/**
+	 * Parses the output of the state command into whether the setting is on, or None if it can't be told.
+	 * Devices are on if any of them isn't soft blocked.
+	 */
+
+	pub fn parse_state(&self, output: &str) -> Option<bool> {
+		match self {
+			Backend::GSettings { on, off, .. } | Backend::Command { on_output: on, off_output: off, .. } => match output.trim() {
+				value if value == on => Some(true),
+				value if value == off => Some(false),
+				_ => None
+			},
+			Backend::NmCli { .. } => match output.trim() {
+				"enabled" => Some(true),
+				"disabled" => Some(false),
+				_ => None
+			},
+			Backend::Rfkill { .. } => {
+				let blocked: Vec<bool> = output.lines().filter_map(|line| line.trim().strip_prefix("Soft blocked:"))
+					.map(|value| value.trim() == "yes").collect();
+				if blocked.is_empty() { None } else { Some(blocked.iter().any(|blocked| !blocked)) }
+			}
+		}
+	}
+
+
+	/**
+	 * Reads the setting's current value, returning None if the command fails or its output can't be parsed.
+	 * A backend is only available on this system if this returns a value.
+	 * This waits for the command to exit, so it should be run off the main thread.
+	 */
+
+	pub fn get_state(&self) -> Option<bool> {
+		let args = self.get_state_command();
+		let output = std::process::Command::new(args.get(0)?).args(&args[1..])
+			.stderr(std::process::Stdio::null()).output().ok()?;
+		if !output.status.success() { return None; }
+		self.parse_state(&String::from_utf8_lossy(&output.stdout))
+	}
+
+
+	/**
+	 * Switches the setting on or off.
+	 * This waits for the command to exit, so it should be run off the main thread.
+	 */
+
+	pub fn set_state(&self, on: bool) -> std::io::Result<()> {
+		let args = self.get_command(on);
+		let program = args.get(0).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "The command is empty"))?;
+		let status = std::process::Command::new(program).args(&args[1..])
+			.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).status()?;
+		if status.success() { Ok(()) }
+		else { Err(std::io::Error::new(std::io::ErrorKind::Other, format!("'{}' exited with {}", args.join(" "), status))) }
+	}
+}
+
+
+fn default_icon() -> String { "preferences-system-symbolic".to_owned() }
+
+/**
+ * A setting that can be switched, with the names it is found by and its backends in order of preference.
+ */
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Toggle {
+	pub name: String,
+
+	#[serde(default)]
+	pub aliases: Vec<String>,
+
+	#[serde(default = "default_icon")]
+	pub icon: String,
+
+	pub backends: Vec<Backend>
+}
+
+impl Toggle {
+	fn new(name: &str, aliases: &[&str], icon: &str, backends: Vec<Backend>) -> Self {
+		Toggle { name: name.to_owned(), aliases: aliases.iter().map(|alias| alias.to_string()).collect(), icon: icon.to_owned(), backends }
+	}
+}
+
+
+/**
+ * A toggle that works on this system, with its first working backend and the state it was last known to be in.
+ */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailableToggle {
+	pub toggle: Toggle,
+	pub backend: Backend,
+	pub state: Option<bool>
+}
+
+
+/**
+ * The available toggles with their last known states, shared with the threads that read and switch them.
+ */
+
+pub type ToggleCache = std::sync::Arc<std::sync::Mutex<Vec<AvailableToggle>>>;
+
+
+/**
+ * Stores the state of a toggle in the cache, after it has been switched or read again.
+ */
+
+pub fn set_cached_state(cache: &ToggleCache, name: &str, state: Option<bool>) {
+	if let Ok(mut available) = cache.lock() {
+		available.iter_mut().filter(|available| available.toggle.name == name).for_each(|available| available.state = state);
+	}
+}
+
+
+/**
+ * Returns the toggles that are built into the plugin.
+ */
+
+pub fn get_builtin() -> Vec<Toggle> {
+	let gsettings = |schema: &str, key: &str, on: &str, off: &str| Backend::GSettings {
+		schema: schema.to_owned(), key: key.to_owned(), on: on.to_owned(), off: off.to_owned() };
+
+	vec![
+		Toggle::new("Dark Mode", &[ "dark theme", "dark style" ], "weather-clear-night-symbolic",
+			vec![ gsettings("org.gnome.desktop.interface", "color-scheme", "'prefer-dark'", "'default'") ]),
+		Toggle::new("Night Light", &[ "night mode", "blue light" ], "night-light-symbolic",
+			vec![ gsettings("org.gnome.settings-daemon.plugins.color", "night-light-enabled", "true", "false") ]),
+		Toggle::new("Wi-Fi", &[ "wifi", "wireless", "wlan" ], "network-wireless-symbolic",
+			vec![ Backend::NmCli { radio: "wifi".to_owned() }, Backend::Rfkill { device: "wlan".to_owned() } ]),
+		Toggle::new("Bluetooth", &[], "bluetooth-symbolic",
+			vec![ Backend::Rfkill { device: "bluetooth".to_owned() } ])
+	]
+}
+
+
+/**
+ * Finds the first working backend of each toggle and its current state, leaving out toggles that have none,
+ * so unsupported settings are hidden. This runs every backend's state command, so it should be run off the main thread.
+ */
+
+pub fn find_available(toggles: &[Toggle]) -> Vec<AvailableToggle> {
+	toggles.iter().filter_map(|toggle| toggle.backends.iter()
+		.find_map(|backend| backend.get_state().map(|state| (backend, state)))
+		.map(|(backend, state)| AvailableToggle { toggle: toggle.clone(), backend: backend.clone(), state: Some(state) })).collect()
+}
+
+
+/**
+ * Splits a query like `wifi off` or `turn on bluetooth` into the setting it names and the state asked for,
+ * which is None if the query doesn't ask for one. The setting is lowercased, without whitespace or dashes.
+ */
+
+pub fn parse_query(query: &str) -> (String, Option<bool>) {
+	let mut state = None;
+	let words: Vec<String> = query.to_lowercase().split_whitespace().filter(|word| match *word {
+		"on" | "enable" => { state = Some(true); false },
+		"off" | "disable" => { state = Some(false); false },
+		"turn" | "toggle" | "switch" => false,
+		_ => true
+	}).map(|word| word.replace('-', "")).collect();
+
+	(words.join(""), state)
+}
+
+
+/**
+ * Returns true if the setting from `parse_query` is the start of the toggle's name or one of its aliases.
+ * At least two characters are needed, so that single letters don't list every toggle.
+ */
+
+pub fn matches(setting: &str, toggle: &Toggle) -> bool {
+	setting.chars().count() >= 2 && std::iter::once(&toggle.name).chain(toggle.aliases.iter())
+		.any(|name| name.to_lowercase().replace(|c: char| c.is_whitespace() || c == '-', "").starts_with(setting))
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_toggle(name: &str) -> Toggle {
+		get_builtin().into_iter().find(|toggle| toggle.name == name).unwrap()
+	}
+
+	#[test]
+	fn verbs_are_parsed_into_the_requested_state() {
+		assert_eq!(parse_query("wifi off"), ("wifi".to_owned(), Some(false)));
+		assert_eq!(parse_query("Turn ON Bluetooth"), ("bluetooth".to_owned(), Some(true)));
+		assert_eq!(parse_query("disable night light"), ("nightlight".to_owned(), Some(false)));
+		assert_eq!(parse_query("toggle wi-fi"), ("wifi".to_owned(), None));
+		assert_eq!(parse_query("dark mode"), ("darkmode".to_owned(), None));
+	}
+
+	#[test]
+	fn settings_match_names_and_aliases() {
+		assert!(matches("wifi", &get_toggle("Wi-Fi")));
+		assert!(matches("wire", &get_toggle("Wi-Fi")));
+		assert!(matches("darkth", &get_toggle("Dark Mode")));
+		assert!(!matches("w", &get_toggle("Wi-Fi")));
+		assert!(!matches("bluetooth", &get_toggle("Wi-Fi")));
+	}
+
+	#[test]
+	fn backend_commands_switch_known_toggles() {
+		assert_eq!(get_toggle("Dark Mode").backends[0].get_command(true),
+			vec![ "gsettings", "set", "org.gnome.desktop.interface", "color-scheme", "'prefer-dark'" ]);
+		assert_eq!(get_toggle("Wi-Fi").backends[0].get_command(false), vec![ "nmcli", "radio", "wifi", "off" ]);
+		assert_eq!(get_toggle("Bluetooth").backends[0].get_command(true), vec![ "rfkill", "unblock", "bluetooth" ]);
+		assert_eq!(get_toggle("Bluetooth").backends[0].get_state_command(), vec![ "rfkill", "list", "bluetooth" ]);
+	}
+
+	#[test]
+	fn backend_states_are_parsed() {
+		assert_eq!(get_toggle("Dark Mode").backends[0].parse_state("'prefer-dark'\n"), Some(true));
+		assert_eq!(get_toggle("Night Light").backends[0].parse_state("false\n"), Some(false));
+		assert_eq!(get_toggle("Wi-Fi").backends[0].parse_state("enabled\n"), Some(true));
+		assert_eq!(get_toggle("Wi-Fi").backends[0].parse_state("unknown\n"), None);
+
+		let rfkill = &get_toggle("Bluetooth").backends[0];
+		assert_eq!(rfkill.parse_state("0: hci0: Bluetooth\n\tSoft blocked: yes\n\tHard blocked: no\n"), Some(false));
+		assert_eq!(rfkill.parse_state("0: hci0: Bluetooth\n\tSoft blocked: yes\n1: hci1: Bluetooth\n\tSoft blocked: no\n"), Some(true));
+		assert_eq!(rfkill.parse_state(""), None);
+	}
+}